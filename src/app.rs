@@ -12,18 +12,54 @@ use crate::util;
 use engine::game::{Game, GameConfig, WordEntry};
 use engine::game::KeyFeedback;
 use engine::romaji::RomajiRules;
-use store::json::{ScoreBook, ScoreRecord};
+use store::json::{KeyEv, ScoreBook, ScoreRecord};
+use store::ScoreStore;
 // no name input
 use util::config::AppConfig;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum Screen { Top, Play, Ranking, Details, Settings, Help }
+pub enum Screen { Top, Play, Ranking, Details, Compare, Stats, Settings, Help, Dictionary, Editor, Profiles }
 
 pub struct App {
     pub screen: Screen,
     pub quit: bool,
     pub game: Option<Game>,
-    pub scorebook: ScoreBook,
+    pub store: Box<dyn ScoreStore>,
+    /// Ranking rows for `ranking_mode` (refreshed from the store on entering Ranking / after a session)
+    pub ranking_top: Vec<ScoreRecord>,
+    pub ranking_lap: Vec<ScoreRecord>,
+    /// Cursor in `ranking_top`
+    pub ranking_sel: usize,
+    pub ranking_input: Option<RankingInput>,
+    /// Last deleted record (U restores it)
+    pub undo_delete: Option<ScoreRecord>,
+    /// Ranking shows the full history of the mode (newest first) instead of the Top-100
+    pub ranking_history: bool,
+    pub ranking_sort: RankingSort,
+    /// Record shown on the details screen (loaded from the store with its replay)
+    pub detail: Option<ScoreRecord>,
+    /// Ids the details screen steps through (the ranking list it was opened from)
+    pub detail_ids: Vec<String>,
+    pub detail_pos: usize,
+    /// Hash / replay check of `detail` against the current rules
+    pub detail_check: Option<engine::verify::Report>,
+    /// Per-mora latency / hesitations of `detail`
+    pub detail_latency: Option<engine::latency::Analysis>,
+    /// Classified misses of `detail` (None when its replay cannot be re-typed)
+    pub detail_misses: Option<Vec<engine::misses::Miss>>,
+    /// What the left analysis panel of the details screen shows ('A' cycles)
+    pub detail_panel: DetailPanel,
+    /// Ranking 'C': id of the record picked as side A of a comparison
+    pub compare_mark: Option<String>,
+    pub compare: Option<CompareState>,
+    /// Progress dashboard of `ranking_mode` (built from the history on opening)
+    pub stats: Option<engine::progress::Progress>,
+    /// Trend charts or the miss report ('M' toggles)
+    pub stats_view: StatsView,
+    /// Miss patterns of `ranking_mode` across the history (built when the miss view is shown)
+    pub stats_misses: Option<engine::misses::Report>,
+    /// Finger usage of `ranking_mode`: whole history and the newest session (built for the finger view)
+    pub stats_hands: Option<(engine::fingers::HandUsage, Option<engine::fingers::HandUsage>)>,
     pub last_result: Option<ScoreRecord>,
     pub cfg: AppConfig,
     #[allow(dead_code)]
@@ -35,6 +71,24 @@ pub struct App {
     pub rules: RomajiRules,
     pub countdown_until: Option<Instant>,
     pub sound: Option<util::sound::SoundPlayer>,
+    /// One-line message shown on the top screen (load errors etc.)
+    pub status: Option<String>,
+    /// What the long-text import left out, shown in the play header
+    pub long_text_note: Option<String>,
+    pub ranking_mode: String,
+    pub dicts: Vec<DictInfo>,
+    pub dict_sel: usize,
+    pub editor: Option<EditorState>,
+    pub watch_words: Option<util::watch::FileWatch>,
+    pub watch_rules: Option<util::watch::FileWatch>,
+    /// Corrupted files found at startup, asked one by one before anything else
+    pub recovery: Vec<util::persist::Recovery>,
+    pub profiles: Vec<String>,
+    pub profile_sel: usize,
+    /// Name being typed for a new profile
+    pub profile_input: Option<String>,
+    /// Office leaderboard for `ranking_mode`: (profile, best run)
+    pub office: Vec<(String, ScoreRecord)>,
 }
 
 #[derive(Clone, Copy)]
@@ -53,28 +107,71 @@ impl Default for Theme {
 }
 
 pub fn run(terminal: &mut Terminal<ratatui::prelude::CrosstermBackend<std::io::Stdout>>) -> Result<()> {
+    // 旧バージョンの ./data を一度だけ新しい保存先へコピー
+    let dirs = util::paths::dirs();
+    let migrated = if dirs.overridden { Vec::new() } else { util::paths::migrate_legacy(std::path::Path::new(util::paths::LEGACY_DIR), dirs)? };
+    // 壊れたファイルは退避して既定値で起動し、バックアップからの復元を提案する
+    let mut recovery = Vec::new();
+    recovery.extend(util::persist::check("設定", &AppConfig::path(), |s| AppConfig::from_json(s).map(|_| ()))?);
+    recovery.extend(util::persist::check("記録", &ScoreBook::path(), |s| ScoreBook::from_json(s).map(|_| ()))?);
     let cfg = AppConfig::load_or_default()?;
-    // Embed dictionary & rules for crates.io install
-    let words: Vec<WordEntry> = engine::game::load_words_from_str(include_str!("../data/words/basic_common.json"))?;
-    let romaji_rules: RomajiRules = engine::romaji::RomajiRules::from_yaml_str(include_str!("../data/rules/romaji.yaml"))?;
-    let scorebook = store::json::ScoreBook::load_or_default()?;
+    let mut status = if migrated.is_empty() { None } else { Some(format!("./{} から {} ファイルを {} へ移行しました", util::paths::LEGACY_DIR, migrated.len(), dirs.data.display())) };
+    let (words, romaji_rules) = load_content(&cfg, &mut status)?;
+    let store = store::open(&cfg.store).or_else(|e| {
+        status = Some(format!("スコア保存先 {} を開けません（JSONを使用）: {}", cfg.store, e));
+        store::open("json")
+    })?;
 
     let mut app = App {
         screen: Screen::Top,
         quit: false,
         game: None,
-        scorebook,
+        store,
+        ranking_top: Vec::new(),
+        ranking_lap: Vec::new(),
+        ranking_sel: 0,
+        ranking_history: false,
+        ranking_sort: RankingSort::Time,
+        detail: None,
+        detail_ids: Vec::new(),
+        detail_pos: 0,
+        detail_check: None,
+        detail_latency: None,
+        detail_misses: None,
+        detail_panel: DetailPanel::Fastest,
+        compare_mark: None,
+        compare: None,
+        stats: None,
+        stats_view: StatsView::Trends,
+        stats_misses: None,
+        stats_hands: None,
+        ranking_input: None,
+        undo_delete: None,
         last_result: None,
         cfg,
         theme: Theme::default(),
         anim_tick: 0,
-        words: words.clone(),
+        words,
         replay: None,
         rec_prompt: None,
         rules: romaji_rules,
         countdown_until: None,
         sound: util::sound::SoundPlayer::new().ok(),
+        status,
+        long_text_note: None,
+        dicts: Vec::new(),
+        dict_sel: 0,
+        editor: None,
+        watch_words: None,
+        watch_rules: None,
+        recovery,
+        profiles: Vec::new(),
+        profile_sel: 0,
+        profile_input: None,
+        office: Vec::new(),
+        ranking_mode: engine::game::MODE_BASIC.into(),
     };
+    reset_watches(&mut app);
 
     let mut last_tick = Instant::now();
     let tick_rate = Duration::from_millis(16);
@@ -84,7 +181,7 @@ pub fn run(terminal: &mut Terminal<ratatui::prelude::CrosstermBackend<std::io::S
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
         if event::poll(timeout)? {
             if let Event::Key(key) = event::read()? {
-                handle_key(&mut app, key, &words)?;
+                handle_key(&mut app, key)?;
             }
         }
         if last_tick.elapsed() >= tick_rate {
@@ -98,25 +195,34 @@ pub fn run(terminal: &mut Terminal<ratatui::prelude::CrosstermBackend<std::io::S
                 }
             }
             if let Some(g) = &mut app.game { g.on_tick(); }
+            // 辞書・ルールの変更はセッションの合間にだけ反映
+            if app.game.is_none() { poll_reload(&mut app); }
             // replay time update
             if matches!(app.screen, Screen::Details) {
-                if let (Some(rep), Some(rec)) = (&mut app.replay, &app.last_result) {
+                if let (Some(rep), Some(rec)) = (&mut app.replay, &app.detail) {
                     if rep.playing {
                         rep.time += tick_rate.as_secs_f64() * rep.speed;
                         if let Some(evs) = &rec.replay {
                             // advance event index based on elapsed time
                             while rep.ev_idx + 1 < evs.len() && evs[rep.ev_idx + 1].t <= rep.time { rep.ev_idx += 1; }
-                            // loop replay when reaching the end
+                            // loop replay when reaching the end (or stop there)
                             if let Some(last) = evs.last() {
                                 if rep.ev_idx + 1 >= evs.len() && rep.time >= last.t {
-                                    rep.time = 0.0;
-                                    rep.ev_idx = 0;
+                                    if app.cfg.replay_loop { rep.time = 0.0; rep.ev_idx = 0; } else { rep.time = last.t; rep.playing = false; }
                                 }
                             }
                         }
                     }
                 }
             }
+            if let (Screen::Compare, Some(c)) = (app.screen, &mut app.compare) {
+                if c.replay.playing {
+                    c.replay.time += tick_rate.as_secs_f64() * c.replay.speed;
+                    if c.replay.time >= c.end() {
+                        if app.cfg.replay_loop { c.replay.time = 0.0; } else { c.replay.time = c.end(); c.replay.playing = false; }
+                    }
+                }
+            }
             app.anim_tick = app.anim_tick.wrapping_add(1);
             last_tick = Instant::now();
         }
@@ -125,7 +231,8 @@ pub fn run(terminal: &mut Terminal<ratatui::prelude::CrosstermBackend<std::io::S
     Ok(())
 }
 
-fn handle_key(app: &mut App, key: KeyEvent, words: &Vec<WordEntry>) -> Result<()> {
+fn handle_key(app: &mut App, key: KeyEvent) -> Result<()> {
+    if !app.recovery.is_empty() { return handle_recovery_key(app, key); }
     match app.screen {
         Screen::Top => {
             match key.code {
@@ -138,20 +245,24 @@ fn handle_key(app: &mut App, key: KeyEvent, words: &Vec<WordEntry>) -> Result<()
                     gc.target_chars = app.cfg.target_chars as usize;
                     gc.time_limit_sec = f64::INFINITY;
                     gc.max_words = usize::MAX; // 固定文字数モードでは周回できるよう制限なし
-                    let mut g = Game::new_with_rules(gc, words.clone(), app.rules.clone())?;
-                    g.start();
-                    app.game = Some(g);
-                    app.screen = Screen::Play;
-                    // カウントダウン開始（0秒なら即開始）
-                    if app.cfg.countdown_sec > 0 {
-                        app.countdown_until = Some(Instant::now() + Duration::from_secs(app.cfg.countdown_sec));
-                    } else {
-                        app.countdown_until = None;
-                        if let Some(g) = &mut app.game { g.begin_now(); }
-                    }
+                    gc.session = app.cfg.session.clone();
+                    let words = if gc.session.grade.is_some() {
+                        // 難度は自分の過去の語ごとの速さも加味
+                        let hist = engine::difficulty::WordTimes::from_records(app.store.history()?.iter(), &app.words);
+                        engine::difficulty::with_history(&app.words, &hist)
+                    } else { app.words.clone() };
+                    start_game(app, gc, words)?;
+                }
+                KeyCode::Char('p') | KeyCode::Char('P') => open_profiles(app),
+                KeyCode::Char('d') => {
+                    app.dicts = list_dictionaries();
+                    app.dict_sel = app.dicts.iter().position(|d| d.path == app.cfg.dictionary).unwrap_or(0);
+                    app.screen = Screen::Dictionary;
                 }
-                KeyCode::Char('r') => app.screen = Screen::Ranking,
+                KeyCode::Char('l') => start_long_text(app)?,
+                KeyCode::Char('r') => open_ranking(app)?,
                 KeyCode::Char('s') => app.screen = Screen::Settings,
+                KeyCode::Char('t') => open_stats(app)?,
                 _ => {}
             }
         }
@@ -168,16 +279,7 @@ fn handle_key(app: &mut App, key: KeyEvent, words: &Vec<WordEntry>) -> Result<()
                 if app.countdown_until.is_some() {
                     if matches!(key.code, KeyCode::Esc) {
                         let finished = g.handle_key(key)?;
-                        if finished {
-                            if g.aborted() {
-                                // 中断：保存しないでトップへ
-                                app.game = None;
-                                app.last_result = None;
-                                app.rec_prompt = None;
-                                app.screen = Screen::Top;
-                                app.countdown_until = None;
-                            }
-                        }
+                        if finished && g.aborted() { abort_session(app); }
                     }
                     return Ok(());
                 }
@@ -199,15 +301,23 @@ fn handle_key(app: &mut App, key: KeyEvent, words: &Vec<WordEntry>) -> Result<()
                 }
                 if finished {
                     if g.aborted() {
-                        // 中断：保存しないでトップへ
+                        abort_session(app);
+                    } else if g.mode() == engine::game::MODE_TRY {
+                        // 試し打ち: 記録せずエディタへ戻る
+                        let rec = g.finish_record();
+                        if let Some(ed) = &mut app.editor {
+                            ed.message = Some(format!("試し打ち: {:.3}秒  ミス {}", rec.time_sec, rec.miss));
+                        }
                         app.game = None;
-                        app.last_result = None;
-                        app.rec_prompt = None;
-                        app.screen = Screen::Top;
-                        app.countdown_until = None;
+                        app.screen = Screen::Editor;
                     } else {
                         let record = g.finish_record();
-                        let (rank_in, is_new) = app.scorebook.insert_and_rank(record.clone());
+                        if record.mode == engine::game::MODE_LONG_TEXT {
+                            // 栞を進める（次回は続きの文から）
+                            app.cfg.long_text_pos = app.cfg.long_text_pos.saturating_add(g.words_len() as u32);
+                            app.cfg.save()?;
+                        }
+                        let (rank_in, is_new) = app.store.insert_and_rank(record.clone())?;
                         app.last_result = Some(record);
                         app.rec_prompt = Some(RecordPrompt { is_new, rank_in_top: rank_in });
                         // 画面はPlayのまま。オーバーレイを表示。
@@ -216,12 +326,82 @@ fn handle_key(app: &mut App, key: KeyEvent, words: &Vec<WordEntry>) -> Result<()
             }
         }
         Screen::Ranking => {
+            // messages stay in the footer until the next key
+            app.status = None;
+            if app.ranking_input.is_some() { return handle_ranking_input(app, key); }
             match key.code {
                 KeyCode::Esc => app.screen = Screen::Top,
-                KeyCode::Enter => {
-                    app.screen = Screen::Details;
-                    // Reset replay every time we open details to avoid stale progress
-                    app.replay = Some(ReplayState::default());
+                KeyCode::Up => app.ranking_sel = app.ranking_sel.saturating_sub(1),
+                KeyCode::Down => app.ranking_sel = (app.ranking_sel + 1).min(app.ranking_top.len().saturating_sub(1)),
+                KeyCode::PageUp => app.ranking_sel = app.ranking_sel.saturating_sub(10),
+                KeyCode::PageDown => app.ranking_sel = (app.ranking_sel + 10).min(app.ranking_top.len().saturating_sub(1)),
+                KeyCode::Home => app.ranking_sel = 0,
+                KeyCode::End => app.ranking_sel = app.ranking_top.len().saturating_sub(1),
+                KeyCode::Char('e') | KeyCode::Char('E') => {
+                    if let Some(r) = app.ranking_top.get(app.ranking_sel) { app.ranking_input = Some(RankingInput::Memo(r.memo.clone().unwrap_or_default())); }
+                }
+                KeyCode::Char('p') | KeyCode::Char('P') => {
+                    if let Some(r) = app.ranking_top.get(app.ranking_sel) {
+                        let (id, pin) = (r.id.clone(), !r.pinned);
+                        app.store.set_pinned(&id, pin)?;
+                        app.store.save()?;
+                        refresh_ranking(app)?;
+                        select_ranking_id(app, &id);
+                        app.status = Some(if pin { "ピン留めしました（Top-100から外れても残ります）".into() } else { "ピン留めを外しました".into() });
+                    }
+                }
+                KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete => {
+                    match app.ranking_top.get(app.ranking_sel) {
+                        Some(r) if r.pinned => app.status = Some("ピン留め中の記録は削除できません（P で解除）".into()),
+                        Some(_) => app.ranking_input = Some(RankingInput::ConfirmDelete),
+                        None => {}
+                    }
+                }
+                KeyCode::Char('u') | KeyCode::Char('U') => {
+                    if let Some(rec) = app.undo_delete.take() {
+                        let id = rec.id.clone();
+                        app.store.import(vec![rec])?;
+                        app.store.save()?;
+                        refresh_ranking(app)?;
+                        select_ranking_id(app, &id);
+                        app.status = Some("削除を取り消しました".into());
+                    }
+                }
+                KeyCode::Tab => {
+                    app.ranking_mode = if app.ranking_mode == engine::game::MODE_BASIC { engine::game::MODE_LONG_TEXT.into() } else { engine::game::MODE_BASIC.into() };
+                    refresh_ranking(app)?;
+                }
+                KeyCode::Char('x') | KeyCode::Char('X') => {
+                    app.status = Some(match export_from_ranking(app) {
+                        Ok(paths) => format!("書き出しました: {}", paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")),
+                        Err(e) => format!("書き出しに失敗しました: {}", e),
+                    });
+                }
+                KeyCode::Char('h') | KeyCode::Char('H') => {
+                    app.ranking_history = !app.ranking_history;
+                    app.ranking_sel = 0;
+                    refresh_ranking(app)?;
+                }
+                KeyCode::Char('s') | KeyCode::Char('S') => {
+                    app.ranking_sort = app.ranking_sort.next();
+                    app.ranking_sel = 0;
+                    refresh_ranking(app)?;
+                }
+                KeyCode::Char('c') | KeyCode::Char('C') => {
+                    if let Some(id) = app.ranking_top.get(app.ranking_sel).map(|r| r.id.clone()) {
+                        match app.compare_mark.take() {
+                            Some(a) if a != id => open_compare(app, &a, &id)?,
+                            Some(_) => app.status = Some("比較元の選択を解除しました".into()),
+                            None => {
+                                app.compare_mark = Some(id);
+                                app.status = Some("比較元（A）に設定しました。比べる記録を選んで C".into());
+                            }
+                        }
+                    }
+                }
+                KeyCode::Enter if !app.ranking_top.is_empty() => {
+                    app.detail_ids = app.ranking_top.iter().map(|r| r.id.clone()).collect();
+                    open_detail(app, app.ranking_sel)?;
                 },
                 KeyCode::Char('m') | KeyCode::Char('M') => { app.cfg.sound_enabled = !app.cfg.sound_enabled; app.cfg.save()?; }
                 KeyCode::Char('o') | KeyCode::Char('O') => {
@@ -233,10 +413,21 @@ fn handle_key(app: &mut App, key: KeyEvent, words: &Vec<WordEntry>) -> Result<()
             }
         }
         Screen::Details => {
+            app.status = None;
+            if app.replay.as_ref().is_some_and(|r| r.goto.is_some()) { return handle_goto_input(app, key); }
+            let evs: &[KeyEv] = app.detail.as_ref().and_then(|r| r.replay.as_deref()).unwrap_or(&[]);
             match key.code {
                 KeyCode::Esc => app.screen = Screen::Top,
-                KeyCode::Char('r') => app.screen = Screen::Ranking,
-                KeyCode::Char(' ') => { if let Some(rep)=&mut app.replay { rep.playing = !rep.playing; } },
+                KeyCode::Char('r') => { app.ranking_sel = app.detail_pos; open_ranking(app)?; }
+                KeyCode::Up | KeyCode::PageUp if app.detail_pos > 0 => open_detail(app, app.detail_pos - 1)?,
+                KeyCode::Down | KeyCode::PageDown if app.detail_pos + 1 < app.detail_ids.len() => open_detail(app, app.detail_pos + 1)?,
+                KeyCode::Char(' ') => {
+                    if let Some(rep) = &mut app.replay {
+                        // 末尾で止まっていれば最初から
+                        if !rep.playing && !evs.is_empty() && rep.ev_idx + 1 >= evs.len() { rep.ev_idx = 0; rep.time = 0.0; }
+                        rep.playing = !rep.playing;
+                    }
+                },
                 KeyCode::Char('s') | KeyCode::Char('S') => {
                     if let Some(rep) = &mut app.replay {
                         rep.ev_idx = 0;
@@ -244,40 +435,102 @@ fn handle_key(app: &mut App, key: KeyEvent, words: &Vec<WordEntry>) -> Result<()
                         rep.playing = true;
                     }
                 },
-                KeyCode::Left => {
-                    if let (Some(rep), Some(rec)) = (&mut app.replay, &app.last_result) {
-                        if let Some(evs) = &rec.replay {
-                            // Move one event back and sync time to the new event
-                            let new_idx = rep.ev_idx.saturating_sub(1).min(evs.len().saturating_sub(1));
-                            rep.ev_idx = new_idx;
-                            rep.time = if evs.is_empty() { 0.0 } else { evs[new_idx].t };
-                            rep.playing = false;
-                        } else {
-                            rep.ev_idx = rep.ev_idx.saturating_sub(1);
-                            rep.playing = false;
-                        }
-                    }
+                // Move one event back / forward and sync time to the new event
+                KeyCode::Left => { if let Some(rep) = &mut app.replay { rep.seek_event(evs, rep.ev_idx.saturating_sub(1)); } },
+                KeyCode::Right => { if let Some(rep) = &mut app.replay { rep.seek_event(evs, rep.ev_idx.saturating_add(1)); } },
+                KeyCode::Home => { if let Some(rep) = &mut app.replay { rep.seek_time(evs, 0.0); } },
+                KeyCode::End => { if let Some(rep) = &mut app.replay { rep.seek_event(evs, usize::MAX); } },
+                KeyCode::Char(',') | KeyCode::Char('.') => {
+                    if let Some(rep) = &mut app.replay { rep.seek_time(evs, rep.time + if key.code == KeyCode::Char(',') { -1.0 } else { 1.0 }); }
                 },
-                KeyCode::Right => {
-                    if let (Some(rep), Some(rec)) = (&mut app.replay, &app.last_result) {
-                        if let Some(evs) = &rec.replay {
-                            // Move one event forward (bounded) and sync time to the new event
-                            let mut new_idx = rep.ev_idx.saturating_add(1);
-                            if !evs.is_empty() { new_idx = new_idx.min(evs.len()-1); }
-                            rep.ev_idx = new_idx;
-                            rep.time = if evs.is_empty() { 0.0 } else { evs[new_idx].t };
-                            rep.playing = false;
-                        } else {
-                            rep.ev_idx = rep.ev_idx.saturating_add(1);
-                            rep.playing = false;
+                KeyCode::Char('[') | KeyCode::Char(']') | KeyCode::Char('n') | KeyCode::Char('N') => {
+                    if let Some(rep) = &mut app.replay {
+                        let to = match key.code {
+                            KeyCode::Char('[') => store::replay::prev_word(evs, rep.ev_idx),
+                            KeyCode::Char(']') => store::replay::next_word(evs, rep.ev_idx),
+                            KeyCode::Char('n') => store::replay::next_miss(evs, rep.ev_idx),
+                            _ => store::replay::prev_miss(evs, rep.ev_idx),
+                        };
+                        match to {
+                            Some(i) => rep.seek_event(evs, i),
+                            None => app.status = Some(if matches!(key.code, KeyCode::Char('n') | KeyCode::Char('N')) { "この先（前）にミスはありません".into() } else { "これ以上移動できません".into() }),
                         }
                     }
                 },
+                KeyCode::Char('g') | KeyCode::Char('G') => { if let Some(rep) = &mut app.replay { rep.goto = Some(String::new()); } },
+                KeyCode::Char('a') | KeyCode::Char('A') => app.detail_panel = app.detail_panel.next(),
+                KeyCode::Char('l') | KeyCode::Char('L') => {
+                    app.cfg.replay_loop = !app.cfg.replay_loop;
+                    app.cfg.save()?;
+                    app.status = Some(if app.cfg.replay_loop { "ループ再生: オン".into() } else { "ループ再生: オフ（末尾で停止）".into() });
+                },
+                KeyCode::Char('c') | KeyCode::Char('C') => {
+                    if let Some(rec) = &app.detail {
+                        app.status = Some(match ui::cast::write(&store::export::export_dir(), rec, &app.rules, app.cfg.stage_w, app.cfg.stage_h) {
+                            Ok(path) => format!("asciinema 形式で書き出しました: {}", path.display()),
+                            Err(e) => format!("書き出せません: {}", e),
+                        });
+                    }
+                }
+                KeyCode::Char('w') | KeyCode::Char('W') => {
+                    if let Some(rec) = &app.detail {
+                        let path = store::export::export_dir().join(store::replay::file_name(rec));
+                        app.status = Some(match store::replay::ReplayFile::from_record(rec, &app.rules.source).and_then(|f| f.write(&path)) {
+                            Ok(()) => format!("リプレイを書き出しました: {}", path.display()),
+                            Err(e) => format!("リプレイを書き出せません: {}", e),
+                        });
+                    }
+                }
                 KeyCode::Char('+') => { if let Some(rep)=&mut app.replay { rep.speed = (rep.speed*1.25).min(4.0);} },
                 KeyCode::Char('-') => { if let Some(rep)=&mut app.replay { rep.speed = (rep.speed/1.25).max(0.25);} },
                 _ => {}
             }
         }
+        Screen::Compare => {
+            let Some(c) = &mut app.compare else { app.screen = Screen::Ranking; return Ok(()) };
+            let end = c.end();
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('r') | KeyCode::Char('R') => { app.compare = None; app.screen = Screen::Ranking; }
+                KeyCode::Up => c.scroll = c.scroll.saturating_sub(1),
+                KeyCode::Down => c.scroll = (c.scroll + 1).min(c.cmp.rows.len().saturating_sub(1)),
+                KeyCode::PageUp => c.scroll = c.scroll.saturating_sub(10),
+                KeyCode::PageDown => c.scroll = (c.scroll + 10).min(c.cmp.rows.len().saturating_sub(1)),
+                KeyCode::Char(' ') => {
+                    if !c.replay.playing && c.replay.time >= end { c.replay.time = 0.0; }
+                    c.replay.playing = !c.replay.playing;
+                }
+                KeyCode::Char('s') | KeyCode::Char('S') => { c.replay.time = 0.0; c.replay.playing = true; }
+                KeyCode::Left => { c.replay.time = (c.replay.time - 1.0).max(0.0); c.replay.playing = false; }
+                KeyCode::Right => { c.replay.time = (c.replay.time + 1.0).min(end); c.replay.playing = false; }
+                KeyCode::Home => { c.replay.time = 0.0; c.replay.playing = false; }
+                KeyCode::End => { c.replay.time = end; c.replay.playing = false; }
+                KeyCode::Char('+') => c.replay.speed = (c.replay.speed * 1.25).min(4.0),
+                KeyCode::Char('-') => c.replay.speed = (c.replay.speed / 1.25).max(0.25),
+                KeyCode::Char('x') | KeyCode::Char('X') => {
+                    std::mem::swap(&mut c.a, &mut c.b);
+                    c.cmp = engine::compare::compare(&c.a, &c.b);
+                }
+                _ => {}
+            }
+        }
+        Screen::Stats => {
+            match key.code {
+                KeyCode::Esc => { app.stats = None; app.stats_misses = None; app.stats_hands = None; app.screen = Screen::Top; }
+                KeyCode::Tab => {
+                    app.ranking_mode = if app.ranking_mode == engine::game::MODE_BASIC { engine::game::MODE_LONG_TEXT.into() } else { engine::game::MODE_BASIC.into() };
+                    open_stats(app)?;
+                }
+                KeyCode::Char('m') | KeyCode::Char('M') => {
+                    app.stats_view = if app.stats_view == StatsView::Misses { StatsView::Trends } else { StatsView::Misses };
+                    open_stats(app)?;
+                }
+                KeyCode::Char('f') | KeyCode::Char('F') => {
+                    app.stats_view = if app.stats_view == StatsView::Fingers { StatsView::Trends } else { StatsView::Fingers };
+                    open_stats(app)?;
+                }
+                _ => {}
+            }
+        }
         Screen::Settings => {
             match key.code {
                 KeyCode::Esc => app.screen = Screen::Top,
@@ -293,21 +546,446 @@ fn handle_key(app: &mut App, key: KeyEvent, words: &Vec<WordEntry>) -> Result<()
                 KeyCode::Char('c') | KeyCode::Char('C') => { app.cfg.countdown_sec = (app.cfg.countdown_sec + 1).min(10); app.cfg.save()?; }
                 KeyCode::Char('x') | KeyCode::Char('X') => { app.cfg.countdown_sec = app.cfg.countdown_sec.saturating_sub(1).min(10); app.cfg.save()?; }
                 KeyCode::Char('m') | KeyCode::Char('M') => { app.cfg.sound_enabled = !app.cfg.sound_enabled; app.cfg.save()?; }
+                KeyCode::Char('a') | KeyCode::Char('A') => { app.cfg.keep_aborted = !app.cfg.keep_aborted; app.cfg.save()?; }
+                KeyCode::Char('l') | KeyCode::Char('L') => { app.cfg.replay_loop = !app.cfg.replay_loop; app.cfg.save()?; }
+                KeyCode::Char('k') | KeyCode::Char('K') => { app.cfg.keyboard_layout = app.cfg.keyboard_layout.next(); app.cfg.save()?; }
+                // 出題条件
+                KeyCode::Char('w') | KeyCode::Char('W') => { app.cfg.session.weighted = !app.cfg.session.weighted; app.cfg.save()?; }
+                KeyCode::Char('t') | KeyCode::Char('T') => { app.cfg.session.include_tags = next_tag(&app.cfg.session.include_tags, &word_tags(&app.words)); app.cfg.save()?; }
+                KeyCode::Char('e') | KeyCode::Char('E') => { app.cfg.session.exclude_tags = next_tag(&app.cfg.session.exclude_tags, &word_tags(&app.words)); app.cfg.save()?; }
+                KeyCode::Char('1') => { app.cfg.session.min_keys = step_opt(app.cfg.session.min_keys, -1); app.cfg.save()?; }
+                KeyCode::Char('2') => { app.cfg.session.min_keys = step_opt(app.cfg.session.min_keys, 1); app.cfg.save()?; }
+                KeyCode::Char('3') => { app.cfg.session.max_keys = step_opt(app.cfg.session.max_keys, -1); app.cfg.save()?; }
+                KeyCode::Char('4') => { app.cfg.session.max_keys = step_opt(app.cfg.session.max_keys, 1); app.cfg.save()?; }
+                KeyCode::Char('5') => { app.cfg.session.no_repeat_within = app.cfg.session.no_repeat_within.saturating_sub(1); app.cfg.save()?; }
+                KeyCode::Char('6') => { app.cfg.session.no_repeat_within = (app.cfg.session.no_repeat_within + 1).min(100); app.cfg.save()?; }
                 _ => {}
             }
         }
         Screen::Help => { if key.code == KeyCode::Esc { app.screen = Screen::Top; } }
+        Screen::Profiles => {
+            if let Some(name) = &mut app.profile_input {
+                match key.code {
+                    KeyCode::Esc => app.profile_input = None,
+                    KeyCode::Backspace => { name.pop(); }
+                    KeyCode::Char(c) => name.push(c),
+                    KeyCode::Enter => {
+                        let name = name.trim().to_string();
+                        match util::paths::create_profile(&name) {
+                            Ok(()) => { app.profile_input = None; switch_profile(app, &name)?; open_profiles(app); }
+                            Err(e) => app.status = Some(e.to_string()),
+                        }
+                    }
+                    _ => {}
+                }
+                return Ok(());
+            }
+            match key.code {
+                KeyCode::Esc => app.screen = Screen::Top,
+                KeyCode::Up => app.profile_sel = app.profile_sel.saturating_sub(1),
+                KeyCode::Down => app.profile_sel = (app.profile_sel + 1).min(app.profiles.len().saturating_sub(1)),
+                KeyCode::Enter => {
+                    if let Some(name) = app.profiles.get(app.profile_sel).cloned() {
+                        if name != util::paths::profile() { switch_profile(app, &name)?; }
+                        app.screen = Screen::Top;
+                    }
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') => app.profile_input = Some(String::new()),
+                KeyCode::Tab => {
+                    app.ranking_mode = if app.ranking_mode == engine::game::MODE_BASIC { engine::game::MODE_LONG_TEXT.into() } else { engine::game::MODE_BASIC.into() };
+                    app.office = store::office_leaderboard(&app.ranking_mode);
+                }
+                _ => {}
+            }
+        }
+        Screen::Dictionary => {
+            match key.code {
+                KeyCode::Esc => app.screen = Screen::Top,
+                KeyCode::Up => app.dict_sel = app.dict_sel.saturating_sub(1),
+                KeyCode::Down => app.dict_sel = (app.dict_sel + 1).min(app.dicts.len().saturating_sub(1)),
+                KeyCode::Enter => {
+                    if let Some(d) = app.dicts.get(app.dict_sel) {
+                        if d.error.is_none() {
+                            app.words = d.words.clone();
+                            app.cfg.dictionary = d.path.clone();
+                            app.cfg.save()?;
+                            app.status = Some(format!("辞書: {}（{} 語）", d.title, d.words.len()));
+                            app.screen = Screen::Top;
+                            reset_watches(app);
+                        }
+                    }
+                }
+                KeyCode::Char('e') | KeyCode::Char('E') => {
+                    if let Some(d) = app.dicts.get(app.dict_sel) {
+                        // 内蔵辞書はユーザー辞書にコピーしてから編集
+                        let path = d.path.clone().unwrap_or_else(|| util::paths::words_dir().join("user.json").to_string_lossy().into_owned());
+                        let opened = if d.path.is_none() && !std::path::Path::new(&path).exists() {
+                            let wf = engine::game::WordsFile { title: "ユーザー辞書".into(), version: 1, entries: d.words.clone() };
+                            let mut ed = EditorState::new(path, wf);
+                            ed.dirty = true;
+                            Ok(ed)
+                        } else { EditorState::open(&path) };
+                        match opened {
+                            Ok(ed) => { app.editor = Some(ed); app.screen = Screen::Editor; }
+                            Err(e) => app.status = Some(format!("辞書を開けません: {e}")),
+                        }
+                    }
+                }
+                KeyCode::Char('d') | KeyCode::Char('D') => {
+                    use engine::difficulty::Grade::*;
+                    app.cfg.session.grade = match app.cfg.session.grade { None => Some(Easy), Some(Easy) => Some(Normal), Some(Normal) => Some(Hard), Some(Hard) => None };
+                    app.cfg.save()?;
+                }
+                _ => {}
+            }
+        }
+        Screen::Editor => handle_editor_key(app, key)?,
+    }
+    Ok(())
+}
+
+fn handle_editor_key(app: &mut App, key: KeyEvent) -> Result<()> {
+    let Some(ed) = &mut app.editor else { app.screen = Screen::Dictionary; return Ok(()); };
+    if !matches!(key.code, KeyCode::Esc) { ed.confirm_discard = false; }
+    match ed.mode {
+        EditorMode::Search => match key.code {
+            KeyCode::Esc | KeyCode::Enter => ed.mode = EditorMode::Browse,
+            KeyCode::Backspace => { ed.query.pop(); ed.sel = 0; }
+            KeyCode::Char(c) => { ed.query.push(c); ed.sel = 0; }
+            _ => {}
+        },
+        EditorMode::Edit => match key.code {
+            KeyCode::Esc => { ed.mode = EditorMode::Browse; ed.message = None; }
+            KeyCode::Tab | KeyCode::Down => ed.field = (ed.field + 1) % 3,
+            KeyCode::BackTab | KeyCode::Up => ed.field = (ed.field + 2) % 3,
+            KeyCode::Backspace => { ed.form[ed.field].pop(); }
+            KeyCode::Char(c) => ed.form[ed.field].push(c),
+            KeyCode::Enter => {
+                match ed.form_error(&app.rules) {
+                    Some(e) => ed.message = Some(e),
+                    None => { ed.commit_form(); ed.message = None; }
+                }
+            }
+            _ => {}
+        },
+        EditorMode::ConfirmDelete => {
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                if let Some(i) = ed.selected() {
+                    let e = ed.file.entries.remove(i);
+                    ed.dirty = true;
+                    ed.message = Some(format!("削除: {}", e.jp));
+                    ed.sel = ed.sel.min(ed.visible().len().saturating_sub(1));
+                }
+            }
+            ed.mode = EditorMode::Browse;
+        }
+        EditorMode::Browse => match key.code {
+            KeyCode::Esc => {
+                if ed.dirty && !ed.confirm_discard {
+                    ed.confirm_discard = true;
+                    ed.message = Some("未保存の変更があります。もう一度 ESC で破棄".into());
+                } else {
+                    app.editor = None;
+                    app.dicts = list_dictionaries();
+                    app.screen = Screen::Dictionary;
+                }
+            }
+            KeyCode::Up => ed.sel = ed.sel.saturating_sub(1),
+            KeyCode::Down => ed.sel = (ed.sel + 1).min(ed.visible().len().saturating_sub(1)),
+            KeyCode::PageUp => ed.sel = ed.sel.saturating_sub(10),
+            KeyCode::PageDown => ed.sel = (ed.sel + 10).min(ed.visible().len().saturating_sub(1)),
+            KeyCode::Char('/') => ed.mode = EditorMode::Search,
+            KeyCode::Char('a') => ed.begin_edit(None),
+            KeyCode::Char('e') | KeyCode::Enter => { if let Some(i) = ed.selected() { ed.begin_edit(Some(i)); } }
+            KeyCode::Char('x') | KeyCode::Delete => { if ed.selected().is_some() { ed.mode = EditorMode::ConfirmDelete; } }
+            KeyCode::Char('s') => {
+                match ed.save() {
+                    Ok(()) => {
+                        ed.message = Some(format!("保存しました: {}", ed.path));
+                        if app.cfg.dictionary.as_deref() == Some(ed.path.as_str()) { app.words = ed.file.entries.clone(); }
+                    }
+                    Err(e) => ed.message = Some(format!("保存に失敗: {e}")),
+                }
+            }
+            KeyCode::Char('t') => {
+                if let Some(i) = ed.selected() {
+                    let entry = ed.file.entries[i].clone();
+                    let gc = GameConfig {
+                        time_limit_sec: f64::INFINITY,
+                        max_words: 1,
+                        loss_ms_per_miss: app.cfg.loss_ms_per_miss,
+                        fixed_chars: false,
+                        target_chars: 0,
+                        mode: engine::game::MODE_TRY.into(),
+                        session: Default::default(),
+                    };
+                    start_game(app, gc, vec![entry])?;
+                    // 試し打ちはカウントダウンなし（最初の打鍵で計測開始）
+                    app.countdown_until = None;
+                }
+            }
+            _ => {}
+        },
     }
     Ok(())
 }
 
+// All tags used in the dictionary (sorted, unique)
+pub fn word_tags(words: &[WordEntry]) -> Vec<String> {
+    let mut tags: Vec<String> = words.iter().flat_map(|w| w.tags.iter().cloned()).collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+// Cycle a single-tag selection: none -> tag1 -> tag2 -> ... -> none
+fn next_tag(cur: &[String], tags: &[String]) -> Vec<String> {
+    let pos = cur.first().and_then(|c| tags.iter().position(|t| t == c));
+    match pos {
+        None => tags.first().cloned().into_iter().collect(),
+        Some(i) => tags.get(i + 1).cloned().into_iter().collect(),
+    }
+}
+
+// Step an optional bound; stepping below 1 clears it
+fn step_opt(v: Option<u32>, d: i32) -> Option<u32> {
+    let n = v.map(|x| x as i32).unwrap_or(0) + d;
+    if n <= 0 { None } else { Some((n as u32).min(60)) }
+}
+
+/// Dictionary and romaji rules named in the config, falling back to the built-ins
+/// (the reason is left in `status`).
+fn load_content(cfg: &AppConfig, status: &mut Option<String>) -> Result<(Vec<WordEntry>, RomajiRules)> {
+    // Embed dictionary & rules for crates.io install
+    let builtin: Vec<WordEntry> = engine::game::load_words_from_str(BUILTIN_WORDS)?;
+    let words = match &cfg.dictionary {
+        Some(p) => engine::game::load_words_json(std::path::Path::new(p)).unwrap_or_else(|e| {
+            *status = Some(format!("辞書 {} を読み込めません（内蔵辞書を使用）: {}", p, e));
+            builtin.clone()
+        }),
+        None => builtin,
+    };
+    let builtin_rules: RomajiRules = engine::romaji::RomajiRules::from_yaml_str(BUILTIN_RULES)?;
+    let rules = match &cfg.rules_path {
+        Some(p) => load_rules(std::path::Path::new(p)).unwrap_or_else(|e| {
+            *status = Some(format!("ルール {} を読み込めません（内蔵ルールを使用）: {}", p, e));
+            builtin_rules
+        }),
+        None => builtin_rules,
+    };
+    Ok((words, rules))
+}
+
+/// Save the current profile, then load config, scores, dictionary and rules of `name`.
+fn switch_profile(app: &mut App, name: &str) -> Result<()> {
+    app.store.save()?;
+    app.cfg.save()?;
+    util::paths::set_profile(name);
+    util::paths::save_active_profile()?;
+    app.cfg = AppConfig::load_or_default()?;
+    let mut status = Some(format!("プロファイル「{}」に切り替えました", name));
+    let (words, rules) = load_content(&app.cfg, &mut status)?;
+    app.words = words;
+    app.rules = rules;
+    app.store = store::open(&app.cfg.store).or_else(|e| {
+        status = Some(format!("スコア保存先 {} を開けません（JSONを使用）: {}", app.cfg.store, e));
+        store::open("json")
+    })?;
+    app.last_result = None;
+    app.detail = None;
+    app.detail_ids.clear();
+    app.compare = None;
+    app.compare_mark = None;
+    app.stats = None;
+    app.status = status;
+    reset_watches(app);
+    Ok(())
+}
+
+fn open_profiles(app: &mut App) {
+    app.profiles = util::paths::profiles();
+    let cur = util::paths::profile();
+    app.profile_sel = app.profiles.iter().position(|p| *p == cur).unwrap_or(0);
+    app.office = store::office_leaderboard(&app.ranking_mode);
+    app.screen = Screen::Profiles;
+}
+
+/// Load and validate a words file for hot-reload.
+pub fn load_words_checked(path: &std::path::Path) -> Result<Vec<WordEntry>> {
+    let words = engine::game::load_words_json(path)?;
+    if words.is_empty() { anyhow::bail!("語がありません"); }
+    if let Some((i, w)) = words.iter().enumerate().find(|(_, w)| w.jp.trim().is_empty() || w.romas.iter().all(|r| r.is_empty())) {
+        anyhow::bail!("{}番目の語（{}）に日本語またはローマ字がありません", i + 1, w.jp);
+    }
+    Ok(words)
+}
+
+/// Romaji rules of `cfg` without touching the UI (built-in ones if the file is unusable).
+pub fn rules_for(cfg: &AppConfig) -> Result<RomajiRules> {
+    match cfg.rules_path.as_deref().map(|p| load_rules(std::path::Path::new(p))) {
+        Some(Ok(r)) => Ok(r),
+        _ => RomajiRules::from_yaml_str(BUILTIN_RULES),
+    }
+}
+
+fn load_rules(path: &std::path::Path) -> Result<RomajiRules> {
+    let rules = RomajiRules::from_yaml_file(path)?;
+    // sanity check: a trivial word must still be typable with the new rules
+    let mut m = engine::romaji::RomajiMatcher::new("あい", &["ai".to_string()], &rules);
+    if !matches!((m.input_char('a'), m.input_char('i')), (_, engine::romaji::InputResult::Complete)) { anyhow::bail!("ルールの検証に失敗しました"); }
+    Ok(rules)
+}
+
+fn reset_watches(app: &mut App) {
+    app.watch_words = app.cfg.dictionary.as_ref().map(util::watch::FileWatch::new);
+    app.watch_rules = app.cfg.rules_path.as_ref().map(util::watch::FileWatch::new);
+}
+
+// Poll watched files; on change revalidate and swap, keeping the old data on error.
+fn poll_reload(app: &mut App) {
+    if let Some(w) = &mut app.watch_words {
+        if w.poll() {
+            let name = w.path.display().to_string();
+            match load_words_checked(&w.path) {
+                Ok(words) => { app.status = Some(format!("辞書を再読み込みしました: {}（{} 語）", name, words.len())); app.words = words; }
+                Err(e) => app.status = Some(format!("辞書 {} の再読み込みに失敗（前の内容を使用）: {}", name, e)),
+            }
+        }
+    }
+    if let Some(w) = &mut app.watch_rules {
+        if w.poll() {
+            let name = w.path.display().to_string();
+            match load_rules(&w.path) {
+                Ok(rules) => { app.rules = rules; app.status = Some(format!("ルールを再読み込みしました: {}", name)); }
+                Err(e) => app.status = Some(format!("ルール {} の再読み込みに失敗（前の内容を使用）: {}", name, e)),
+            }
+        }
+    }
+}
+
+/// Restore prompt for a corrupted file: ↑/↓ pick a backup, Enter restores, ESC keeps the defaults.
+fn handle_recovery_key(app: &mut App, key: KeyEvent) -> Result<()> {
+    let r = &mut app.recovery[0];
+    match key.code {
+        KeyCode::Up => r.sel = r.sel.saturating_sub(1),
+        KeyCode::Down => r.sel = (r.sel + 1).min(r.backups.len().saturating_sub(1)),
+        KeyCode::Enter if !r.backups.is_empty() => {
+            let r = app.recovery.remove(0);
+            util::persist::restore(&r, &r.backups[r.sel])?;
+            if r.path == AppConfig::path() {
+                app.cfg = AppConfig::load_or_default()?;
+                reset_watches(app);
+            } else {
+                app.store = store::open(&app.cfg.store)?;
+            }
+            app.status = Some(format!("{}を {} から復元しました", r.label, r.backups[r.sel].display()));
+        }
+        KeyCode::Esc => {
+            let r = app.recovery.remove(0);
+            app.status = Some(format!("{}を初期状態で開始しました（壊れたファイル: {}）", r.label, r.moved_to.display()));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// 中断：保存しないでトップへ（試し打ちはエディタへ）
+fn abort_session(app: &mut App) {
+    let is_try = app.game.as_ref().map(|g| g.mode() == engine::game::MODE_TRY).unwrap_or(false);
+    // 中断したセッションも履歴に残す（設定時のみ。打鍵がなければ残さない）
+    if let Some(g) = &app.game {
+        if app.cfg.keep_aborted && !is_try && g.elapsed_secs() > 0.0 {
+            let mut rec = g.finish_record();
+            rec.aborted = true;
+            let saved = app.store.insert_and_rank(rec).and_then(|_| app.store.save());
+            if let Err(e) = saved { app.status = Some(format!("中断記録を保存できません: {}", e)); }
+        }
+    }
+    app.game = None;
+    app.rec_prompt = None;
+    app.countdown_until = None;
+    if is_try {
+        app.screen = Screen::Editor;
+    } else {
+        app.last_result = None;
+        app.screen = Screen::Top;
+    }
+}
+
+fn start_game(app: &mut App, gc: GameConfig, words: Vec<WordEntry>) -> Result<()> {
+    let mut g = match Game::new_with_rules(gc, words, app.rules.clone()) {
+        Ok(g) => g,
+        Err(e) => { app.status = Some(e.to_string()); return Ok(()); }
+    };
+    g.start();
+    app.game = Some(g);
+    app.status = None;
+    app.screen = Screen::Play;
+    // カウントダウン開始（0秒なら即開始）
+    if app.cfg.countdown_sec > 0 {
+        app.countdown_until = Some(Instant::now() + Duration::from_secs(app.cfg.countdown_sec));
+    } else {
+        app.countdown_until = None;
+        if let Some(g) = &mut app.game { g.begin_now(); }
+    }
+    Ok(())
+}
+
+// 長文モード: 設定の long_text_path（青空文庫 .txt / WordsFile .json）を栞の位置から順に入力
+fn start_long_text(app: &mut App) -> Result<()> {
+    let Some(path) = app.cfg.long_text_path.clone() else {
+        app.status = Some("長文: config.json の long_text_path が未設定です".into());
+        return Ok(());
+    };
+    let (wf, st) = match engine::aozora::load_long_text(std::path::Path::new(&path)) {
+        Ok(r) => r,
+        Err(e) => { app.status = Some(format!("長文の読み込みに失敗: {e}")); return Ok(()); }
+    };
+    let n = wf.entries.len();
+    let start = app.cfg.long_text_pos as usize % n;
+    let count = (app.cfg.long_text_sentences.max(1) as usize).min(n);
+    let words: Vec<WordEntry> = wf.entries.iter().cycle().skip(start).take(count).cloned().collect();
+    app.cfg.long_text_pos = start as u32;
+    let gc = GameConfig {
+        time_limit_sec: f64::INFINITY,
+        max_words: words.len(),
+        loss_ms_per_miss: app.cfg.loss_ms_per_miss,
+        fixed_chars: false,
+        target_chars: 0,
+        mode: engine::game::MODE_LONG_TEXT.into(),
+        session: Default::default(),
+    };
+    start_game(app, gc, words)?;
+    app.long_text_note = st.note();
+    if let Some(note) = &app.long_text_note { app.status = Some(format!("長文: {note}")); }
+    Ok(())
+}
+
 fn draw(f: &mut Frame, app: &mut App) {
+    draw_screen(f, app);
+    if !app.recovery.is_empty() { ui::recovery::draw(f, app); return; }
+    // status line (top screen shows it in its own footer; play stays clean)
+    if let Some(msg) = &app.status {
+        if !matches!(app.screen, Screen::Top | Screen::Play) {
+            let area = f.size();
+            let line = ratatui::layout::Rect { x: area.x, y: area.y + area.height.saturating_sub(1), width: area.width, height: 1.min(area.height) };
+            f.render_widget(Paragraph::new(msg.as_str()).style(ratatui::style::Style::default().fg(Color::Yellow)), line);
+        }
+    }
+}
+
+fn draw_screen(f: &mut Frame, app: &mut App) {
     match app.screen {
         Screen::Top => ui::top::draw(f, app),
         Screen::Play => ui::play::draw(f, app),
         Screen::Ranking => ui::ranking::draw(f, app),
         Screen::Details => ui::details::draw(f, app),
+        Screen::Compare => ui::compare::draw(f, app),
+        Screen::Stats => ui::stats::draw(f, app),
         Screen::Settings => ui::settings::draw(f, app),
+        Screen::Dictionary => ui::dictionary::draw(f, app),
+        Screen::Editor => ui::editor::draw(f, app),
+        Screen::Profiles => ui::profiles::draw(f, app),
         Screen::Help => {
             let layout = Layout::default().direction(Direction::Vertical).constraints([
                 Constraint::Percentage(100)
@@ -320,18 +998,347 @@ fn draw(f: &mut Frame, app: &mut App) {
 
 pub use ui::*;
 
+/// Comparison screen: two records, their aligned splits and a shared replay clock.
+#[derive(Debug, Clone)]
+pub struct CompareState {
+    pub a: ScoreRecord,
+    pub b: ScoreRecord,
+    pub cmp: engine::compare::Comparison,
+    /// First row shown in the split table
+    pub scroll: usize,
+    /// Only `time`, `playing` and `speed` are used (both replays follow the same clock)
+    pub replay: ReplayState,
+}
+
+impl CompareState {
+    /// Time of the later of the two last keystrokes.
+    pub fn end(&self) -> f64 {
+        [&self.a, &self.b].iter().filter_map(|r| r.replay.as_ref()?.last().map(|e| e.t)).fold(0.0, f64::max)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayState {
+    pub ev_idx: usize, pub playing: bool, pub speed: f64, pub time: f64,
+    /// 'G' で入力中の移動先時刻
+    pub goto: Option<String>,
+}
+impl Default for ReplayState { fn default()->Self{ Self{ ev_idx:0, playing:true, speed:1.0, time:0.0, goto:None } }}
+impl ReplayState {
+    /// Pause on event `idx` (its time becomes the playback time).
+    pub fn seek_event(&mut self, evs: &[KeyEv], idx: usize) {
+        self.ev_idx = idx.min(evs.len().saturating_sub(1));
+        self.time = evs.get(self.ev_idx).map(|e| e.t).unwrap_or(0.0);
+        self.playing = false;
+    }
+    /// Pause at time `t` (clamped to the replay).
+    pub fn seek_time(&mut self, evs: &[KeyEv], t: f64) {
+        self.time = t.clamp(0.0, evs.last().map(|e| e.t).unwrap_or(0.0));
+        self.ev_idx = store::replay::event_at(evs, self.time).unwrap_or(0);
+        self.playing = false;
+    }
+}
+
+const BUILTIN_WORDS: &str = include_str!("../data/words/basic_common.json");
+const BUILTIN_RULES: &str = include_str!("../data/rules/romaji.yaml");
+
+/// A dictionary shown in the picker (path None = built-in).
+#[derive(Debug, Clone)]
+pub struct DictInfo { pub title: String, pub path: Option<String>, pub words: Vec<WordEntry>, pub error: Option<String> }
+
+/// Built-in dictionary plus every WordsFile in the user words folder.
+pub fn list_dictionaries() -> Vec<DictInfo> {
+    let mut out = Vec::new();
+    if let Ok(wf) = serde_json::from_str::<engine::game::WordsFile>(BUILTIN_WORDS) {
+        out.push(DictInfo { title: format!("{}（内蔵）", wf.title), path: None, words: wf.entries, error: None });
+    }
+    let mut paths: Vec<std::path::PathBuf> = std::fs::read_dir(util::paths::words_dir()).map(|rd| {
+        rd.filter_map(|e| e.ok().map(|e| e.path())).filter(|p| p.extension().map(|x| x == "json").unwrap_or(false)).collect()
+    }).unwrap_or_default();
+    paths.sort();
+    for p in paths {
+        let path = Some(p.to_string_lossy().into_owned());
+        match std::fs::read_to_string(&p).map_err(anyhow::Error::from).and_then(|s| Ok(serde_json::from_str::<engine::game::WordsFile>(&s)?)) {
+            Ok(wf) => out.push(DictInfo { title: wf.title, path, words: wf.entries, error: None }),
+            Err(e) => out.push(DictInfo { title: p.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(), path, words: vec![], error: Some(e.to_string()) }),
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorMode { Browse, Search, Edit, ConfirmDelete }
+
+/// Dictionary editor state (one user WordsFile).
 #[derive(Debug, Clone)]
-pub struct ReplayState { pub ev_idx: usize, pub playing: bool, pub speed: f64, pub time: f64 }
-impl Default for ReplayState { fn default()->Self{ Self{ ev_idx:0, playing:true, speed:1.0, time:0.0 } }}
+pub struct EditorState {
+    pub path: String,
+    pub file: engine::game::WordsFile,
+    pub query: String,
+    /// Cursor within `visible()`
+    pub sel: usize,
+    pub mode: EditorMode,
+    /// Edit form: jp, romas (comma separated), tags (comma separated)
+    pub form: [String; 3],
+    pub field: usize,
+    /// Entry being edited; None = new entry
+    pub editing: Option<usize>,
+    pub dirty: bool,
+    pub confirm_discard: bool,
+    pub message: Option<String>,
+}
+
+fn split_list(s: &str) -> Vec<String> {
+    s.split(',').map(|x| x.trim().to_string()).filter(|x| !x.is_empty()).collect()
+}
+
+impl EditorState {
+    pub fn new(path: String, file: engine::game::WordsFile) -> Self {
+        Self { path, file, query: String::new(), sel: 0, mode: EditorMode::Browse, form: Default::default(), field: 0, editing: None, dirty: false, confirm_discard: false, message: None }
+    }
+
+    pub fn open(path: &str) -> Result<Self> {
+        let s = std::fs::read_to_string(path)?;
+        Ok(Self::new(path.to_string(), serde_json::from_str(&s)?))
+    }
+
+    /// Entry indices matching the search query (jp, romaji or tag).
+    pub fn visible(&self) -> Vec<usize> {
+        let q = self.query.to_lowercase();
+        self.file.entries.iter().enumerate()
+            .filter(|(_, e)| q.is_empty() || e.jp.contains(&q) || e.romas.iter().any(|r| r.contains(&q)) || e.tags.iter().any(|t| t.contains(&q)))
+            .map(|(i, _)| i).collect()
+    }
+
+    pub fn selected(&self) -> Option<usize> { self.visible().get(self.sel).copied() }
+
+    fn begin_edit(&mut self, idx: Option<usize>) {
+        self.form = match idx.and_then(|i| self.file.entries.get(i)) {
+            Some(e) => [e.jp.clone(), e.romas.join(", "), e.tags.join(", ")],
+            None => Default::default(),
+        };
+        self.editing = idx;
+        self.field = 0;
+        self.mode = EditorMode::Edit;
+        self.message = None;
+    }
+
+    /// Live validation of each romaji in the form.
+    pub fn form_checks(&self, rules: &RomajiRules) -> Vec<(String, engine::romaji::RomaCheck)> {
+        let jp = self.form[0].trim();
+        split_list(&self.form[1]).into_iter().map(|r| { let c = engine::romaji::check_roma(jp, &r, rules); (r, c) }).collect()
+    }
+
+    /// First problem that prevents committing the form.
+    pub fn form_error(&self, rules: &RomajiRules) -> Option<String> {
+        let jp = self.form[0].trim();
+        if jp.is_empty() { return Some("日本語が空です".into()); }
+        if self.file.entries.iter().enumerate().any(|(i, e)| e.jp == jp && Some(i) != self.editing) { return Some(format!("「{}」は既に登録されています", jp)); }
+        let checks = self.form_checks(rules);
+        if checks.is_empty() { return Some("ローマ字を1つ以上入力してください".into()); }
+        checks.into_iter().find_map(|(r, c)| match c { engine::romaji::RomaCheck::Invalid(msg) => Some(format!("{}: {}", r, msg)), _ => None })
+    }
+
+    fn commit_form(&mut self) {
+        let mut e = self.editing.and_then(|i| self.file.entries.get(i).cloned()).unwrap_or_default();
+        e.jp = self.form[0].trim().to_string();
+        e.romas = split_list(&self.form[1]);
+        e.tags = split_list(&self.form[2]);
+        match self.editing {
+            Some(i) => self.file.entries[i] = e,
+            None => {
+                self.file.entries.push(e);
+                self.query.clear();
+                self.sel = self.file.entries.len() - 1;
+            }
+        }
+        self.dirty = true;
+        self.mode = EditorMode::Browse;
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        util::persist::write_atomic(std::path::Path::new(&self.path), serde_json::to_string_pretty(&self.file)?.as_bytes())?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Left panel of the details screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailPanel { Fastest, Latency, Rhythm, Misses }
+
+impl DetailPanel {
+    pub fn next(self) -> Self {
+        match self { Self::Fastest => Self::Latency, Self::Latency => Self::Rhythm, Self::Rhythm => Self::Misses, Self::Misses => Self::Fastest }
+    }
+}
+
+/// What the statistics screen shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsView { Trends, Misses, Fingers }
+
+/// Order of the ranking list (S toggles).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankingSort { Time, Consistency }
+
+impl RankingSort {
+    pub fn next(self) -> Self { match self { Self::Time => Self::Consistency, Self::Consistency => Self::Time } }
+    pub fn label(self) -> &'static str { match self { Self::Time => "タイム順", Self::Consistency => "安定度順" } }
+}
+
+/// Text entry / confirmation shown in the ranking footer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RankingInput { Memo(String), ConfirmDelete }
 
 #[derive(Debug, Clone)]
 pub struct RecordPrompt { pub is_new: bool, pub rank_in_top: Option<usize> }
 
+/// Load the `pos`-th record of `detail_ids` (with its replay) into the details screen.
+fn open_detail(app: &mut App, pos: usize) -> Result<()> {
+    let Some(id) = app.detail_ids.get(pos) else { return Ok(()) };
+    match app.store.get(id)? {
+        Some(rec) => {
+            app.detail_check = Some(engine::verify::verify(&rec, &app.rules));
+            app.detail_latency = engine::latency::analyze(&rec, app.cfg.hesitation_ms as f64 / 1000.0);
+            app.detail_misses = engine::misses::classify(&rec, &app.rules);
+            app.detail = Some(rec);
+            app.detail_pos = pos;
+            // Reset replay every time we open details to avoid stale progress
+            app.replay = Some(ReplayState::default());
+            app.screen = Screen::Details;
+        }
+        None => app.status = Some("記録が見つかりません（削除された可能性があります）".into()),
+    }
+    Ok(())
+}
+
+fn open_stats(app: &mut App) -> Result<()> {
+    let history = app.store.history()?;
+    // the history of some backends comes without keystrokes
+    let full = if app.stats_view == StatsView::Trends { Vec::new() } else {
+        history.iter().filter(|r| r.mode == app.ranking_mode)
+            .map(|r| if r.replay.is_some() { Ok(r.clone()) } else { Ok(app.store.get(&r.id)?.unwrap_or_else(|| r.clone())) })
+            .collect::<Result<Vec<ScoreRecord>>>()?
+    };
+    app.stats_misses = (app.stats_view == StatsView::Misses).then(|| engine::misses::aggregate(&full, &app.ranking_mode, &app.rules));
+    app.stats_hands = (app.stats_view == StatsView::Fingers).then(|| engine::fingers::over_history(&full, &app.ranking_mode, &app.cfg.fingers()));
+    app.stats = Some(engine::progress::build(&history, &app.ranking_mode));
+    app.screen = Screen::Stats;
+    Ok(())
+}
+
+/// Load two records (with their replays) into the comparison screen.
+fn open_compare(app: &mut App, a: &str, b: &str) -> Result<()> {
+    match (app.store.get(a)?, app.store.get(b)?) {
+        (Some(a), Some(b)) => {
+            let cmp = engine::compare::compare(&a, &b);
+            // 同じ語順でなければ同期再生はしない
+            let replay = ReplayState { playing: cmp.same_words && a.replay.is_some() && b.replay.is_some(), ..Default::default() };
+            app.compare = Some(CompareState { a, b, cmp, scroll: 0, replay });
+            app.screen = Screen::Compare;
+        }
+        _ => app.status = Some("記録が見つかりません（削除された可能性があります）".into()),
+    }
+    Ok(())
+}
+
+/// Reload the ranking rows of `ranking_mode` from the store.
+pub fn refresh_ranking(app: &mut App) -> Result<()> {
+    app.ranking_top = if app.ranking_history {
+        let mut v: Vec<ScoreRecord> = app.store.history()?.into_iter().filter(|r| r.mode == app.ranking_mode).collect();
+        v.reverse();
+        v
+    } else if app.ranking_sort == RankingSort::Consistency {
+        store::top_by_consistency(app.store.history()?, &app.ranking_mode, 100)
+    } else { app.store.top(&app.ranking_mode, 100)? };
+    app.ranking_lap = app.store.lap_top(10)?;
+    app.ranking_sel = app.ranking_sel.min(app.ranking_top.len().saturating_sub(1));
+    Ok(())
+}
+
+/// Ranking action: history as CSV + JSON Lines, and a Markdown report of the last session.
+fn export_from_ranking(app: &App) -> Result<Vec<std::path::PathBuf>> {
+    use store::export::{self, Format};
+    let dir = export::export_dir();
+    let stem = format!("irohatype-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let records = export::collect(app.store.as_ref(), None, false)?;
+    let mut out = vec![export::write(&dir, &stem, &records, Format::Csv)?, export::write(&dir, &stem, &records, Format::Jsonl)?];
+    if let Some(r) = &app.last_result { out.push(export::write(&dir, &format!("{}-report", stem), std::slice::from_ref(r), Format::Markdown)?); }
+    Ok(out)
+}
+
+/// Memo entry / delete confirmation on the ranking screen.
+fn handle_ranking_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    let Some(id) = app.ranking_top.get(app.ranking_sel).map(|r| r.id.clone()) else { app.ranking_input = None; return Ok(()) };
+    match app.ranking_input.as_mut() {
+        Some(RankingInput::Memo(text)) => match key.code {
+            KeyCode::Esc => app.ranking_input = None,
+            KeyCode::Backspace => { text.pop(); }
+            KeyCode::Char(c) => text.push(c),
+            KeyCode::Enter => {
+                let memo = text.trim().to_string();
+                app.ranking_input = None;
+                app.store.set_memo(&id, memo)?;
+                app.store.save()?;
+                refresh_ranking(app)?;
+                select_ranking_id(app, &id);
+            }
+            _ => {}
+        },
+        Some(RankingInput::ConfirmDelete) => {
+            app.ranking_input = None;
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                // keep the full record (with replay) for undo
+                app.undo_delete = app.store.get(&id)?;
+                app.store.delete(&id)?;
+                app.store.save()?;
+                refresh_ranking(app)?;
+                app.status = Some("記録を削除しました（U で元に戻す）".into());
+            }
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+fn select_ranking_id(app: &mut App, id: &str) {
+    if let Some(i) = app.ranking_top.iter().position(|r| r.id == id) { app.ranking_sel = i; }
+}
+
+fn open_ranking(app: &mut App) -> Result<()> {
+    refresh_ranking(app)?;
+    app.screen = Screen::Ranking;
+    Ok(())
+}
+
 fn finalize_result_prompt(app: &mut App, go_ranking: bool) -> anyhow::Result<()> {
     // 記録は挿入済み。ここで保存して画面遷移するだけ。
     app.rec_prompt = None;
     app.game = None;
-    app.scorebook.save()?;
-    app.screen = if go_ranking { Screen::Ranking } else { Screen::Top };
+    app.store.save()?;
+    if go_ranking {
+        open_ranking(app)?;
+        // put the cursor on the run just finished (the top row when it is not listed)
+        app.ranking_sel = 0;
+        if let Some(id) = app.last_result.as_ref().map(|r| r.id.clone()) { select_ranking_id(app, &id); }
+    } else { app.screen = Screen::Top; }
+    Ok(())
+}
+
+/// Time entry ('G') on the details screen.
+fn handle_goto_input(app: &mut App, key: KeyEvent) -> Result<()> {
+    let evs: &[KeyEv] = app.detail.as_ref().and_then(|r| r.replay.as_deref()).unwrap_or(&[]);
+    let Some(rep) = app.replay.as_mut() else { return Ok(()) };
+    let Some(text) = rep.goto.as_mut() else { return Ok(()) };
+    match key.code {
+        KeyCode::Esc => rep.goto = None,
+        KeyCode::Backspace => { text.pop(); }
+        KeyCode::Char(c) if c.is_ascii_digit() || c == '.' || c == ':' => text.push(c),
+        KeyCode::Enter => match store::replay::parse_time(text) {
+            Some(t) => { rep.goto = None; rep.seek_time(evs, t); }
+            None => app.status = Some(format!("時刻として読めません: {}", text)),
+        },
+        _ => {}
+    }
     Ok(())
 }
@@ -0,0 +1,177 @@
+// Command-line subcommands (run without the TUI).
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::store::{self, export::{self, Format}, merge, replay::{self, ReplayFile}};
+use crate::util::config::AppConfig;
+
+pub const USAGE: &str = "使い方: irohatype [--data-dir DIR] [--profile NAME] [コマンド]
+  export [--format csv|jsonl|md] [--out FILE] [--mode MODE] [--id ID] [--aborted]
+  import <scores.json|scores.db|*.irohareplay|同期フォルダ>
+  replay <ID> [--out FILE]   記録をリプレイファイル (.irohareplay) に書き出す
+  verify [ID|FILE.irohareplay]   リプレイを再生して記録の改ざんを検査（省略時は全記録）
+  cast <ID|FILE.irohareplay> [--out FILE] [--size WxH]   asciinema (.cast) として書き出す";
+
+/// Run a subcommand. `args` are the words after the subcommand name.
+pub fn run(cmd: &str, args: &[String]) -> Result<()> {
+    match cmd {
+        "export" => export_cmd(args),
+        "import" => import_cmd(args),
+        "replay" => replay_cmd(args),
+        "verify" => verify_cmd(args),
+        "cast" => cast_cmd(args),
+        _ => bail!("不明なコマンド: {}\n{}", cmd, USAGE),
+    }
+}
+
+/// Take the value of `--name VALUE` / `--name=VALUE`.
+fn value(args: &[String], i: &mut usize, name: &str) -> Result<String> {
+    let a = &args[*i];
+    if let Some(v) = a.strip_prefix(&format!("{}=", name)) { return Ok(v.to_string()); }
+    *i += 1;
+    args.get(*i).cloned().ok_or_else(|| anyhow!("{} には値を指定してください", name))
+}
+
+fn export_cmd(args: &[String]) -> Result<()> {
+    let (mut format, mut out, mut mode, mut id, mut aborted) = (Format::Csv, None::<PathBuf>, None::<String>, None::<String>, false);
+    let mut i = 0;
+    while i < args.len() {
+        let name = args[i].split('=').next().unwrap_or_default().to_string();
+        match name.as_str() {
+            "--format" => format = Format::parse(&value(args, &mut i, &name)?)?,
+            "--out" => out = Some(value(args, &mut i, &name)?.into()),
+            "--mode" => mode = Some(value(args, &mut i, &name)?),
+            "--id" => id = Some(value(args, &mut i, &name)?),
+            "--aborted" => aborted = true,
+            _ => bail!("不明な引数: {}\n{}", args[i], USAGE),
+        }
+        i += 1;
+    }
+    let cfg = AppConfig::load_or_default()?;
+    let store = store::open(&cfg.store)?;
+    let records = match &id {
+        Some(id) => vec![store.get(id)?.ok_or_else(|| anyhow!("記録 {} が見つかりません", id))?],
+        None => export::collect(store.as_ref(), mode.as_deref(), aborted)?,
+    };
+    let text = export::render(&records, format)?;
+    match out {
+        Some(p) => { crate::util::paths::ensure_parent(&p)?; std::fs::write(&p, text)?; eprintln!("{} 件を {} に書き出しました", records.len(), p.display()); }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+/// Merge another store into the local one; a folder is synced both ways.
+fn import_cmd(args: &[String]) -> Result<()> {
+    let [src] = args else { bail!("取り込むファイルかフォルダを1つ指定してください\n{}", USAGE) };
+    let src = PathBuf::from(src);
+    let mut cfg = AppConfig::load_or_default()?;
+    let mut store = store::open(&cfg.store)?;
+    let report = if src.is_dir() {
+        if cfg.machine_id.is_none() { cfg.machine_id = Some(cfg.machine_name()); cfg.save()?; }
+        let machine = cfg.machine_name();
+        let r = merge::sync_folder(store.as_mut(), &src, &machine)?;
+        eprintln!("同期フォルダ {} に {} を書き出しました", src.display(), merge::sync_file(&src, &machine).display());
+        r
+    } else {
+        let r = merge::merge_into(store.as_mut(), merge::read_source(&src)?)?;
+        store.save()?;
+        r
+    };
+    println!("{}", report.summary());
+    for c in &report.conflicts {
+        println!("  競合 {} ({}): {} が異なるためローカルの記録を残しました", c.id, c.datetime, c.fields.join(", "));
+    }
+    for id in &report.rejected { println!("  除外 {}: ハッシュが一致しません", id); }
+    Ok(())
+}
+
+/// Write one record as a standalone .irohareplay file.
+fn replay_cmd(args: &[String]) -> Result<()> {
+    let (mut id, mut out) = (None::<String>, None::<PathBuf>);
+    let mut i = 0;
+    while i < args.len() {
+        let name = args[i].split('=').next().unwrap_or_default().to_string();
+        match name.as_str() {
+            "--out" => out = Some(value(args, &mut i, &name)?.into()),
+            _ if id.is_none() && !name.starts_with("--") => id = Some(args[i].clone()),
+            _ => bail!("不明な引数: {}\n{}", args[i], USAGE),
+        }
+        i += 1;
+    }
+    let id = id.ok_or_else(|| anyhow!("記録のIDを指定してください\n{}", USAGE))?;
+    let cfg = AppConfig::load_or_default()?;
+    let rec = store::open(&cfg.store)?.get(&id)?.ok_or_else(|| anyhow!("記録 {} が見つかりません", id))?;
+    let rules = crate::app::rules_for(&cfg)?;
+    if rec.rules_version.as_deref().is_some_and(|v| v != rules.version()) {
+        eprintln!("注意: 記録時とローマ字ルールが異なります（現在のルールを同梱します）");
+    }
+    let path = out.unwrap_or_else(|| export::export_dir().join(replay::file_name(&rec)));
+    ReplayFile::from_record(&rec, &rules.source)?.write(&path)?;
+    eprintln!("{} に書き出しました", path.display());
+    Ok(())
+}
+
+/// Re-run replays and check hashes; fails when any record does not match.
+fn verify_cmd(args: &[String]) -> Result<()> {
+    let cfg = AppConfig::load_or_default()?;
+    let (records, rules) = match args {
+        [] => { let store = store::open(&cfg.store)?; (export::collect(store.as_ref(), None, true)?, crate::app::rules_for(&cfg)?) }
+        // a replay file brings its own rules
+        [src] => { let (rec, rules) = load_for_playback(&cfg, src)?; (vec![rec], rules) }
+        _ => bail!("検査する記録を1つ指定してください\n{}", USAGE),
+    };
+    let (mut verified, mut bad) = (0, 0);
+    for rec in &records {
+        let rep = crate::engine::verify::verify(rec, &rules);
+        if rep.verified() { verified += 1; }
+        if !rep.ok() || args.len() == 1 {
+            println!("{} ({}): {}", rec.id, rec.datetime, rep.summary());
+            for i in &rep.issues { println!("  {}", i); }
+        }
+        if !rep.ok() { bad += 1; }
+    }
+    println!("{} 件中 検証済み {} 件 / 不一致 {} 件", records.len(), verified, bad);
+    if bad > 0 { bail!("{} 件の記録が一致しませんでした", bad); }
+    Ok(())
+}
+
+/// Record or replay file -> asciinema v2 cast (default size = the configured stage).
+fn cast_cmd(args: &[String]) -> Result<()> {
+    let (mut src, mut out, mut size) = (None::<String>, None::<PathBuf>, None::<String>);
+    let mut i = 0;
+    while i < args.len() {
+        let name = args[i].split('=').next().unwrap_or_default().to_string();
+        match name.as_str() {
+            "--out" => out = Some(value(args, &mut i, &name)?.into()),
+            "--size" => size = Some(value(args, &mut i, &name)?),
+            _ if src.is_none() && !name.starts_with("--") => src = Some(args[i].clone()),
+            _ => bail!("不明な引数: {}\n{}", args[i], USAGE),
+        }
+        i += 1;
+    }
+    let src = src.ok_or_else(|| anyhow!("記録のIDかリプレイファイルを指定してください\n{}", USAGE))?;
+    let cfg = AppConfig::load_or_default()?;
+    let (w, h) = match size {
+        Some(s) => s.split_once('x').and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?))).ok_or_else(|| anyhow!("--size は 100x30 の形で指定してください"))?,
+        None => (cfg.stage_w, cfg.stage_h),
+    };
+    let (rec, rules) = load_for_playback(&cfg, &src)?;
+    let path = out.unwrap_or_else(|| export::export_dir().join(format!("{}.{}", if rec.id.is_empty() { "replay" } else { rec.id.as_str() }, crate::ui::cast::EXT)));
+    crate::ui::cast::write_to(&path, &rec, &rules, w, h)?;
+    eprintln!("{} に書き出しました", path.display());
+    Ok(())
+}
+
+/// A stored record (with the current rules) or a replay file (with the rules it carries).
+fn load_for_playback(cfg: &AppConfig, src: &str) -> Result<(crate::store::json::ScoreRecord, crate::engine::romaji::RomajiRules)> {
+    let rules = crate::app::rules_for(cfg)?;
+    if src.ends_with(&format!(".{}", replay::EXT)) {
+        let file = ReplayFile::read(std::path::Path::new(src))?;
+        let rules = if file.header.rules.is_empty() { rules } else { crate::engine::romaji::RomajiRules::from_yaml_str(&file.header.rules)? };
+        return Ok((file.into_record(), rules));
+    }
+    Ok((store::open(&cfg.store)?.get(src)?.ok_or_else(|| anyhow!("記録 {} が見つかりません", src))?, rules))
+}
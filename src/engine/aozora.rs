@@ -0,0 +1,223 @@
+// Aozora Bunko (青空文庫) plain-text importer.
+// Strips header/footer and ［＃…］ annotations, resolves ruby (｜漢字《かんじ》 / 漢字《かんじ》)
+// into readings and splits the body into sentence entries for the long-text mode.
+
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Result};
+
+use super::game::{WordEntry, WordsFile};
+use super::kana::kana_to_romaji;
+
+#[derive(Debug, Clone, Default)]
+pub struct AozoraDoc {
+    pub title: String,
+    pub author: String,
+    /// (display text, kana reading) per sentence
+    pub sentences: Vec<(String, String)>,
+    /// (base, ruby) of every ruby in the body, first reading of each base
+    pub readings: Vec<(String, String)>,
+}
+
+/// What the import made of the sentences.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportStats {
+    pub sentences: usize,
+    /// Sentences whose kanji without ruby were read from a ruby given elsewhere in the text
+    pub filled: usize,
+    /// Sentences kept with kanji that have no reading: only their kana are typed
+    pub partial: usize,
+    /// Sentences with nothing to type (gaiji, unknown characters)
+    pub skipped: usize,
+    /// Characters of all sentences, and those left untyped
+    pub chars: usize,
+    pub untyped: usize,
+}
+
+impl ImportStats {
+    /// Share of the text's characters that are typed.
+    pub fn typable_share(&self) -> f64 { if self.chars == 0 { 0.0 } else { 1.0 - self.untyped as f64 / self.chars as f64 } }
+    /// One-line summary for the status line; None when every sentence was imported whole.
+    pub fn note(&self) -> Option<String> {
+        (self.partial + self.skipped > 0).then(|| format!("読み不明の漢字を含む {} 文は仮名のみ入力・{} 文はスキップ（全 {} 文）", self.partial, self.skipped, self.sentences))
+    }
+}
+
+/// Below this share of typable characters an import is rejected.
+pub const MIN_TYPABLE_SHARE: f64 = 0.5;
+
+fn is_separator(line: &str) -> bool { line.chars().count() >= 10 && line.chars().all(|c| c == '-') }
+
+fn is_kanji(c: char) -> bool {
+    matches!(c, '々' | '〆' | 'ヶ' | '〇') || ('\u{4E00}'..='\u{9FFF}').contains(&c) || ('\u{3400}'..='\u{4DBF}').contains(&c)
+}
+
+/// Parse an Aozora Bunko text (UTF-8) into sentences with readings.
+pub fn parse(text: &str) -> AozoraDoc {
+    let text = text.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let lines: Vec<&str> = text.lines().collect();
+    let title = lines.first().map(|s| s.trim().to_string()).unwrap_or_default();
+    let author = lines.get(1).map(|s| s.trim().to_string()).unwrap_or_default();
+
+    // Header: title/author, then the 【テキスト中に現れる記号について】 block between two separators
+    let seps: Vec<usize> = lines.iter().enumerate().filter(|(_, l)| is_separator(l.trim())).map(|(i, _)| i).take(2).collect();
+    let body_start = match seps.as_slice() {
+        [_, b] => b + 1,
+        [a] => a + 1,
+        _ => lines.iter().position(|l| l.trim().is_empty()).map(|i| i + 1).unwrap_or(0),
+    };
+    // Footer: 底本 information and everything after it
+    let body_end = lines.iter().enumerate().skip(body_start)
+        .find(|(_, l)| l.starts_with("底本：") || l.starts_with("底本:"))
+        .map(|(i, _)| i).unwrap_or(lines.len());
+
+    let mut sentences = Vec::new();
+    let mut readings: Vec<(String, String)> = Vec::new();
+    for line in &lines[body_start..body_end] {
+        let (display, reading) = resolve_ruby_into(&strip_annotations(line), &mut readings);
+        for (d, r) in split_sentences(&display, &reading) {
+            sentences.push((d, r));
+        }
+    }
+    AozoraDoc { title, author, sentences, readings }
+}
+
+/// Remove ［＃…］ annotation blocks. Gaiji markers (※) are kept so the sentence is skipped later.
+pub fn strip_annotations(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut depth = 0usize;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '［' && chars.peek() == Some(&'＃') { depth += 1; continue; }
+        if depth > 0 {
+            if c == '［' { depth += 1; }
+            if c == '］' { depth -= 1; }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Resolve ruby markup. Returns (display text without ruby, reading with ruby applied).
+pub fn resolve_ruby(line: &str) -> (String, String) { resolve_ruby_into(line, &mut Vec::new()) }
+
+/// `resolve_ruby`, collecting each new (base, ruby) pair into `readings`.
+fn resolve_ruby_into(line: &str, readings: &mut Vec<(String, String)>) -> (String, String) {
+    let mut display = String::new();
+    let mut reading = String::new();
+    // explicit base start (reading byte offset) set by ｜
+    let mut mark: Option<usize> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '｜' => { mark = Some(reading.len()); }
+            '《' => {
+                let ruby: String = chars.by_ref().take_while(|&c| c != '》').collect();
+                let base_start = match mark.take() {
+                    Some(r) => r,
+                    None => {
+                        // implicit base: trailing run of kanji (not yet replaced by an earlier ruby)
+                        let tail: usize = reading.chars().rev().take_while(|&c| is_kanji(c)).map(|c| c.len_utf8()).sum();
+                        reading.len().saturating_sub(tail)
+                    }
+                };
+                let base = &reading[base_start..];
+                if !base.is_empty() && !ruby.is_empty() && !readings.iter().any(|(b, _)| b == base) { readings.push((base.to_string(), ruby.clone())); }
+                reading.truncate(base_start);
+                reading.push_str(&ruby);
+            }
+            _ => { display.push(c); reading.push(c); }
+        }
+    }
+    (display, reading)
+}
+
+/// Split a line into sentences at 。！？ outside of 「」『』（） quotes.
+fn split_sentences(display: &str, reading: &str) -> Vec<(String, String)> {
+    fn cut(s: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        let mut cur = String::new();
+        let mut depth = 0usize;
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            cur.push(c);
+            match c {
+                '「' | '『' | '（' => depth += 1,
+                '」' | '』' | '）' => depth = depth.saturating_sub(1),
+                '。' | '！' | '？' if depth == 0 => {
+                    // keep runs like "！？" together
+                    while let Some(&n) = chars.peek() {
+                        if matches!(n, '！' | '？') { cur.push(n); chars.next(); } else { break; }
+                    }
+                    out.push(std::mem::take(&mut cur));
+                }
+                _ => {}
+            }
+        }
+        if !cur.trim().is_empty() { out.push(cur); }
+        out.into_iter().map(|s| s.trim_matches(|c: char| c.is_whitespace() || c == '　').to_string()).filter(|s| !s.is_empty()).collect()
+    }
+    let d = cut(display);
+    let r = cut(reading);
+    // ruby never contains sentence terminators, so both sides split identically
+    if d.len() != r.len() { return vec![]; }
+    d.into_iter().zip(r).collect()
+}
+
+/// Read kanji left without ruby with the rubies given elsewhere in the text (Aozora Bunko
+/// usually puts ruby on the first occurrence only), longest base first.
+fn fill_readings(reading: &str, readings: &[(String, String)]) -> String {
+    let mut out = String::with_capacity(reading.len());
+    let mut rest = reading;
+    while let Some(c) = rest.chars().next() {
+        let hit = if is_kanji(c) { readings.iter().filter(|(b, _)| rest.starts_with(b.as_str())).max_by_key(|(b, _)| b.len()) } else { None };
+        match hit {
+            Some((b, r)) => { out.push_str(r); rest = &rest[b.len()..]; }
+            None => { out.push(c); rest = &rest[c.len_utf8()..]; }
+        }
+    }
+    out
+}
+
+/// Convert parsed sentences into word entries. Kanji without ruby take a reading given to the
+/// same kanji elsewhere in the text; sentences still containing kanji without a reading are
+/// kept with the kanji shown but not typed. Sentences with nothing typable (gaiji etc.) are skipped.
+pub fn to_words_file(doc: &AozoraDoc) -> (WordsFile, ImportStats) {
+    let mut entries = Vec::new();
+    let mut st = ImportStats { sentences: doc.sentences.len(), ..Default::default() };
+    for (display, reading) in &doc.sentences {
+        st.chars += display.chars().count();
+        let filled = fill_readings(reading, &doc.readings);
+        let untyped = filled.chars().filter(|&c| is_kanji(c)).count();
+        let kana: String = filled.chars().filter(|&c| !is_kanji(c)).collect();
+        match kana_to_romaji(&kana) {
+            Some(roma) if roma.chars().any(|c| c.is_ascii_alphanumeric()) => {
+                if filled != *reading { st.filled += 1; }
+                if untyped > 0 { st.partial += 1; }
+                st.untyped += untyped;
+                entries.push(WordEntry { jp: display.clone(), romas: vec![roma], ..Default::default() });
+            }
+            _ => { st.skipped += 1; st.untyped += display.chars().count(); }
+        }
+    }
+    (WordsFile { title: doc.title.clone(), version: 1, entries }, st)
+}
+
+/// Load a long-text source: a WordsFile JSON or an Aozora Bunko .txt file.
+/// Aozora texts that are mostly untypable are rejected; the stats are those of the import
+/// (default for JSON).
+pub fn load_long_text(path: &Path) -> Result<(WordsFile, ImportStats)> {
+    let data = fs::read_to_string(path).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    let (wf, st) = if path.extension().map(|e| e == "json").unwrap_or(false) {
+        (serde_json::from_str(&data)?, ImportStats::default())
+    } else {
+        let (wf, st) = to_words_file(&parse(&data));
+        if !wf.entries.is_empty() && st.typable_share() < MIN_TYPABLE_SHARE {
+            return Err(anyhow!("{}: 読みの分からない文字が多すぎます（入力できるのは {:.0}%）", path.display(), st.typable_share() * 100.0));
+        }
+        (wf, st)
+    };
+    if wf.entries.is_empty() { return Err(anyhow!("{}: 入力可能な文がありません", path.display())); }
+    Ok((wf, st))
+}
@@ -0,0 +1,82 @@
+// Two-record comparison: splits aligned word by word (by position when both runs typed
+// the same sequence, otherwise by a longest-common-subsequence match of the words).
+
+use crate::store::json::ScoreRecord;
+
+/// One aligned word. `a` / `b` are None when only the other run has the word.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordDelta {
+    pub word: String,
+    pub a: Option<f64>,
+    pub b: Option<f64>,
+    pub miss_a: u32,
+    pub miss_b: u32,
+}
+
+impl WordDelta {
+    /// B - A in seconds (negative = B was faster).
+    pub fn delta(&self) -> Option<f64> { Some(self.b? - self.a?) }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Comparison {
+    pub rows: Vec<WordDelta>,
+    /// Both runs typed the same words in the same order (their replays can be played in sync)
+    pub same_words: bool,
+    pub time_delta: f64,
+    pub miss_delta: i64,
+}
+
+impl Comparison {
+    /// Row indices of the `n` largest differences, largest first.
+    pub fn largest(&self, n: usize) -> Vec<usize> {
+        let mut v: Vec<(usize, f64)> = self.rows.iter().enumerate().filter_map(|(i, r)| r.delta().map(|d| (i, d.abs()))).collect();
+        v.sort_by(|x, y| y.1.partial_cmp(&x.1).unwrap_or(std::cmp::Ordering::Equal));
+        v.into_iter().filter(|(_, d)| *d > 0.0).take(n).map(|(i, _)| i).collect()
+    }
+    /// Words typed by both runs.
+    pub fn matched(&self) -> usize { self.rows.iter().filter(|r| r.delta().is_some()).count() }
+}
+
+/// The sequence of words a run typed (the stored sequence when present).
+pub fn word_list(rec: &ScoreRecord) -> Vec<String> {
+    if rec.word_seq.is_empty() { rec.splits.iter().map(|s| s.word.clone()).collect() } else { rec.word_seq.iter().map(|w| w.jp.clone()).collect() }
+}
+
+pub fn compare(a: &ScoreRecord, b: &ScoreRecord) -> Comparison {
+    let (wa, wb) = (word_list(a), word_list(b));
+    let same_words = !wa.is_empty() && wa == wb;
+    let row = |ia: Option<usize>, ib: Option<usize>| {
+        let (sa, sb) = (ia.and_then(|i| a.splits.get(i)), ib.and_then(|i| b.splits.get(i)));
+        WordDelta {
+            word: sa.or(sb).map(|s| s.word.clone()).unwrap_or_default(),
+            a: sa.map(|s| s.sec), b: sb.map(|s| s.sec),
+            miss_a: sa.map(|s| s.miss).unwrap_or(0), miss_b: sb.map(|s| s.miss).unwrap_or(0),
+        }
+    };
+    let rows = if same_words || a.splits.iter().zip(&b.splits).all(|(x, y)| x.word == y.word) {
+        (0..a.splits.len().max(b.splits.len())).map(|i| row((i < a.splits.len()).then_some(i), (i < b.splits.len()).then_some(i))).collect()
+    } else {
+        align(&a.splits.iter().map(|s| s.word.as_str()).collect::<Vec<_>>(), &b.splits.iter().map(|s| s.word.as_str()).collect::<Vec<_>>())
+            .into_iter().map(|(ia, ib)| row(ia, ib)).collect()
+    };
+    Comparison { rows, same_words, time_delta: b.time_sec - a.time_sec, miss_delta: b.miss as i64 - a.miss as i64 }
+}
+
+/// LCS alignment: matched pairs in order, unmatched words of either side in between.
+fn align(a: &[&str], b: &[&str]) -> Vec<(Option<usize>, Option<usize>)> {
+    let (n, m) = (a.len(), b.len());
+    let mut dp = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            dp[i][j] = if a[i] == b[j] { dp[i + 1][j + 1] + 1 } else { dp[i + 1][j].max(dp[i][j + 1]) };
+        }
+    }
+    let (mut i, mut j, mut out) = (0, 0, Vec::with_capacity(n.max(m)));
+    while i < n || j < m {
+        if i < n && j < m && a[i] == b[j] { out.push((Some(i), Some(j))); i += 1; j += 1; }
+        else if j >= m || (i < n && dp[i + 1][j] >= dp[i][j + 1]) { out.push((Some(i), None)); i += 1; }
+        else { out.push((None, Some(j))); j += 1; }
+    }
+    out
+}
@@ -0,0 +1,124 @@
+// Word difficulty scoring and easy/normal/hard grading.
+// Score is roughly "keystroke-equivalents": length plus penalties for
+// yōon/sokuon/ん, same-finger bigrams and row jumps, scaled by the user's
+// own per-key time on the word when history is available.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::game::WordEntry;
+use crate::store::json::ScoreRecord;
+use crate::util::keymap::{finger_of, row_of};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Grade { Easy, Normal, Hard }
+
+impl Grade {
+    pub fn label(self) -> &'static str { match self { Grade::Easy => "易", Grade::Normal => "中", Grade::Hard => "難" } }
+}
+
+/// Breakdown of the static (history-free) features of a word.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Features { pub keys: u32, pub edge_cases: u32, pub same_finger: u32, pub row_jumps: u32 }
+
+fn is_vowel(c: u8) -> bool { matches!(c, b'a' | b'i' | b'u' | b'e' | b'o') }
+
+pub fn features(roma: &str) -> Features {
+    let b = roma.as_bytes();
+    let mut f = Features { keys: b.len() as u32, ..Default::default() };
+    for i in 0..b.len() {
+        let c = b[i];
+        let next = b.get(i + 1).copied();
+        // 拗音: Cy + vowel, sh/ch + vowel (not "shi"/"chi")
+        if c == b'y' && i > 0 && !is_vowel(b[i - 1]) && b[i - 1] != b'n' && next.map(is_vowel).unwrap_or(false) { f.edge_cases += 1; }
+        if c == b'h' && i > 0 && matches!(b[i - 1], b's' | b'c') && matches!(next, Some(b'a' | b'u' | b'o')) { f.edge_cases += 1; }
+        // 促音: doubled consonant
+        if i > 0 && c == b[i - 1] && c.is_ascii_alphabetic() && !is_vowel(c) && c != b'n' { f.edge_cases += 1; }
+        // 撥音: "nn"/"n'" or n before a consonant
+        if c == b'n' && (matches!(next, Some(b'n' | b'\'')) || matches!(next, Some(n) if n.is_ascii_alphabetic() && !is_vowel(n) && n != b'y')) && (i == 0 || b[i - 1] != b'n') { f.edge_cases += 1; }
+        if let Some(n) = next {
+            let (c, n) = (c as char, n as char);
+            if c != n {
+                if let (Some(a), Some(z)) = (finger_of(c), finger_of(n)) { if a == z { f.same_finger += 1; } }
+            }
+            if let (Some(a), Some(z)) = (row_of(c), row_of(n)) { if a.abs_diff(z) >= 2 { f.row_jumps += 1; } }
+        }
+    }
+    f
+}
+
+/// The user's per-word typing speed relative to their overall speed.
+#[derive(Debug, Clone, Default)]
+pub struct WordTimes { per_word: HashMap<String, (f64, u32)>, mean_sec_per_key: f64 }
+
+impl WordTimes {
+    /// Build from stored records (split time per word; keystrokes from the dictionary).
+    pub fn from_records<'a>(records: impl Iterator<Item = &'a ScoreRecord>, words: &[WordEntry]) -> Self {
+        let keys: HashMap<&str, usize> = words.iter().map(|w| (w.jp.as_str(), min_keys(w))).collect();
+        let mut per_word: HashMap<String, (f64, u32)> = HashMap::new();
+        let (mut sum, mut cnt) = (0.0, 0u32);
+        for r in records {
+            for s in &r.splits {
+                let Some(&k) = keys.get(s.word.as_str()) else { continue };
+                if k == 0 || s.sec <= 0.0 { continue; }
+                let spk = s.sec / k as f64;
+                let e = per_word.entry(s.word.clone()).or_insert((0.0, 0));
+                e.0 += spk; e.1 += 1;
+                sum += spk; cnt += 1;
+            }
+        }
+        Self { per_word, mean_sec_per_key: if cnt > 0 { sum / cnt as f64 } else { 0.0 } }
+    }
+
+    /// Relative slowness (1.0 = average). None when the word was never typed.
+    pub fn factor(&self, jp: &str) -> Option<f64> {
+        let (s, n) = self.per_word.get(jp)?;
+        if *n == 0 || self.mean_sec_per_key <= 0.0 { return None; }
+        Some((s / *n as f64 / self.mean_sec_per_key).clamp(0.5, 2.0))
+    }
+}
+
+fn min_keys(e: &WordEntry) -> usize { e.romas.iter().map(|s| s.len()).min().unwrap_or(0) }
+
+/// Difficulty of a word. An explicit `difficulty` in the dictionary wins.
+pub fn score(e: &WordEntry, hist: Option<&WordTimes>) -> f64 {
+    if let Some(d) = e.difficulty { return d; }
+    let roma = e.romas.iter().min_by_key(|s| s.len()).map(|s| s.as_str()).unwrap_or("");
+    let f = features(roma);
+    let base = f.keys as f64 + 2.0 * f.edge_cases as f64 + 1.5 * f.same_finger as f64 + f.row_jumps as f64;
+    base * hist.and_then(|h| h.factor(&e.jp)).unwrap_or(1.0)
+}
+
+/// Fill in `difficulty` (where not set in the dictionary) using the user's history.
+pub fn with_history(words: &[WordEntry], hist: &WordTimes) -> Vec<WordEntry> {
+    words.iter().map(|w| {
+        let mut w = w.clone();
+        if w.difficulty.is_none() { w.difficulty = Some(score(&w, Some(hist))); }
+        w
+    }).collect()
+}
+
+/// Tercile boundaries (easy < lo <= normal < hi <= hard) over a word list.
+pub fn grade_bounds(words: &[WordEntry]) -> (f64, f64) {
+    let mut s: Vec<f64> = words.iter().map(|w| score(w, None)).collect();
+    if s.is_empty() { return (0.0, 0.0); }
+    s.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    (s[s.len() / 3], s[s.len() * 2 / 3])
+}
+
+pub fn grade_of(score: f64, bounds: (f64, f64)) -> Grade {
+    if score < bounds.0 { Grade::Easy } else if score < bounds.1 { Grade::Normal } else { Grade::Hard }
+}
+
+/// Histogram of scores in fixed-width buckets: (bucket start, count).
+pub fn distribution(words: &[WordEntry], bucket: f64) -> Vec<(f64, u64)> {
+    let mut counts: Vec<u64> = Vec::new();
+    for w in words {
+        let i = (score(w, None) / bucket).floor().max(0.0) as usize;
+        if counts.len() <= i { counts.resize(i + 1, 0); }
+        counts[i] += 1;
+    }
+    counts.into_iter().enumerate().map(|(i, c)| (i as f64 * bucket, c)).collect()
+}
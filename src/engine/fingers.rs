@@ -0,0 +1,125 @@
+// Finger and hand usage of a run: every keystroke is mapped to a finger with the configured
+// `FingerMap` (the finger that pressed the key, so a slip counts on the finger that slipped).
+
+use std::collections::BTreeMap;
+
+use crate::store::json::{KeyEv, ScoreRecord};
+use crate::util::keymap::{Finger, FingerMap};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FingerStat {
+    pub finger: Finger,
+    /// Keys pressed with this finger (misses included)
+    pub keys: usize,
+    pub misses: usize,
+    /// Sum / count of the intervals before its keys (the first key of each word is a reaction
+    /// time, not a finger's, and is left out; miss penalties are subtracted)
+    pub time: f64,
+    pub timed: usize,
+}
+
+impl FingerStat {
+    pub fn miss_rate(&self) -> Option<f64> { (self.keys > 0).then(|| self.misses as f64 / self.keys as f64) }
+    pub fn mean_latency(&self) -> Option<f64> { (self.timed > 0).then(|| self.time / self.timed as f64) }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandUsage {
+    /// In `Finger::ALL` order
+    pub fingers: Vec<FingerStat>,
+    /// Presses per key
+    pub keys: BTreeMap<char, usize>,
+    /// Consecutive correct keys inside a word, and how many of them switch hands
+    pub pairs: usize,
+    pub alternations: usize,
+    /// Consecutive correct keys typed by the same finger on different keys
+    pub same_finger: usize,
+    /// Keys the map does not know (spaces, symbols of other layouts)
+    pub unmapped: usize,
+    pub sessions: usize,
+}
+
+impl Default for HandUsage {
+    fn default() -> Self {
+        let fingers = Finger::ALL.iter().map(|&finger| FingerStat { finger, keys: 0, misses: 0, time: 0.0, timed: 0 }).collect();
+        Self { fingers, keys: BTreeMap::new(), pairs: 0, alternations: 0, same_finger: 0, unmapped: 0, sessions: 0 }
+    }
+}
+
+impl HandUsage {
+    pub fn total_keys(&self) -> usize { self.fingers.iter().map(|f| f.keys).sum() }
+    pub fn stat(&self, f: Finger) -> &FingerStat { &self.fingers[Finger::ALL.iter().position(|&x| x == f).unwrap_or(0)] }
+    /// Share of all mapped keys typed by `f`.
+    pub fn load(&self, f: Finger) -> f64 {
+        let total = self.total_keys();
+        if total == 0 { 0.0 } else { self.stat(f).keys as f64 / total as f64 }
+    }
+    /// Share of keys typed by the left hand.
+    pub fn left_share(&self) -> Option<f64> {
+        let total = self.total_keys();
+        (total > 0).then(|| self.fingers.iter().filter(|f| f.finger.is_left()).map(|f| f.keys).sum::<usize>() as f64 / total as f64)
+    }
+    /// Hand alternation ratio of the key pairs.
+    pub fn alternation(&self) -> Option<f64> { (self.pairs > 0).then(|| self.alternations as f64 / self.pairs as f64) }
+    pub fn same_finger_per_session(&self) -> f64 { if self.sessions == 0 { 0.0 } else { self.same_finger as f64 / self.sessions as f64 } }
+    pub fn add(&mut self, o: &HandUsage) {
+        for (a, b) in self.fingers.iter_mut().zip(&o.fingers) {
+            a.keys += b.keys; a.misses += b.misses; a.time += b.time; a.timed += b.timed;
+        }
+        for (k, n) in &o.keys { *self.keys.entry(*k).or_insert(0) += n; }
+        self.pairs += o.pairs;
+        self.alternations += o.alternations;
+        self.same_finger += o.same_finger;
+        self.unmapped += o.unmapped;
+        self.sessions += o.sessions;
+    }
+}
+
+/// Finger usage of one replay. `penalty` is the time added to each miss event.
+pub fn analyze(evs: &[KeyEv], map: &FingerMap, penalty: f64) -> HandUsage {
+    let mut u = HandUsage { sessions: 1, ..Default::default() };
+    let mut prev: Option<(char, Finger)> = None;
+    for (i, e) in evs.iter().enumerate() {
+        if i > 0 && evs[i - 1].w != e.w { prev = None; }
+        let c = e.c.chars().next().unwrap_or(' ').to_ascii_lowercase();
+        let Some(f) = map.finger(c) else {
+            u.unmapped += 1;
+            if e.ok { prev = None; }
+            continue;
+        };
+        let idx = Finger::ALL.iter().position(|&x| x == f).unwrap_or(0);
+        let st = &mut u.fingers[idx];
+        st.keys += 1;
+        if !e.ok { st.misses += 1; }
+        if i > 0 && evs[i - 1].w == e.w {
+            st.time += (e.t - evs[i - 1].t - if e.ok { 0.0 } else { penalty }).max(0.0);
+            st.timed += 1;
+        }
+        *u.keys.entry(c).or_insert(0) += 1;
+        if !e.ok { continue; }
+        if let Some((pc, pf)) = prev {
+            u.pairs += 1;
+            if pf.is_left() != f.is_left() { u.alternations += 1; }
+            if pf == f && pc != c { u.same_finger += 1; }
+        }
+        prev = Some((c, f));
+    }
+    u
+}
+
+/// Finger usage of one record (None without replay).
+pub fn of_record(rec: &ScoreRecord, map: &FingerMap) -> Option<HandUsage> {
+    Some(analyze(rec.replay.as_deref()?, map, rec.miss_penalty()))
+}
+
+/// Usage summed over the records of `mode` with replays, and that of the newest one.
+pub fn over_history(history: &[ScoreRecord], mode: &str, map: &FingerMap) -> (HandUsage, Option<HandUsage>) {
+    let mut total = HandUsage::default();
+    let mut latest: Option<(&str, HandUsage)> = None;
+    for r in history.iter().filter(|r| r.mode == mode) {
+        let Some(u) = of_record(r, map) else { continue };
+        total.add(&u);
+        if latest.as_ref().is_none_or(|(dt, _)| r.datetime.as_str() > *dt) { latest = Some((&r.datetime, u)); }
+    }
+    (total, latest.map(|l| l.1))
+}
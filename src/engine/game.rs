@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::fs;
 use std::path::Path;
 use std::time::{Duration, Instant};
@@ -6,13 +7,21 @@ use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use serde::{Deserialize, Serialize};
 
+use super::difficulty::{self, Grade};
 use super::romaji::{RomajiMatcher, RomajiRules};
 use super::stats::compute_wpm_stats;
 use rand::seq::SliceRandom;
 use crate::store::json::KeyEv;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WordEntry { pub jp: String, pub romas: Vec<String> }
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WordEntry {
+    pub jp: String,
+    pub romas: Vec<String>,
+    /// Relative sampling weight (e.g. word frequency); 1.0 when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub weight: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub difficulty: Option<f64>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct WordsFile { pub title: String, pub version: u32, pub entries: Vec<WordEntry> }
@@ -20,6 +29,11 @@ pub struct WordsFile { pub title: String, pub version: u32, pub entries: Vec<Wor
 #[derive(Debug, Clone)]
 pub struct Split { pub word: String, pub sec: f64, pub miss: u32, pub keystrokes: u32 }
 
+pub const MODE_BASIC: &str = "basic_common";
+pub const MODE_LONG_TEXT: &str = "long_text";
+/// One-word test from the dictionary editor; never recorded.
+pub const MODE_TRY: &str = "try";
+
 #[derive(Debug, Clone)]
 pub struct GameConfig {
     pub time_limit_sec: f64,
@@ -27,9 +41,69 @@ pub struct GameConfig {
     pub loss_ms_per_miss: u64,
     pub fixed_chars: bool,
     pub target_chars: usize,
+    /// Stored in the record; rankings are kept per mode.
+    pub mode: String,
+    pub session: SessionOptions,
 }
 impl Default for GameConfig {
-    fn default() -> Self { Self { time_limit_sec: 60.0, max_words: 50, loss_ms_per_miss: 200, fixed_chars: false, target_chars: 0 } }
+    fn default() -> Self { Self { time_limit_sec: 60.0, max_words: 50, loss_ms_per_miss: 200, fixed_chars: false, target_chars: 0, mode: MODE_BASIC.into(), session: SessionOptions::default() } }
+}
+
+/// Word selection options for a session. Stored in the record so results stay comparable.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct SessionOptions {
+    /// Sample by `WordEntry::weight` instead of a uniform shuffle
+    #[serde(default)] pub weighted: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub include_tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub exclude_tags: Vec<String>,
+    /// Keystroke length bounds (shortest romaji)
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub min_keys: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub max_keys: Option<u32>,
+    /// A word does not appear again within this many words
+    #[serde(default)] pub no_repeat_within: u32,
+    /// Keep only the easy/normal/hard third of the dictionary
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub grade: Option<Grade>,
+}
+
+impl SessionOptions {
+    pub fn is_default(&self) -> bool { *self == Self::default() }
+
+    pub fn accepts(&self, e: &WordEntry) -> bool {
+        let len = min_roma_len(e) as u32;
+        if let Some(min) = self.min_keys { if len < min { return false; } }
+        if let Some(max) = self.max_keys { if len > max { return false; } }
+        if !self.include_tags.is_empty() && !e.tags.iter().any(|t| self.include_tags.contains(t)) { return false; }
+        !e.tags.iter().any(|t| self.exclude_tags.contains(t))
+    }
+
+    /// Short one-line description for result screens.
+    pub fn summary(&self) -> String {
+        if self.is_default() { return "標準".into(); }
+        let mut parts: Vec<String> = Vec::new();
+        if self.weighted { parts.push("重み付き".into()); }
+        if !self.include_tags.is_empty() { parts.push(format!("+{}", self.include_tags.join(","))); }
+        if !self.exclude_tags.is_empty() { parts.push(format!("-{}", self.exclude_tags.join(","))); }
+        if self.min_keys.is_some() || self.max_keys.is_some() {
+            let lo = self.min_keys.map(|v| v.to_string()).unwrap_or_default();
+            let hi = self.max_keys.map(|v| v.to_string()).unwrap_or_default();
+            parts.push(format!("{}..{}打", lo, hi));
+        }
+        if self.no_repeat_within > 0 { parts.push(format!("重複なし{}語", self.no_repeat_within)); }
+        if let Some(g) = self.grade { parts.push(format!("難度:{}", g.label())); }
+        parts.join(" ")
+    }
+}
+
+pub fn filter_words(all: &[WordEntry], opts: &SessionOptions) -> Vec<WordEntry> {
+    // grades are terciles of the whole dictionary, not of the filtered pool
+    let bounds = opts.grade.map(|_| difficulty::grade_bounds(all));
+    all.iter()
+        .filter(|e| opts.accepts(e))
+        .filter(|e| match (opts.grade, bounds) {
+            (Some(g), Some(b)) => difficulty::grade_of(difficulty::score(e, None), b) == g,
+            _ => true,
+        })
+        .cloned().collect()
 }
 
 pub fn load_words_json(path: &Path) -> Result<Vec<WordEntry>> {
@@ -50,24 +124,70 @@ fn min_roma_len(e: &WordEntry) -> usize {
     e.romas.iter().map(|s| s.len()).min().unwrap_or(0)
 }
 
-fn build_session_words(all: &[WordEntry], _rules: &RomajiRules, target_chars: usize) -> Vec<WordEntry> {
-    if all.is_empty() { return vec![]; }
+/// Sample a session from the filtered pool; `target_chars == 0` draws as many words as the pool has.
+fn build_session_words(all: &[WordEntry], _rules: &RomajiRules, target_chars: usize, opts: &SessionOptions) -> Vec<WordEntry> {
+    let mut pool: Vec<WordEntry> = filter_words(all, opts);
+    if pool.is_empty() { return vec![]; }
     let mut rng = rand::thread_rng();
-    let mut pool: Vec<WordEntry> = all.to_vec();
+    if opts.weighted { return weighted_session_words(&pool, target_chars, opts.no_repeat_within as usize, &mut rng); }
     pool.shuffle(&mut rng);
     if target_chars == 0 { return pool; }
     let mut out: Vec<WordEntry> = Vec::new();
     let mut sum = 0usize;
     // cycle through shuffled pool until reaching/exceeding target_chars
+    // (a word repeats only after the whole pool, so no_repeat_within < pool size always holds)
     for e in pool.iter().cycle() {
         let len = min_roma_len(e);
         out.push(e.clone());
         sum += len;
-        if sum >= target_chars || out.len() > all.len()*3 { break; }
+        if sum >= target_chars || out.len() > pool.len()*3 { break; }
     }
     out
 }
 
+fn weighted_session_words(pool: &[WordEntry], target_chars: usize, no_repeat_within: usize, rng: &mut impl rand::Rng) -> Vec<WordEntry> {
+    use rand::distributions::{Distribution, WeightedIndex};
+    let weights: Vec<f64> = pool.iter().map(|e| e.weight.unwrap_or(1.0).max(0.0)).collect();
+    let dist = WeightedIndex::new(&weights).ok();
+    // a window as large as the pool could never be satisfied
+    let window = no_repeat_within.min(pool.len().saturating_sub(1));
+    let mut recent: VecDeque<usize> = VecDeque::with_capacity(window + 1);
+    let mut out: Vec<WordEntry> = Vec::new();
+    let mut sum = 0usize;
+    let limit = if target_chars == 0 { pool.len() } else { target_chars };
+    while out.len() < limit {
+        let mut pick = None;
+        for _ in 0..32 {
+            let i = match &dist { Some(d) => d.sample(rng), None => rng.gen_range(0..pool.len()) };
+            if !recent.contains(&i) { pick = Some(i); break; }
+        }
+        // unlucky draws: fall back to any word outside the window
+        let Some(i) = pick
+            .or_else(|| (0..pool.len()).find(|i| !recent.contains(i) && weights[*i] > 0.0))
+            .or_else(|| (0..pool.len()).find(|i| !recent.contains(i))) else { break; };
+        if window > 0 {
+            recent.push_back(i);
+            if recent.len() > window { recent.pop_front(); }
+        }
+        out.push(pool[i].clone());
+        sum += min_roma_len(&pool[i]);
+        if target_chars > 0 && sum >= target_chars { break; }
+    }
+    out
+}
+
+fn select_words(cfg: &GameConfig, words: Vec<WordEntry>, rules: &RomajiRules) -> Result<Vec<WordEntry>> {
+    let had_words = !words.is_empty();
+    // long text / 試し打ち type the given sequence as is
+    let ordered = cfg.mode == MODE_LONG_TEXT || cfg.mode == MODE_TRY;
+    let mut words_sel = if ordered { filter_words(&words, &cfg.session) }
+        else if cfg.fixed_chars && cfg.target_chars > 0 { build_session_words(&words, rules, cfg.target_chars, &cfg.session) }
+        else { build_session_words(&words, rules, 0, &cfg.session) };
+    if had_words && words_sel.is_empty() { anyhow::bail!("出題条件に合う語がありません（{}）", cfg.session.summary()); }
+    if !words_sel.is_empty() && !cfg.fixed_chars { words_sel.truncate(cfg.max_words.min(words_sel.len())); }
+    Ok(words_sel)
+}
+
 pub struct Game {
     pub words: Vec<WordEntry>,
     idx: usize,
@@ -90,13 +210,14 @@ pub struct Game {
     // Stores the romaji variant actually typed for each word (if completed)
     display_romas: Vec<Option<String>>,
     last_feedback: KeyFeedback,
+    /// Fixed elapsed time while playing back a replay (see `set_clock`)
+    clock: Option<f64>,
 }
 
 impl Game {
     pub fn new(cfg: GameConfig, words: Vec<WordEntry>, rules_path: &Path) -> Result<Self> {
         let rules = RomajiRules::from_yaml_file(rules_path)?;
-        let mut words_sel = if cfg.fixed_chars && cfg.target_chars > 0 { build_session_words(&words, &rules, cfg.target_chars) } else { words };
-        if !words_sel.is_empty() && !cfg.fixed_chars { words_sel.truncate(cfg.max_words.min(words_sel.len())); }
+        let words_sel = select_words(&cfg, words, &rules)?;
         let dr_len = words_sel.len();
         Ok(Self{
             words: words_sel,
@@ -118,12 +239,12 @@ impl Game {
             replay: vec![],
             display_romas: vec![None; dr_len],
             last_feedback: KeyFeedback::None,
+            clock: None,
         })
     }
 
     pub fn new_with_rules(cfg: GameConfig, words: Vec<WordEntry>, rules: RomajiRules) -> Result<Self> {
-        let mut words_sel = if cfg.fixed_chars && cfg.target_chars > 0 { build_session_words(&words, &rules, cfg.target_chars) } else { words };
-        if !words_sel.is_empty() && !cfg.fixed_chars { words_sel.truncate(cfg.max_words.min(words_sel.len())); }
+        let words_sel = select_words(&cfg, words, &rules)?;
         let dr_len = words_sel.len();
         Ok(Self{
             words: words_sel,
@@ -145,9 +266,25 @@ impl Game {
             replay: vec![],
             display_romas: vec![None; dr_len],
             last_feedback: KeyFeedback::None,
+            clock: None,
         })
     }
 
+    /// A game over exactly `words` (no sampling), driven by a recorded replay.
+    pub fn for_replay(mode: &str, words: Vec<WordEntry>, rules: RomajiRules) -> Self {
+        let cfg = GameConfig { mode: mode.into(), max_words: words.len(), time_limit_sec: f64::INFINITY, ..Default::default() };
+        let n = words.len();
+        Self {
+            words, idx: 0, typed: String::new(), correct_keystrokes: 0, miss: 0, splits: vec![],
+            started_at: None, ended_at: None, word_start: None, finished: false, aborted: false,
+            rules, matcher: None, speed_series: vec![], cfg, last_miss_char: None, replay: vec![],
+            display_romas: vec![None; n], last_feedback: KeyFeedback::None, clock: None,
+        }
+    }
+
+    /// Pin the elapsed time (replay playback); keystrokes are then stamped with it.
+    pub fn set_clock(&mut self, t: f64) { self.clock = Some(t); }
+
     pub fn start(&mut self) {
         // 計測は最初の打鍵で開始するため、ここでは開始しない
         self.started_at = None;
@@ -257,8 +394,9 @@ impl Game {
         let time_sec = self.elapsed_secs();
         let timeloss_sec = (self.cfg.loss_ms_per_miss as f64 * self.miss as f64)/1000.0;
         let (wpm_top, wpm_worst) = compute_wpm_stats(&self.splits);
-        crate::store::json::ScoreRecord {
-            mode: "basic_common".into(),
+        let mut rec = crate::store::json::ScoreRecord {
+            id: crate::store::json::new_id(),
+            mode: self.cfg.mode.clone(),
             datetime: chrono::Local::now().to_rfc3339(),
             time_sec,
             miss: self.miss,
@@ -267,13 +405,25 @@ impl Game {
             wpm_top, wpm_worst,
             rank: super::level::estimate_rank(self.avg_cps()).to_string(),
             memo: None,
-            speed_series: Some(self.speed_series.clone()),
+            // the curve is rebuilt from the replay (store::replay::speed_series)
+            speed_series: if self.replay.is_empty() { Some(self.speed_series.clone()) } else { None },
             word_display: None,
             replay: if self.replay.is_empty() { None } else { Some(self.replay.clone()) },
-        }
+            word_seq: self.words.clone(),
+            rules_version: Some(self.rules.version()),
+            session: if self.cfg.session.is_default() { None } else { Some(self.cfg.session.clone()) },
+            aborted: self.aborted(),
+            pinned: false,
+            rhythm: None,
+            hash: None,
+        };
+        rec.rhythm = rec.compute_rhythm();
+        rec.seal();
+        rec
     }
 
     pub fn elapsed_secs(&self) -> f64 {
+        if let Some(t) = self.clock { return t; }
         match (self.started_at, self.ended_at) {
             (Some(start), Some(end)) => end.saturating_duration_since(start).as_secs_f64(),
             (Some(start), None) => start.elapsed().as_secs_f64(),
@@ -362,6 +512,7 @@ impl Game {
     pub fn last_miss_char(&self) -> Option<char> { self.last_miss_char }
     pub fn current_typed_total(&self) -> u32 { self.correct_keystrokes }
     pub fn aborted(&self) -> bool { self.aborted }
+    pub fn mode(&self) -> &str { &self.cfg.mode }
 
     fn push_ev(&mut self, c: char, ok: bool) {
         let t = self.elapsed_secs();
@@ -0,0 +1,105 @@
+// Kana -> romaji conversion for generated word entries (imports etc.).
+// Produces the same base spelling style as data/words (shi/chi/tsu/ji/fu, "-" for ー);
+// the matcher adds the usual yure variants on top.
+
+/// Convert katakana to hiragana (other chars are kept as-is).
+pub fn to_hiragana(s: &str) -> String {
+    s.chars().map(|c| {
+        let u = c as u32;
+        if (0x30A1..=0x30F6).contains(&u) { char::from_u32(u - 0x60).unwrap_or(c) } else { c }
+    }).collect()
+}
+
+fn is_small_y(c: char) -> bool { matches!(c, 'ゃ'|'ゅ'|'ょ'|'ぁ'|'ぃ'|'ぅ'|'ぇ'|'ぉ') }
+
+/// Split kana into mora units (きゃ, っ, ん, ー are each one unit).
+pub fn split_mora(s: &str) -> Vec<String> {
+    let chars: Vec<char> = to_hiragana(s).chars().collect();
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if i + 1 < chars.len() && is_small_y(chars[i+1]) && mora_roma(&format!("{}{}", c, chars[i+1])).is_some() {
+            out.push(format!("{}{}", c, chars[i+1]));
+            i += 2; continue;
+        }
+        out.push(c.to_string());
+        i += 1;
+    }
+    out
+}
+
+/// Base romaji of one mora (None for っ, ん and characters without a reading).
+pub fn mora_roma(m: &str) -> Option<&'static str> {
+    let r = match m {
+        "あ"=>"a","い"=>"i","う"=>"u","え"=>"e","お"=>"o",
+        "か"=>"ka","き"=>"ki","く"=>"ku","け"=>"ke","こ"=>"ko",
+        "さ"=>"sa","し"=>"shi","す"=>"su","せ"=>"se","そ"=>"so",
+        "た"=>"ta","ち"=>"chi","つ"=>"tsu","て"=>"te","と"=>"to",
+        "な"=>"na","に"=>"ni","ぬ"=>"nu","ね"=>"ne","の"=>"no",
+        "は"=>"ha","ひ"=>"hi","ふ"=>"fu","へ"=>"he","ほ"=>"ho",
+        "ま"=>"ma","み"=>"mi","む"=>"mu","め"=>"me","も"=>"mo",
+        "や"=>"ya","ゆ"=>"yu","よ"=>"yo",
+        "ら"=>"ra","り"=>"ri","る"=>"ru","れ"=>"re","ろ"=>"ro",
+        "わ"=>"wa","ゐ"=>"wi","ゑ"=>"we","を"=>"wo",
+        "が"=>"ga","ぎ"=>"gi","ぐ"=>"gu","げ"=>"ge","ご"=>"go",
+        "ざ"=>"za","じ"=>"ji","ず"=>"zu","ぜ"=>"ze","ぞ"=>"zo",
+        "だ"=>"da","ぢ"=>"di","づ"=>"du","で"=>"de","ど"=>"do",
+        "ば"=>"ba","び"=>"bi","ぶ"=>"bu","べ"=>"be","ぼ"=>"bo",
+        "ぱ"=>"pa","ぴ"=>"pi","ぷ"=>"pu","ぺ"=>"pe","ぽ"=>"po",
+        "ゔ"=>"vu",
+        "きゃ"=>"kya","きゅ"=>"kyu","きょ"=>"kyo",
+        "しゃ"=>"sha","しゅ"=>"shu","しぇ"=>"she","しょ"=>"sho",
+        "ちゃ"=>"cha","ちゅ"=>"chu","ちぇ"=>"che","ちょ"=>"cho",
+        "にゃ"=>"nya","にゅ"=>"nyu","にょ"=>"nyo",
+        "ひゃ"=>"hya","ひゅ"=>"hyu","ひょ"=>"hyo",
+        "みゃ"=>"mya","みゅ"=>"myu","みょ"=>"myo",
+        "りゃ"=>"rya","りゅ"=>"ryu","りょ"=>"ryo",
+        "ぎゃ"=>"gya","ぎゅ"=>"gyu","ぎょ"=>"gyo",
+        "じゃ"=>"ja","じゅ"=>"ju","じぇ"=>"je","じょ"=>"jo",
+        "びゃ"=>"bya","びゅ"=>"byu","びょ"=>"byo",
+        "ぴゃ"=>"pya","ぴゅ"=>"pyu","ぴょ"=>"pyo",
+        "ふぁ"=>"fa","ふぃ"=>"fi","ふぇ"=>"fe","ふぉ"=>"fo",
+        "てぃ"=>"thi","でぃ"=>"dhi","とぅ"=>"twu","どぅ"=>"dwu",
+        "うぃ"=>"wi","うぇ"=>"we","うぉ"=>"who","ゔぁ"=>"va","ゔぃ"=>"vi","ゔぇ"=>"ve","ゔぉ"=>"vo",
+        "ぁ"=>"xa","ぃ"=>"xi","ぅ"=>"xu","ぇ"=>"xe","ぉ"=>"xo",
+        "ゃ"=>"xya","ゅ"=>"xyu","ょ"=>"xyo","ゎ"=>"xwa",
+        "ー"=>"-","、"=>",","。"=>".","！"=>"!","？"=>"?","「"=>"[","」"=>"]",
+        "・"=>"/","〜"=>"~","～"=>"~",
+        _ => return None,
+    };
+    Some(r)
+}
+
+/// Convert a kana reading to a base romaji spelling.
+/// Returns None when the text contains characters without a reading (e.g. kanji).
+pub fn kana_to_romaji(s: &str) -> Option<String> {
+    let moras = split_mora(s);
+    let mut out = String::new();
+    for (i, m) in moras.iter().enumerate() {
+        let next = moras.get(i+1).and_then(|n| mora_roma(n));
+        match m.as_str() {
+            "っ" => {
+                // 促音: double the next consonant, otherwise type it standalone
+                match next.and_then(|n| n.chars().next()) {
+                    Some(c) if c.is_ascii_alphabetic() && !matches!(c, 'a'|'i'|'u'|'e'|'o'|'n') => out.push(c),
+                    _ => out.push_str("xtu"),
+                }
+            }
+            "ん" => {
+                // 撥音: 母音・y・n の直前と語末は nn、それ以外は n
+                let single = matches!(next.and_then(|n| n.chars().next()), Some(c) if c.is_ascii_alphabetic() && !matches!(c, 'a'|'i'|'u'|'e'|'o'|'y'|'n'));
+                out.push_str(if single { "n" } else { "nn" });
+            }
+            " " | "　" => {}
+            other => {
+                let c = other.chars().next().unwrap_or(' ');
+                if let Some(r) = mora_roma(other) { out.push_str(r); }
+                else if c.is_ascii_alphanumeric() { out.push(c.to_ascii_lowercase()); }
+                else if ('０'..='９').contains(&c) { out.push(char::from_u32(c as u32 - 0xFEE0).unwrap_or(c)); }
+                else { return None; }
+            }
+        }
+    }
+    Some(out)
+}
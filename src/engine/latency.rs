@@ -0,0 +1,204 @@
+// Where the time of a run went: the first-key reaction of each word (初速), the time
+// spent on each mora and the pauses inside words. The correct keystrokes of a word are
+// split into morae by matching them against the usual spellings of each mora.
+
+use crate::store::json::{KeyEv, ScoreRecord};
+
+use super::kana;
+
+/// Pauses inside a word longer than this are hesitations (see `AppConfig::hesitation_ms`).
+pub const DEFAULT_HESITATION_MS: u64 = 400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MoraKind { Plain, Voiced, Youon, Sokuon, Hatsuon, Chouon, Other }
+
+impl MoraKind {
+    pub fn of(m: &str) -> Self {
+        let mut cs = m.chars();
+        let c = cs.next().unwrap_or(' ');
+        if cs.next().is_some() { return Self::Youon; }
+        match c {
+            'っ' => Self::Sokuon,
+            'ん' => Self::Hatsuon,
+            'ー' => Self::Chouon,
+            c if "がぎぐげござじずぜぞだぢづでどばびぶべぼぱぴぷぺぽゔ".contains(c) => Self::Voiced,
+            'ぁ'..='ゖ' => Self::Plain,
+            _ => Self::Other,
+        }
+    }
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Plain => "清音", Self::Voiced => "濁音・半濁音", Self::Youon => "拗音 ゃゅょ", Self::Sokuon => "促音 っ",
+            Self::Hatsuon => "撥音 ん", Self::Chouon => "長音 ー", Self::Other => "記号・英数",
+        }
+    }
+}
+
+/// Time spent on one mora: from the previous correct key of the word to its own last key
+/// (the reaction before a word's first key is counted in `Analysis::first_key` instead,
+/// so the first mora only counts from its second key).
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoraTime { pub word: usize, pub mora: String, pub kind: MoraKind, pub sec: f64, pub keys: usize }
+
+/// A pause inside a word; `ev` is the keystroke that ended it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hesitation { pub ev: usize, pub word: usize, pub mora: String, pub gap: f64 }
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KindCost { pub kind: MoraKind, pub count: usize, pub total: f64, pub mean: f64 }
+
+#[derive(Debug, Clone, Default)]
+pub struct Analysis {
+    /// Per word: previous word's last key (or the start) to the first correct key
+    pub first_key: Vec<f64>,
+    pub moras: Vec<MoraTime>,
+    pub hesitations: Vec<Hesitation>,
+    /// Completed words whose keys could not be split into morae (kanji, unknown spellings)
+    pub unsegmented: usize,
+}
+
+impl Analysis {
+    pub fn mean_first_key(&self) -> f64 {
+        if self.first_key.is_empty() { 0.0 } else { self.first_key.iter().sum::<f64>() / self.first_key.len() as f64 }
+    }
+    /// Mean time per mora of all morae.
+    pub fn mean_mora(&self) -> f64 {
+        if self.moras.is_empty() { 0.0 } else { self.moras.iter().map(|m| m.sec).sum::<f64>() / self.moras.len() as f64 }
+    }
+    /// Mora kinds by mean time, slowest first.
+    pub fn by_kind(&self) -> Vec<KindCost> {
+        let mut out: Vec<KindCost> = Vec::new();
+        for m in &self.moras {
+            match out.iter_mut().find(|k| k.kind == m.kind) {
+                Some(k) => { k.count += 1; k.total += m.sec; }
+                None => out.push(KindCost { kind: m.kind, count: 1, total: m.sec, mean: 0.0 }),
+            }
+        }
+        for k in &mut out { k.mean = k.total / k.count as f64; }
+        out.sort_by(|a, b| b.mean.partial_cmp(&a.mean).unwrap_or(std::cmp::Ordering::Equal));
+        out
+    }
+    /// Slowest single morae.
+    pub fn slowest(&self, n: usize) -> Vec<&MoraTime> {
+        let mut v: Vec<&MoraTime> = self.moras.iter().collect();
+        v.sort_by(|a, b| b.sec.partial_cmp(&a.sec).unwrap_or(std::cmp::Ordering::Equal));
+        v.truncate(n);
+        v
+    }
+    pub fn is_hesitation(&self, ev: usize) -> bool { self.hesitations.iter().any(|h| h.ev == ev) }
+}
+
+/// Spellings accepted for mora `m` (`next` = the following mora, for っ).
+fn spellings(m: &str, next: Option<&str>) -> Vec<String> {
+    match m {
+        "っ" => {
+            let mut v: Vec<String> = next.map(|n| spellings(n, None)).unwrap_or_default().into_iter()
+                .filter_map(|s| s.chars().next()).filter(|c| c.is_ascii_alphabetic() && !"aiueon".contains(*c))
+                .map(String::from).collect();
+            v.dedup();
+            v.extend(["xtu", "ltu", "xtsu", "ltsu"].map(String::from));
+            v
+        }
+        "ん" => ["nn", "n'", "xn", "n"].map(String::from).to_vec(),
+        " " | "　" => vec![String::new()],
+        _ => {
+            let Some(base) = kana::mora_roma(m) else {
+                let c = m.chars().next().unwrap_or(' ');
+                return if c.is_ascii_alphanumeric() || c.is_ascii_punctuation() { vec![c.to_ascii_lowercase().to_string()] } else { Vec::new() };
+            };
+            let mut v = vec![base.to_string()];
+            let alt: &[(&str, &[&str])] = &[
+                ("shi", &["si", "ci"]), ("chi", &["ti"]), ("tsu", &["tu"]), ("ji", &["zi"]), ("fu", &["hu"]),
+                ("sha", &["sya"]), ("shu", &["syu"]), ("sho", &["syo"]), ("she", &["sye"]),
+                ("cha", &["tya", "cya"]), ("chu", &["tyu", "cyu"]), ("cho", &["tyo", "cyo"]), ("che", &["tye", "cye"]),
+                ("ja", &["jya", "zya"]), ("ju", &["jyu", "zyu"]), ("jo", &["jyo", "zyo"]), ("je", &["jye", "zye"]),
+                ("ka", &["ca"]), ("ku", &["cu", "qu"]), ("ko", &["co"]), ("se", &["ce"]),
+                ("xa", &["la"]), ("xi", &["li"]), ("xu", &["lu"]), ("xe", &["le"]), ("xo", &["lo"]),
+                ("xya", &["lya"]), ("xyu", &["lyu"]), ("xyo", &["lyo"]), ("xwa", &["lwa"]),
+            ];
+            if let Some((_, a)) = alt.iter().find(|(b, _)| *b == base) { v.extend(a.iter().map(|s| s.to_string())); }
+            v
+        }
+    }
+}
+
+/// Split the romaji typed for `jp` into its morae: (mora, number of keys).
+/// None when the keys do not spell the word with the known spellings.
+pub fn segment(jp: &str, typed: &str) -> Option<Vec<(String, usize)>> {
+    let moras = kana::split_mora(jp);
+    let spell: Vec<Vec<String>> = moras.iter().enumerate().map(|(i, m)| spellings(m, moras.get(i + 1).map(|s| s.as_str()))).collect();
+    fn go(i: usize, rest: &str, spell: &[Vec<String>], out: &mut Vec<usize>) -> bool {
+        if i == spell.len() { return rest.is_empty(); }
+        for s in &spell[i] {
+            if rest.starts_with(s.as_str()) {
+                out.push(s.len());
+                if go(i + 1, &rest[s.len()..], spell, out) { return true; }
+                out.pop();
+            }
+        }
+        false
+    }
+    let mut lens = Vec::with_capacity(moras.len());
+    go(0, &typed.to_ascii_lowercase(), &spell, &mut lens).then(|| moras.into_iter().zip(lens).collect())
+}
+
+/// Whether `s` is the beginning of some usual spelling of `jp` (the spellings of `segment`,
+/// which are looser than a strict rules file).
+pub fn is_spelling_prefix(jp: &str, s: &str) -> bool {
+    let moras = kana::split_mora(jp);
+    let spell: Vec<Vec<String>> = moras.iter().enumerate().map(|(i, m)| spellings(m, moras.get(i + 1).map(|s| s.as_str()))).collect();
+    fn go(i: usize, rest: &str, spell: &[Vec<String>]) -> bool {
+        if rest.is_empty() { return true; }
+        let Some(ss) = spell.get(i) else { return false };
+        ss.iter().any(|sp| if rest.len() <= sp.len() { sp.starts_with(rest) } else { rest.starts_with(sp.as_str()) && go(i + 1, &rest[sp.len()..], spell) })
+    }
+    go(0, &s.to_ascii_lowercase(), &spell)
+}
+
+/// Analyse a record's replay (None without replay or word sequence).
+pub fn analyze(rec: &ScoreRecord, hesitation_sec: f64) -> Option<Analysis> {
+    let evs = rec.replay.as_deref()?;
+    if rec.word_seq.is_empty() { return None; }
+    let mut a = Analysis::default();
+    // a miss event's time includes the penalty; that is not a pause
+    let penalty = rec.miss_penalty();
+    let (mut word_start, mut i) = (0.0f64, 0usize);
+    while i < evs.len() {
+        let w = evs[i].w;
+        let end = evs[i..].iter().position(|e| e.w != w).map(|n| i + n).unwrap_or(evs.len());
+        let word: &[KeyEv] = &evs[i..end];
+        let oks: Vec<usize> = (i..end).filter(|&j| evs[j].ok).collect();
+        if let Some(&first) = oks.first() { a.first_key.push(evs[first].t - word_start); }
+        // the word is done when its keys spell the whole word (the last one of a fixed-keystroke run may not be)
+        let typed: String = oks.iter().map(|&j| evs[j].c.as_str()).collect();
+        let moras = rec.word_seq.get(w).and_then(|e| segment(&e.jp, &typed));
+        if moras.is_none() && w < rec.splits.len() { a.unsegmented += 1; }
+        // mora of each correct key (by position in the word)
+        let mut key_mora: Vec<usize> = Vec::with_capacity(oks.len());
+        if let Some(ms) = &moras {
+            for (k, (_, n)) in ms.iter().enumerate() { key_mora.extend(std::iter::repeat_n(k, *n)); }
+            // the first key's reaction time belongs to 初速, so a one-key first mora has no time of its own
+            let mut prev = oks.first().map(|&j| evs[j].t).unwrap_or(word_start);
+            let mut pos = 0usize;
+            for (m, n) in ms {
+                if *n == 0 { continue; }
+                let last = oks[pos + n - 1];
+                if pos > 0 || *n > 1 { a.moras.push(MoraTime { word: w, mora: m.clone(), kind: MoraKind::of(m), sec: evs[last].t - prev, keys: *n }); }
+                prev = evs[last].t;
+                pos += n;
+            }
+        }
+        for (k, e) in word.iter().enumerate().skip(1) {
+            let gap = e.t - word[k - 1].t - if e.ok { 0.0 } else { penalty };
+            if gap <= hesitation_sec { continue; }
+            let j = i + k;
+            // the mora being typed: the one of the next correct key
+            let mora = oks.iter().position(|&o| o >= j).and_then(|p| key_mora.get(p))
+                .and_then(|&mi| moras.as_ref().map(|ms| ms[mi].0.clone())).unwrap_or_default();
+            a.hesitations.push(Hesitation { ev: j, word: w, mora, gap });
+        }
+        word_start = evs[end - 1].t;
+        i = end;
+    }
+    Some(a)
+}
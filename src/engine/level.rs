@@ -1,3 +1,6 @@
+/// Ranks from slowest to fastest (the values `estimate_rank` returns).
+pub const RANKS: [&str; 11] = ["F", "E", "D", "C", "B", "A", "SB", "SA", "XC", "XD", "XS"];
+
 pub fn estimate_rank(cps: f64) -> &'static str {
     // Rough thresholds (keys per second) mapped to TypeWell-like ranks
     if cps >= 13.0 { "XS" }
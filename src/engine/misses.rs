@@ -0,0 +1,125 @@
+// What kind of mistake each miss was. The replay is re-typed through RomajiMatcher (as in
+// `verify`), and each wrong key is compared with the next character of the romaji the play
+// screen was showing at that moment.
+
+use std::cmp::Reverse;
+
+use crate::store::json::ScoreRecord;
+use crate::util::keymap;
+
+use super::latency;
+use super::romaji::{InputResult, RomajiMatcher, RomajiRules};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MissKind {
+    /// The key just typed, pressed again
+    Doubled,
+    /// A spelling of the kana that the current rules do not accept (e.g. a single n before a vowel)
+    Variant,
+    /// The following key typed first, then the expected one (teh → the)
+    Transposition,
+    /// A key a little further ahead in the word
+    EarlyFinger,
+    /// A neighbour of the expected key
+    Adjacent,
+    Other,
+}
+
+impl MissKind {
+    pub const ALL: [MissKind; 6] = [Self::Doubled, Self::Variant, Self::Transposition, Self::EarlyFinger, Self::Adjacent, Self::Other];
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Doubled => "二度打ち", Self::Transposition => "入れ替わり", Self::Variant => "表記ゆれ",
+            Self::EarlyFinger => "先走り", Self::Adjacent => "隣のキー", Self::Other => "その他",
+        }
+    }
+}
+
+/// One classified miss; `ev` is its index in the replay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Miss { pub ev: usize, pub word: usize, pub jp: String, pub expected: Option<char>, pub typed: char, pub kind: MissKind }
+
+/// Misses with the same kind, expected and typed key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern { pub kind: MissKind, pub expected: Option<char>, pub typed: char, pub count: usize, pub examples: Vec<String> }
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub misses: usize,
+    /// Sessions the misses were taken from
+    pub sessions: usize,
+    /// Misses per kind, most frequent first (kinds that never occurred are left out)
+    pub by_kind: Vec<(MissKind, usize)>,
+    /// Most frequent first
+    pub patterns: Vec<Pattern>,
+}
+
+/// Example words kept per pattern.
+const EXAMPLES: usize = 3;
+
+fn classify_one(jp: &str, shown: &str, typed: &str, c: char, prev: Option<(char, bool)>, next: Option<(char, bool)>) -> MissKind {
+    let pos = typed.len();
+    let ahead: Vec<char> = shown.chars().skip(pos).take(4).collect();
+    let expected = ahead.first().copied();
+    if prev == Some((c, true)) { return MissKind::Doubled; }
+    if latency::is_spelling_prefix(jp, &format!("{}{}", typed, c)) { return MissKind::Variant; }
+    if ahead.get(1) == Some(&c) && next.is_some_and(|(n, ok)| ok && Some(n) == expected) { return MissKind::Transposition; }
+    if ahead.iter().skip(1).any(|&a| a == c) { return MissKind::EarlyFinger; }
+    if expected.is_some_and(|e| keymap::adjacent(e, c)) { return MissKind::Adjacent; }
+    MissKind::Other
+}
+
+/// Classify the misses of a record. None without replay / word sequence, when the record was
+/// typed with other rules, or when the replay does not play back.
+pub fn classify(rec: &ScoreRecord, rules: &RomajiRules) -> Option<Vec<Miss>> {
+    let evs = rec.replay.as_deref()?;
+    if rec.word_seq.is_empty() || rec.rules_version.as_ref().is_some_and(|v| *v != rules.version()) { return None; }
+    let new_matcher = |i: usize| rec.word_seq.get(i).map(|w| RomajiMatcher::new(&w.jp, &w.romas, rules));
+    let (mut idx, mut matcher, mut out) = (0usize, new_matcher(0), Vec::new());
+    for (n, e) in evs.iter().enumerate() {
+        if e.w != idx { return None; }
+        let m = matcher.as_mut()?;
+        let c = e.c.chars().next()?;
+        // what the play screen showed (see `Game::current_roma_line`)
+        let shown = m.display_with_default(rec.word_seq[idx].romas.first().map(|s| s.as_str()).unwrap_or(""));
+        let shown = if shown.starts_with(&m.typed) { shown } else { m.display_candidate() };
+        let typed = m.typed.clone();
+        match m.input_char(c) {
+            InputResult::Miss if !e.ok => {
+                let key = |j: usize| evs.get(j).filter(|x| x.w == idx).and_then(|x| Some((x.c.chars().next()?, x.ok)));
+                let jp = rec.word_seq[idx].jp.clone();
+                let kind = classify_one(&jp, &shown, &typed, c, n.checked_sub(1).and_then(key), key(n + 1));
+                out.push(Miss { ev: n, word: idx, jp, expected: shown.chars().nth(typed.len()), typed: c, kind });
+            }
+            InputResult::Correct if e.ok => {}
+            InputResult::Complete if e.ok => { idx += 1; matcher = new_matcher(idx); }
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+/// Group misses (of one or more sessions) into kinds and patterns.
+pub fn report(misses: &[Miss], sessions: usize) -> Report {
+    let mut by_kind: Vec<(MissKind, usize)> = MissKind::ALL.iter().map(|&k| (k, misses.iter().filter(|m| m.kind == k).count())).filter(|k| k.1 > 0).collect();
+    by_kind.sort_by_key(|k| Reverse(k.1));
+    let mut patterns: Vec<Pattern> = Vec::new();
+    for m in misses {
+        match patterns.iter_mut().find(|p| p.kind == m.kind && p.expected == m.expected && p.typed == m.typed) {
+            Some(p) => {
+                p.count += 1;
+                if p.examples.len() < EXAMPLES && !p.examples.contains(&m.jp) { p.examples.push(m.jp.clone()); }
+            }
+            None => patterns.push(Pattern { kind: m.kind, expected: m.expected, typed: m.typed, count: 1, examples: vec![m.jp.clone()] }),
+        }
+    }
+    // stable: equal counts keep the order they first occurred in
+    patterns.sort_by_key(|p| Reverse(p.count));
+    Report { misses: misses.len(), sessions, by_kind, patterns }
+}
+
+/// Report over every record of `mode` that can be classified.
+pub fn aggregate(history: &[ScoreRecord], mode: &str, rules: &RomajiRules) -> Report {
+    let per: Vec<Vec<Miss>> = history.iter().filter(|r| r.mode == mode).filter_map(|r| classify(r, rules)).collect();
+    report(&per.concat(), per.len())
+}
@@ -4,4 +4,13 @@ pub mod romaji;
 pub mod timer;
 pub mod stats;
 pub mod level;
+pub mod kana;
+pub mod aozora;
+pub mod difficulty;
 
+pub mod verify;
+pub mod compare;
+pub mod progress;
+pub mod latency;
+pub mod misses;
+pub mod fingers;
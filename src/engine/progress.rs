@@ -0,0 +1,97 @@
+// Long-term progress over the stored history: per-day aggregates of one mode,
+// the rank distribution and practice activity per calendar day.
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::store::json::ScoreRecord;
+
+use super::level::RANKS;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayStat {
+    pub date: NaiveDate,
+    /// Stored sessions that day (aborted ones included)
+    pub sessions: usize,
+    /// Best / median time of the completed sessions (0 when all were aborted)
+    pub best: f64,
+    pub median: f64,
+    /// Median correct keystrokes per minute (None without replays)
+    pub kpm: Option<f64>,
+    /// Misses / all keystrokes of the day (None without replays)
+    pub miss_rate: Option<f64>,
+    /// Median 安定度 of the sessions with rhythm metrics
+    pub consistency: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    pub days: Vec<DayStat>,
+    /// Completed sessions per rank, slowest rank first (ranks never reached included)
+    pub ranks: Vec<(&'static str, u64)>,
+    /// Sessions per calendar day, every mode
+    pub activity: BTreeMap<NaiveDate, usize>,
+}
+
+impl Progress {
+    pub fn sessions(&self) -> usize { self.days.iter().map(|d| d.sessions).sum() }
+    pub fn completed(&self) -> u64 { self.ranks.iter().map(|r| r.1).sum() }
+}
+
+/// Local calendar day a record was made on (None for unparsable dates).
+pub fn day_of(rec: &ScoreRecord) -> Option<NaiveDate> {
+    chrono::DateTime::parse_from_rfc3339(&rec.datetime).ok().map(|d| d.date_naive())
+}
+
+fn median(v: &mut [f64]) -> Option<f64> {
+    if v.is_empty() { return None; }
+    v.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let n = v.len();
+    Some(if n % 2 == 1 { v[n / 2] } else { (v[n / 2 - 1] + v[n / 2]) / 2.0 })
+}
+
+/// Aggregate `history` (any order) for `mode`.
+pub fn build(history: &[ScoreRecord], mode: &str) -> Progress {
+    let mut by_day: BTreeMap<NaiveDate, Vec<&ScoreRecord>> = BTreeMap::new();
+    let mut activity = BTreeMap::new();
+    for r in history {
+        let Some(d) = day_of(r) else { continue };
+        *activity.entry(d).or_insert(0) += 1;
+        if r.mode == mode { by_day.entry(d).or_default().push(r); }
+    }
+    let mut ranks: Vec<(&'static str, u64)> = RANKS.iter().map(|r| (*r, 0)).collect();
+    let days = by_day.into_iter().map(|(date, recs)| {
+        let done: Vec<&&ScoreRecord> = recs.iter().filter(|r| !r.aborted).collect();
+        for r in &done { if let Some(slot) = ranks.iter_mut().find(|(k, _)| *k == r.rank) { slot.1 += 1; } }
+        let mut times: Vec<f64> = done.iter().map(|r| r.time_sec).collect();
+        let mut kpms: Vec<f64> = done.iter().filter_map(|r| r.kpm()).collect();
+        let mut cons: Vec<f64> = done.iter().filter_map(|r| r.consistency()).collect();
+        let (keys, miss) = done.iter().filter_map(|r| r.replay.as_ref()).flatten()
+            .fold((0usize, 0usize), |(k, m), e| (k + 1, m + usize::from(!e.ok)));
+        DayStat {
+            date, sessions: recs.len(),
+            best: times.iter().copied().reduce(f64::min).unwrap_or(0.0),
+            median: median(&mut times).unwrap_or(0.0),
+            kpm: median(&mut kpms),
+            miss_rate: (keys > 0).then(|| miss as f64 / keys as f64),
+            consistency: median(&mut cons),
+        }
+    }).collect();
+    Progress { days, ranks, activity }
+}
+
+/// Calendar heatmap: `weeks` columns of 7 days (Monday first) ending with the week of `today`.
+/// Days after `today` are None.
+pub fn heatmap(activity: &BTreeMap<NaiveDate, usize>, today: NaiveDate, weeks: usize) -> Vec<[Option<usize>; 7]> {
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let first = monday - Duration::weeks(weeks.saturating_sub(1) as i64);
+    (0..weeks).map(|w| {
+        let mut col = [None; 7];
+        for (d, cell) in col.iter_mut().enumerate() {
+            let day = first + Duration::days((w * 7 + d) as i64);
+            if day <= today { *cell = Some(activity.get(&day).copied().unwrap_or(0)); }
+        }
+        col
+    }).collect()
+}
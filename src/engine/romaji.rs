@@ -11,6 +11,9 @@ pub struct RomajiRules {
     pub yure: HashMap<String, Vec<String>>, // e.g., し: ["shi","si"]
     #[serde(default)]
     pub special: Option<SpecialRules>,
+    /// YAML text the rules were read from (embedded in replay files)
+    #[serde(skip)]
+    pub source: String,
 }
 
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -21,16 +24,16 @@ pub struct SpecialRules {
 
 impl RomajiRules {
     pub fn from_yaml_file(path: &Path) -> Result<Self> {
-        let s = fs::read_to_string(path)?;
-        let mut r: RomajiRules = serde_yaml::from_str(&s)?;
-        if r.special.is_none() { r.special = Some(SpecialRules{ n_patterns: vec!["n'".into(), "nn".into(), "n".into()] }); }
-        Ok(r)
+        Self::from_yaml_str(&fs::read_to_string(path)?)
     }
     pub fn from_yaml_str(s: &str) -> Result<Self> {
         let mut r: RomajiRules = serde_yaml::from_str(s)?;
         if r.special.is_none() { r.special = Some(SpecialRules{ n_patterns: vec!["n'".into(), "nn".into(), "n".into()] }); }
+        r.source = s.into();
         Ok(r)
     }
+    /// Fingerprint of the source text, stored with every record.
+    pub fn version(&self) -> String { crate::store::replay::fingerprint(&self.source) }
 }
 
 pub enum InputResult { Correct, Miss, Complete, Noop }
@@ -231,3 +234,34 @@ fn apply_n_doubling_before_cons(set: &mut HashSet<String>) {
     }
     set.extend(add.into_iter());
 }
+
+/// Result of checking a dictionary romaji spelling against the matcher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RomaCheck {
+    /// Typable and matches the kana reading of `jp`
+    Ok,
+    /// Typable, but `jp` contains kanji so the reading can't be checked
+    Unverified,
+    Invalid(String),
+}
+
+/// Validate a romaji spelling for `jp` by feeding it through a matcher built
+/// from the kana reading (when `jp` is kana only).
+pub fn check_roma(jp: &str, roma: &str, rules: &RomajiRules) -> RomaCheck {
+    if roma.is_empty() { return RomaCheck::Invalid("ローマ字が空です".into()); }
+    if let Some(c) = roma.chars().find(|c| !c.is_ascii_graphic() || c.is_ascii_uppercase()) {
+        return RomaCheck::Invalid(format!("入力できない文字 '{}'", c));
+    }
+    let Some(base) = super::kana::kana_to_romaji(jp) else { return RomaCheck::Unverified; };
+    let mut m = RomajiMatcher::new(jp, &[base], rules);
+    let mut complete = false;
+    for (i, c) in roma.chars().enumerate() {
+        match m.input_char(c) {
+            InputResult::Miss => return RomaCheck::Invalid(format!("{}文字目 '{}' が読みと一致しません（例: {}）", i + 1, c, m.display_candidate())),
+            InputResult::Complete if i + 1 < roma.len() => return RomaCheck::Invalid("読みより長すぎます".into()),
+            InputResult::Complete => complete = true,
+            _ => {}
+        }
+    }
+    if complete { RomaCheck::Ok } else { RomaCheck::Invalid(format!("途中で終わっています（例: {}）", m.display_candidate())) }
+}
@@ -1,4 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 use super::game::Split;
+use crate::store::json::KeyEv;
 
 pub fn compute_wpm_stats(splits: &[Split]) -> (f64, f64) {
     let mut top = 0.0_f64;
@@ -18,3 +21,36 @@ pub fn moving_speed_series(points: &[(f64,f64)], _window: f64) -> Vec<(f64,f64)>
     points.to_vec()
 }
 
+/// Width of one bin of the interval histogram (ms); the last bin collects everything slower.
+pub const RHYTHM_BIN_MS: u32 = 50;
+pub const RHYTHM_BINS: usize = 12;
+
+/// Inter-key interval statistics of a session (stored in the record, see `ScoreRecord::rhythm`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct Rhythm {
+    /// Mean and standard deviation of the intervals between keystrokes (s)
+    pub mean: f64,
+    pub std: f64,
+    /// Coefficient of variation (std / mean)
+    pub cv: f64,
+    /// Longest gap between two keystrokes (s), word changes included
+    pub longest_pause: f64,
+    /// 100 / (1 + cv): 100 = perfectly even keystrokes
+    pub consistency: f64,
+    /// Interval counts per `RHYTHM_BIN_MS` bin
+    pub histogram: Vec<u32>,
+}
+
+/// Rhythm of a replay. `penalty` is the time added to each miss event (it is not part of the
+/// player's pause). None with fewer than two keystrokes.
+pub fn rhythm(evs: &[KeyEv], penalty: f64) -> Option<Rhythm> {
+    let iv: Vec<f64> = evs.windows(2).map(|w| (w[1].t - w[0].t - if w[1].ok { 0.0 } else { penalty }).max(0.0)).collect();
+    if iv.is_empty() { return None; }
+    let n = iv.len() as f64;
+    let mean = iv.iter().sum::<f64>() / n;
+    let std = (iv.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt();
+    let cv = if mean > 0.0 { std / mean } else { 0.0 };
+    let mut histogram = vec![0u32; RHYTHM_BINS];
+    for x in &iv { histogram[((x * 1000.0).round() as usize / RHYTHM_BIN_MS as usize).min(RHYTHM_BINS - 1)] += 1; }
+    Some(Rhythm { mean, std, cv, longest_pause: iv.iter().copied().fold(0.0, f64::max), consistency: 100.0 / (1.0 + cv), histogram })
+}
@@ -0,0 +1,108 @@
+// Replay verification: re-type a record's keystrokes through RomajiMatcher with the
+// stored word sequence and check that time, misses, splits and rhythm come out the same.
+// Together with the content hash this makes hand-edited records detectable.
+
+use crate::store::json::{ScoreRecord, SplitRec};
+
+use super::romaji::{InputResult, RomajiMatcher, RomajiRules};
+
+/// Allowed difference for times (events are kept in whole milliseconds).
+pub const TIME_TOLERANCE: f64 = 0.005;
+/// Allowed difference of the stored 安定度 from the one recomputed from the replay.
+pub const CONSISTENCY_TOLERANCE: f64 = 0.05;
+
+/// Result of re-typing the replay.
+#[derive(Debug, Clone)]
+pub struct Replayed { pub time_sec: f64, pub miss: u32, pub splits: Vec<SplitRec> }
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// None = the record has no hash (older versions)
+    pub hash_ok: Option<bool>,
+    /// None = no rules fingerprint stored; false = typed with other rules than the ones given
+    pub rules_match: Option<bool>,
+    /// None = nothing to re-run (no replay or no word sequence)
+    pub replayed: Option<Replayed>,
+    /// Mismatches found; empty means verified (as far as the record allows)
+    pub issues: Vec<String>,
+}
+
+impl Report {
+    pub fn ok(&self) -> bool { self.issues.is_empty() }
+    /// Fully checked: hash present and valid, and the replay re-run matched.
+    pub fn verified(&self) -> bool { self.ok() && self.hash_ok == Some(true) && self.replayed.is_some() }
+    pub fn summary(&self) -> String {
+        if !self.ok() { return format!("不一致 {} 件", self.issues.len()); }
+        match (self.hash_ok, &self.replayed) {
+            (None, Some(_)) => "OK（ハッシュなし）".into(),
+            (_, Some(_)) => "OK".into(),
+            (_, None) => "未検証（リプレイなし）".into(),
+        }
+    }
+}
+
+/// Re-type the replay. Err explains why the stream cannot be played back at all.
+pub fn replay(rec: &ScoreRecord, rules: &RomajiRules) -> Result<Replayed, String> {
+    let evs = rec.replay.as_deref().ok_or("リプレイがありません")?;
+    if rec.word_seq.is_empty() { return Err("出題語が記録されていません".into()); }
+    let new_matcher = |i: usize| rec.word_seq.get(i).map(|w| RomajiMatcher::new(&w.jp, &w.romas, rules));
+    let (mut idx, mut word_start, mut miss) = (0usize, 0.0f64, 0u32);
+    let mut matcher = new_matcher(0);
+    let mut splits = Vec::new();
+    for (n, e) in evs.iter().enumerate() {
+        if e.w != idx { return Err(format!("{}打目の語番号が {} ですが、再生では {} です", n + 1, e.w, idx)); }
+        let m = matcher.as_mut().ok_or_else(|| format!("{}打目: 全ての語を打ち終えた後の打鍵です", n + 1))?;
+        let c = e.c.chars().next().unwrap_or('\0');
+        let res = m.input_char(c);
+        let ok = matches!(res, InputResult::Correct | InputResult::Complete);
+        if matches!(res, InputResult::Noop) || ok != e.ok {
+            return Err(format!("{}打目「{}」の判定が一致しません（記録: {} / 再生: {}）", n + 1, e.c, if e.ok { "正" } else { "誤" }, if ok { "正" } else { "誤" }));
+        }
+        if !ok { miss += 1; }
+        if matches!(res, InputResult::Complete) {
+            splits.push(SplitRec { word: rec.word_seq[idx].jp.clone(), sec: e.t - word_start, miss: m.miss_count });
+            word_start = e.t;
+            idx += 1;
+            matcher = new_matcher(idx);
+        }
+    }
+    Ok(Replayed { time_sec: evs.last().map(|e| e.t).unwrap_or(0.0), miss, splits })
+}
+
+/// Check `rec` against its hash and (when possible) its replay typed with `rules`.
+pub fn verify(rec: &ScoreRecord, rules: &RomajiRules) -> Report {
+    let mut rep = Report { hash_ok: rec.hash_ok(), rules_match: rec.rules_version.as_ref().map(|v| *v == rules.version()), ..Default::default() };
+    if rep.hash_ok == Some(false) { rep.issues.push("ハッシュが一致しません（記録が書き換えられています）".into()); }
+    if rec.replay.is_none() || rec.word_seq.is_empty() { return rep; }
+    if rep.rules_match == Some(false) { rep.issues.push("記録時とローマ字ルールが異なるため再生できません".into()); return rep; }
+    match replay(rec, rules) {
+        Err(e) => rep.issues.push(e),
+        Ok(r) => { compare(rec, &r, &mut rep.issues); rep.replayed = Some(r); }
+    }
+    rep
+}
+
+fn compare(rec: &ScoreRecord, r: &Replayed, issues: &mut Vec<String>) {
+    if rec.miss != r.miss { issues.push(format!("ミス数: 記録 {} / 再生 {}", rec.miss, r.miss)); }
+    // an aborted run ends at ESC, after the last keystroke
+    let time_bad = if rec.aborted { rec.time_sec + TIME_TOLERANCE < r.time_sec } else { (rec.time_sec - r.time_sec).abs() > TIME_TOLERANCE };
+    if time_bad { issues.push(format!("タイム: 記録 {:.3}s / 再生 {:.3}s", rec.time_sec, r.time_sec)); }
+    // the rhythm is ranked (安定度) but not sealed, so it must match the keystrokes
+    if let Some(stored) = &rec.rhythm {
+        let calc = rec.compute_rhythm();
+        let same = calc.as_ref().is_some_and(|c| (c.consistency - stored.consistency).abs() <= CONSISTENCY_TOLERANCE);
+        if !same { issues.push(format!("安定度: 記録 {:.1} / 再生 {}", stored.consistency, calc.map(|c| format!("{:.1}", c.consistency)).unwrap_or_else(|| "-".into()))); }
+    }
+    // a fixed-keystroke session that ends on a word's last key stores no split for it
+    let n = rec.splits.len();
+    let last_unsplit = n + 1 == r.splits.len() && !rec.aborted;
+    if n != r.splits.len() && !last_unsplit {
+        issues.push(format!("語数: 記録 {} / 再生 {}", n, r.splits.len()));
+        return;
+    }
+    for (i, (a, b)) in rec.splits.iter().zip(&r.splits).enumerate() {
+        if a.word != b.word { issues.push(format!("{}語目: 記録「{}」/ 再生「{}」", i + 1, a.word, b.word)); }
+        if (a.sec - b.sec).abs() > TIME_TOLERANCE { issues.push(format!("{}語目「{}」のタイム: 記録 {:.3}s / 再生 {:.3}s", i + 1, a.word, a.sec, b.sec)); }
+        if a.miss != b.miss { issues.push(format!("{}語目「{}」のミス: 記録 {} / 再生 {}", i + 1, a.word, a.miss, b.miss)); }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod app;
+pub mod cli;
 pub mod engine;
 pub mod store;
 pub mod ui;
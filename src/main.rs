@@ -8,6 +8,44 @@ use ratatui::{prelude::*, Terminal};
 use irohatype::app;
 
 fn main() -> Result<()> {
+    // --data-dir <DIR> (or --data-dir=DIR) overrides $IROHATYPE_DATA_DIR and the platform dirs
+    let mut data_dir = None;
+    let mut profile = None;
+    let mut args = std::env::args().skip(1);
+    let mut command = None;
+    while let Some(a) = args.next() {
+        if a == "--data-dir" {
+            data_dir = Some(args.next().ok_or_else(|| anyhow::anyhow!("--data-dir にはパスを指定してください"))?.into());
+        } else if let Some(v) = a.strip_prefix("--data-dir=") {
+            data_dir = Some(v.into());
+        } else if a == "--profile" {
+            profile = Some(args.next().ok_or_else(|| anyhow::anyhow!("--profile には名前を指定してください"))?);
+        } else if let Some(v) = a.strip_prefix("--profile=") {
+            profile = Some(v.to_string());
+        } else if a == "-h" || a == "--help" {
+            println!("{}", irohatype::cli::USAGE);
+            return Ok(());
+        } else if !a.starts_with('-') {
+            // subcommand: everything after it belongs to it
+            command = Some((a, args.by_ref().collect::<Vec<_>>()));
+        } else {
+            anyhow::bail!("不明な引数: {}\n{}", a, irohatype::cli::USAGE);
+        }
+    }
+    irohatype::util::paths::init(data_dir);
+    match profile {
+        Some(p) if irohatype::util::paths::profiles().contains(&p) => irohatype::util::paths::set_profile(&p),
+        Some(p) => anyhow::bail!("プロファイル {} がありません", p),
+        None => irohatype::util::paths::restore_active_profile(),
+    }
+    if let Some((cmd, rest)) = command {
+        if let Err(e) = irohatype::cli::run(&cmd, &rest) {
+            eprintln!("error: {e:#}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
@@ -0,0 +1,147 @@
+// Export of stored sessions: CSV / JSON Lines for spreadsheets, Markdown reports for chat.
+
+use std::{fs, path::{Path, PathBuf}};
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use super::json::{ScoreRecord, SplitRec};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format { Csv, Jsonl, Markdown }
+
+impl Format {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "csv" => Self::Csv,
+            "jsonl" | "ndjson" => Self::Jsonl,
+            "md" | "markdown" => Self::Markdown,
+            _ => bail!("不明な形式: {}（csv / jsonl / md）", s),
+        })
+    }
+    pub fn ext(self) -> &'static str { match self { Self::Csv => "csv", Self::Jsonl => "jsonl", Self::Markdown => "md" } }
+}
+
+/// One exported session (same columns for CSV and JSON Lines; the replay is left out).
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportRow<'a> {
+    pub id: &'a str,
+    pub datetime: &'a str,
+    pub mode: &'a str,
+    pub time_sec: f64,
+    pub miss: u32,
+    pub timeloss_sec: f64,
+    pub rank: &'a str,
+    pub kpm: Option<f64>,
+    pub wpm_top: f64,
+    pub wpm_worst: f64,
+    /// 安定度 0–100 (see `engine::stats::Rhythm`)
+    pub consistency: Option<f64>,
+    pub words: usize,
+    pub aborted: bool,
+    pub memo: Option<&'a str>,
+    pub splits: &'a [SplitRec],
+}
+
+impl<'a> From<&'a ScoreRecord> for ExportRow<'a> {
+    fn from(r: &'a ScoreRecord) -> Self {
+        Self {
+            id: &r.id, datetime: &r.datetime, mode: &r.mode, time_sec: r.time_sec, miss: r.miss, timeloss_sec: r.timeloss_sec,
+            rank: &r.rank, kpm: r.kpm(), wpm_top: r.wpm_top, wpm_worst: r.wpm_worst, consistency: r.consistency(), words: r.splits.len(), aborted: r.aborted,
+            memo: r.memo.as_deref(), splits: &r.splits,
+        }
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) { format!("\"{}\"", s.replace('"', "\"\"")) } else { s.to_string() }
+}
+
+/// CSV with a header row. Splits go into one column as `word:sec:miss` joined by `|`.
+pub fn to_csv(records: &[ScoreRecord]) -> String {
+    let mut out = String::from("id,datetime,mode,time_sec,miss,timeloss_sec,rank,kpm,wpm_top,wpm_worst,consistency,words,aborted,memo,splits\n");
+    for r in records.iter().map(ExportRow::from) {
+        let splits = r.splits.iter().map(|s| format!("{}:{:.3}:{}", s.word, s.sec, s.miss)).collect::<Vec<_>>().join("|");
+        let cols = [
+            r.id.to_string(), r.datetime.to_string(), r.mode.to_string(), format!("{:.3}", r.time_sec), r.miss.to_string(),
+            format!("{:.3}", r.timeloss_sec), r.rank.to_string(), r.kpm.map(|k| format!("{:.1}", k)).unwrap_or_default(),
+            format!("{:.1}", r.wpm_top), format!("{:.1}", r.wpm_worst),
+            r.consistency.map(|c| format!("{:.1}", c)).unwrap_or_default(), r.words.to_string(), r.aborted.to_string(),
+            r.memo.unwrap_or("").to_string(), splits,
+        ];
+        out.push_str(&cols.iter().map(|c| csv_field(c)).collect::<Vec<_>>().join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// One JSON object per line.
+pub fn to_jsonl(records: &[ScoreRecord]) -> Result<String> {
+    let mut out = String::new();
+    for r in records {
+        out.push_str(&serde_json::to_string(&ExportRow::from(r))?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Markdown report of one session: summary, split table and the fastest/slowest words (as in the details screen).
+pub fn markdown_report(r: &ScoreRecord) -> String {
+    let esc = |s: &str| s.replace('|', "\\|");
+    let mut out = format!("# IrohaType 記録 {}\n\n", r.datetime);
+    out.push_str("| 項目 | 値 |\n|---|---|\n");
+    out.push_str(&format!("| モード | {} |\n", r.mode));
+    out.push_str(&format!("| タイム | {:.3} 秒 |\n", r.time_sec));
+    out.push_str(&format!("| ミス | {} |\n", r.miss));
+    out.push_str(&format!("| タイムロス | {:.3} 秒 |\n", r.timeloss_sec));
+    out.push_str(&format!("| レベル | {} |\n", r.rank));
+    if let Some(k) = r.kpm() { out.push_str(&format!("| KPM | {:.1} |\n", k)); }
+    out.push_str(&format!("| 条件 | {} |\n", r.session.as_ref().map(|s| s.summary()).unwrap_or_else(|| "標準".into())));
+    if r.aborted { out.push_str("| 状態 | 中断 |\n"); }
+    if let Some(m) = &r.memo { out.push_str(&format!("| メモ | {} |\n", esc(m))); }
+
+    out.push_str("\n## ラップ\n\n| # | 語 | 秒 | ミス |\n|---:|---|---:|---:|\n");
+    for (i, s) in r.splits.iter().enumerate() {
+        out.push_str(&format!("| {} | {} | {:.3} | {} |\n", i + 1, esc(&s.word), s.sec, s.miss));
+    }
+    let mut sorted = r.splits.clone();
+    sorted.sort_by(|a, b| a.sec.partial_cmp(&b.sec).unwrap());
+    let list = |it: &mut dyn Iterator<Item = &SplitRec>| it.take(7).map(|s| format!("- {:.3}s {}\n", s.sec, s.word)).collect::<String>();
+    out.push_str("\n## 速い語句\n\n");
+    out.push_str(&list(&mut sorted.iter()));
+    out.push_str("\n## 苦手語句\n\n");
+    out.push_str(&list(&mut sorted.iter().rev()));
+    out
+}
+
+/// Render `records` in `format`. Markdown covers the last record only (one report per session).
+pub fn render(records: &[ScoreRecord], format: Format) -> Result<String> {
+    match format {
+        Format::Csv => Ok(to_csv(records)),
+        Format::Jsonl => to_jsonl(records),
+        Format::Markdown => match records.last() {
+            Some(r) => Ok(markdown_report(r)),
+            None => bail!("書き出す記録がありません"),
+        },
+    }
+}
+
+/// Sessions to export, oldest first, with replays loaded (needed for KPM).
+/// `mode` narrows to one mode; aborted sessions are skipped unless `aborted` is set.
+pub fn collect(store: &dyn super::ScoreStore, mode: Option<&str>, aborted: bool) -> Result<Vec<ScoreRecord>> {
+    store.history()?.into_iter()
+        .filter(|r| mode.map(|m| r.mode == m).unwrap_or(true) && (aborted || !r.aborted))
+        .map(|r| Ok(if r.replay.is_some() { r } else { store.get(&r.id)?.unwrap_or(r) }))
+        .collect()
+}
+
+/// Default folder for exports made from the UI.
+pub fn export_dir() -> PathBuf { crate::util::paths::dirs().data.join("exports") }
+
+/// Write `records` to `dir/<stem>.<ext>` and return the path.
+pub fn write(dir: &Path, stem: &str, records: &[ScoreRecord], format: Format) -> Result<PathBuf> {
+    let path = dir.join(format!("{}.{}", stem, format.ext()));
+    crate::util::paths::ensure_parent(&path)?;
+    fs::write(&path, render(records, format)?)?;
+    Ok(path)
+}
@@ -1,16 +1,68 @@
-use std::{fs, path::PathBuf};
+use std::{collections::HashMap, fs, path::PathBuf};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ScoreBook { pub top: Vec<ScoreRecord>, pub lap: Vec<ScoreRecord> }
+/// Current `ScoreBook::version` (1 = Top-100/Lap-10 only, 2 = full history, 3 = compact replays,
+/// 4 = rhythm statistics).
+pub const SCOREBOOK_VERSION: u32 = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreBook {
+    /// Schema version of the file (see `SCOREBOOK_VERSION`)
+    #[serde(default)] pub version: u32,
+    /// Every stored session, oldest first (append-only; aborted ones only if enabled)
+    #[serde(default)] pub history: Vec<ScoreRecord>,
+    /// Leaderboards derived from `history` (never stored)
+    #[serde(skip)] pub top: Vec<ScoreRecord>,
+    #[serde(skip)] pub lap: Vec<ScoreRecord>,
+    /// Lap entries carried over from v1 files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub legacy_lap: Vec<ScoreRecord>,
+}
+
+impl Default for ScoreBook {
+    fn default() -> Self { Self { version: SCOREBOOK_VERSION, history: Vec::new(), top: Vec::new(), lap: Vec::new(), legacy_lap: Vec::new() } }
+}
+
+/// v1 → v2: the Top-100 becomes the history (oldest first), the Lap-10 is kept as `legacy_lap`.
+fn v1_to_v2(v: &mut serde_json::Value) {
+    let Some(obj) = v.as_object_mut() else { return };
+    let mut top = match obj.remove("top") { Some(serde_json::Value::Array(a)) => a, _ => Vec::new() };
+    let lap = obj.remove("lap").unwrap_or_else(|| serde_json::Value::Array(Vec::new()));
+    // files written by early history builds carry no version but already have `history`
+    if obj.contains_key("history") { return; }
+    top.sort_by(|a, b| a["datetime"].as_str().cmp(&b["datetime"].as_str()));
+    obj.insert("history".into(), serde_json::Value::Array(top));
+    obj.insert("legacy_lap".into(), lap);
+}
+
+/// v2 → v3: replays are re-written compactly on save; the per-tick speed curve is
+/// dropped where a replay exists (it is rebuilt from the events).
+fn v2_to_v3(v: &mut serde_json::Value) {
+    let Some(hist) = v.get_mut("history").and_then(|h| h.as_array_mut()) else { return };
+    for r in hist.iter_mut().filter_map(|r| r.as_object_mut()) {
+        if r.get("replay").map(|x| !x.is_null()).unwrap_or(false) { r.remove("speed_series"); }
+    }
+}
+
+/// v3 → v4: rhythm statistics for records that have a replay.
+fn v3_to_v4(v: &mut serde_json::Value) {
+    let Some(hist) = v.get_mut("history").and_then(|h| h.as_array_mut()) else { return };
+    for r in hist.iter_mut() {
+        let Ok(rec) = serde_json::from_value::<ScoreRecord>(r.clone()) else { continue };
+        if let (Some(rh), Some(obj)) = (rec.compute_rhythm(), r.as_object_mut()) {
+            obj.insert("rhythm".into(), serde_json::to_value(rh).unwrap_or_default());
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SplitRec { pub word: String, pub sec: f64, pub miss: u32 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ScoreRecord {
+    /// Stable unique id (assigned on insert; older files get one on load)
+    #[serde(default, skip_serializing_if = "String::is_empty")] pub id: String,
     pub mode: String,
     pub datetime: String,
     pub time_sec: f64,
@@ -23,50 +75,194 @@ pub struct ScoreRecord {
     #[serde(skip_serializing_if = "Option::is_none")] pub memo: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")] pub speed_series: Option<Vec<(f64,f64)>>,
     #[serde(skip_serializing_if = "Option::is_none")] pub word_display: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")] pub replay: Option<Vec<KeyEv>>, 
+    /// Keystrokes; stored compactly (see `store::replay`)
+    #[serde(default, with = "super::replay::serde_events", skip_serializing_if = "Option::is_none")] pub replay: Option<Vec<KeyEv>>,
+    /// Words of the session in the order shown, for playback elsewhere
+    #[serde(default, skip_serializing_if = "Vec::is_empty")] pub word_seq: Vec<crate::engine::game::WordEntry>,
+    /// Fingerprint of the romaji rules the run was typed with
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub rules_version: Option<String>,
+    /// SHA-256 of the result fields and replay (see `content_hash`); None for records older than hashing
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub hash: Option<String>,
+    /// Inter-key interval statistics (derived from the replay, so not part of the hash)
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub rhythm: Option<crate::engine::stats::Rhythm>,
+    /// Word selection options used for the session (None = defaults)
+    #[serde(default, skip_serializing_if = "Option::is_none")] pub session: Option<crate::engine::game::SessionOptions>,
+    /// Session was abandoned with ESC (kept in the history, excluded from rankings)
+    #[serde(default, skip_serializing_if = "is_false")] pub aborted: bool,
+    /// Favourite run: always kept in the ranking view and protected from deletion
+    #[serde(default, skip_serializing_if = "is_false")] pub pinned: bool,
+}
+
+fn is_false(b: &bool) -> bool { !*b }
+
+/// Fields covered by `ScoreRecord::content_hash`. Id, memo and pin are left out
+/// (they are meant to be edited); the speed curve and rhythm are derived from the replay
+/// (`verify` recomputes the rhythm).
+#[derive(Serialize)]
+struct Sealed<'a> {
+    mode: &'a str, datetime: &'a str, time_sec: f64, miss: u32, timeloss_sec: f64, splits: &'a [SplitRec],
+    wpm_top: f64, wpm_worst: f64, rank: &'a str, word_seq: Vec<(&'a str, &'a [String])>, rules_version: Option<&'a str>,
+    session: Option<&'a crate::engine::game::SessionOptions>, aborted: bool,
+}
+
+impl ScoreRecord {
+    /// Hex SHA-256 over the result and the compact replay encoding (stable across save/load
+    /// in every backend; JSON files rely on serde_json's `float_roundtrip` for the raw f64s). Detects hand edits; the replay verifier catches consistent forgeries.
+    pub fn content_hash(&self) -> String {
+        use sha2::{Digest, Sha256};
+        let sealed = Sealed {
+            mode: &self.mode, datetime: &self.datetime, time_sec: self.time_sec, miss: self.miss, timeloss_sec: self.timeloss_sec, splits: &self.splits,
+            wpm_top: self.wpm_top, wpm_worst: self.wpm_worst, rank: &self.rank,
+            word_seq: self.word_seq.iter().map(|w| (w.jp.as_str(), w.romas.as_slice())).collect(), rules_version: self.rules_version.as_deref(),
+            session: self.session.as_ref(), aborted: self.aborted,
+        };
+        let mut h = Sha256::new();
+        h.update(serde_json::to_vec(&sealed).unwrap_or_default());
+        if let Some(evs) = &self.replay { h.update(super::replay::encode(evs)); }
+        h.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    pub fn seal(&mut self) { self.hash = Some(self.content_hash()); }
+
+    /// None when the record carries no hash.
+    pub fn hash_ok(&self) -> Option<bool> { self.hash.as_ref().map(|h| *h == self.content_hash()) }
+
+    /// Time added to the clock per miss (s).
+    pub fn miss_penalty(&self) -> f64 { if self.miss > 0 { self.timeloss_sec / self.miss as f64 } else { 0.0 } }
+
+    /// Rhythm of the replay at the precision it is stored with (whole ms), so the result is
+    /// the same before and after a save.
+    pub fn compute_rhythm(&self) -> Option<crate::engine::stats::Rhythm> {
+        let evs: Vec<KeyEv> = self.replay.as_deref()?.iter().map(|e| KeyEv { t: (e.t * 1000.0).round() / 1000.0, ..e.clone() }).collect();
+        crate::engine::stats::rhythm(&evs, self.miss_penalty())
+    }
+
+    pub fn consistency(&self) -> Option<f64> { self.rhythm.as_ref().map(|r| r.consistency) }
+
+    /// Correct keystrokes per minute over the whole session (needs the replay).
+    pub fn kpm(&self) -> Option<f64> {
+        let ok = self.replay.as_ref()?.iter().filter(|e| e.ok).count();
+        if self.time_sec <= 0.0 { return None; }
+        Some(ok as f64 / self.time_sec * 60.0)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyEv { pub t: f64, pub c: String, pub ok: bool, pub w: usize }
 
 impl ScoreBook {
-    pub fn path() -> PathBuf { PathBuf::from("data/scores.json") }
-    pub fn load_or_default() -> Result<Self> {
-        let p = Self::path();
-        if let Ok(s) = fs::read_to_string(&p) { Ok(serde_json::from_str(&s)?) } else { Ok(Self::default()) }
+    pub fn path() -> PathBuf { crate::util::paths::scores_json() }
+    pub fn load_or_default() -> Result<Self> { Self::load_from(&Self::path()) }
+    pub fn load_from(p: &std::path::Path) -> Result<Self> {
+        if let Ok(s) = fs::read_to_string(p) { Self::from_json(&s) } else { Ok(Self::default()) }
     }
-    pub fn save(&self) -> Result<()> { fs::create_dir_all("data")?; fs::write(Self::path(), serde_json::to_string_pretty(self)?)?; Ok(()) }
-
-    /// Insert a record, keep Top-100 by fastest time, and return:
-    /// (rank position 1-based if within Top-100, is_personal_best)
-    pub fn insert_and_rank(&mut self, rec: ScoreRecord) -> (Option<usize>, bool) {
-        let prev_best_time = self.top.iter().map(|r| r.time_sec).fold(f64::INFINITY, |a,b| a.min(b));
-        self.top.push(rec.clone());
-        // Time-centric: lower time is better
-        self.top.sort_by(|a,b| a.time_sec.partial_cmp(&b.time_sec).unwrap());
-        // find index by unique datetime
-        let idx = self.top.iter().position(|r| r.datetime == rec.datetime);
-        // Keep Top-100
-        if self.top.len() > 100 { self.top.truncate(100); }
-        let rank_in = idx.and_then(|i| if i < 100 { Some(i+1) } else { None });
+    /// Parse a scorebook, migrating older schema versions and deriving the leaderboards.
+    pub fn from_json(s: &str) -> Result<Self> {
+        let v = crate::util::persist::migrate(serde_json::from_str(s)?, SCOREBOOK_VERSION, &[v1_to_v2, v2_to_v3, v3_to_v4])?;
+        let mut book: Self = serde_json::from_value(v)?;
+        for r in &mut book.history { if r.id.is_empty() { r.id = new_id(); } }
+        book.rebuild();
+        Ok(book)
+    }
+    pub fn save(&self) -> Result<()> { crate::util::persist::save_with_backups_every(&Self::path(), serde_json::to_string_pretty(self)?.as_bytes(), crate::util::persist::BACKUP_INTERVAL) }
+
+    /// Append a record to the history and return:
+    /// (rank position 1-based within its mode if within Top-100, is_personal_best).
+    /// Aborted sessions are kept in the history but never ranked.
+    pub fn insert_and_rank(&mut self, mut rec: ScoreRecord) -> (Option<usize>, bool) {
+        if rec.id.is_empty() { rec.id = new_id(); }
+        let prev_best_time = self.history.iter().filter(|r| r.mode == rec.mode && !r.aborted).map(|r| r.time_sec).fold(f64::INFINITY, |a,b| a.min(b));
+        self.history.push(rec.clone());
+        self.rebuild();
+        if rec.aborted { return (None, false); }
+        let rank_in = self.top_for_mode(&rec.mode).position(|r| r.id == rec.id).map(|i| i + 1);
         let is_new_pb = if prev_best_time.is_finite() { rec.time_sec < prev_best_time } else { true };
+        (rank_in, is_new_pb)
+    }
+
+    /// Recompute the leaderboards from the history: Top-100 per mode (fastest first) and Lap-10.
+    pub fn rebuild(&mut self) {
+        // Time-centric: lower time is better; ties go to the older record
+        let mut top: Vec<ScoreRecord> = self.history.iter().filter(|r| !r.aborted).cloned().collect();
+        top.sort_by(|a,b| a.time_sec.partial_cmp(&b.time_sec).unwrap().then_with(|| a.datetime.cmp(&b.datetime)));
+        let mut per_mode: HashMap<String, usize> = HashMap::new();
+        // pinned runs stay even beyond the 100th place
+        top.retain(|r| { let n = per_mode.entry(r.mode.clone()).or_insert(0); *n += 1; *n <= 100 || r.pinned });
+        self.top = top;
 
-        // Update Lap10 from splits (still by fastest laps)
-        for s in &rec.splits {
-            let laprec = ScoreRecord{ time_sec: s.sec, word_display: Some(s.word.clone()), ..Default::default() };
-            self.lap.push(laprec);
+        // Lap10 from every completed word (aborted sessions included)
+        let mut lap = self.legacy_lap.clone();
+        for r in &self.history {
+            lap.extend(r.splits.iter().map(|s| ScoreRecord{ id: r.id.clone(), mode: r.mode.clone(), datetime: r.datetime.clone(), time_sec: s.sec, miss: s.miss, word_display: Some(s.word.clone()), ..Default::default() }));
         }
-        self.lap.sort_by(|a,b| a.time_sec.partial_cmp(&b.time_sec).unwrap());
-        self.lap.truncate(10);
+        lap.sort_by(|a,b| a.time_sec.partial_cmp(&b.time_sec).unwrap());
+        lap.truncate(10);
+        self.lap = lap;
+    }
 
-        (rank_in, is_new_pb)
+    /// Records of one mode in ranking order.
+    pub fn top_for_mode<'a>(&'a self, mode: &'a str) -> impl Iterator<Item = &'a ScoreRecord> + 'a {
+        self.top.iter().filter(move |r| r.mode == mode)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ScoreRecord> { self.history.iter().find(|r| r.id == id) }
+
+    /// Set memo for a record identified by id (empty clears it). Returns true if updated.
+    pub fn set_memo(&mut self, id: &str, memo: String) -> bool {
+        let Some(r) = self.history.iter_mut().find(|r| r.id == id) else { return false };
+        r.memo = if memo.is_empty() { None } else { Some(memo) };
+        self.rebuild();
+        true
+    }
+
+    pub fn set_pinned(&mut self, id: &str, pinned: bool) -> bool {
+        let Some(r) = self.history.iter_mut().find(|r| r.id == id) else { return false };
+        r.pinned = pinned;
+        self.rebuild();
+        true
     }
 
     /// Set memo for a record identified by datetime. Returns true if updated.
     pub fn set_memo_by_datetime(&mut self, datetime: &str, memo: String) -> bool {
-        if let Some(r) = self.top.iter_mut().find(|r| r.datetime == datetime) {
-            r.memo = Some(memo);
-            true
-        } else { false }
+        match self.history.iter().find(|r| r.datetime == datetime).map(|r| r.id.clone()) {
+            Some(id) => self.set_memo(&id, memo),
+            None => false,
+        }
+    }
+
+    /// Add records from elsewhere, keeping the history ordered by datetime.
+    pub fn import(&mut self, recs: Vec<ScoreRecord>) -> usize {
+        let n = recs.len();
+        self.history.extend(recs.into_iter().map(|mut r| { if r.id.is_empty() { r.id = new_id(); } r }));
+        self.history.sort_by(|a, b| a.datetime.cmp(&b.datetime));
+        self.rebuild();
+        n
     }
+
+    pub fn delete(&mut self, id: &str) -> bool {
+        let before = self.history.len();
+        self.history.retain(|r| r.id != id);
+        if self.history.len() == before { return false; }
+        self.rebuild();
+        true
+    }
+}
+
+/// New stable record id: local timestamp plus random suffix (e.g. "20261018-101500-3fa9c2d1").
+pub fn new_id() -> String {
+    format!("{}-{:08x}", chrono::Local::now().format("%Y%m%d-%H%M%S"), rand::random::<u32>())
+}
+
+impl super::ScoreStore for ScoreBook {
+    fn insert_and_rank(&mut self, rec: ScoreRecord) -> Result<(Option<usize>, bool)> { Ok(ScoreBook::insert_and_rank(self, rec)) }
+    fn top(&self, mode: &str, n: usize) -> Result<Vec<ScoreRecord>> { Ok(self.top_for_mode(mode).enumerate().filter(|(i, r)| *i < n || r.pinned).map(|(_, r)| r.clone()).collect()) }
+    fn lap_top(&self, n: usize) -> Result<Vec<ScoreRecord>> { Ok(self.lap.iter().take(n).cloned().collect()) }
+    fn get(&self, id: &str) -> Result<Option<ScoreRecord>> { Ok(ScoreBook::get(self, id).cloned()) }
+    fn set_memo(&mut self, id: &str, memo: String) -> Result<bool> { Ok(ScoreBook::set_memo(self, id, memo)) }
+    fn set_pinned(&mut self, id: &str, pinned: bool) -> Result<bool> { Ok(ScoreBook::set_pinned(self, id, pinned)) }
+    fn delete(&mut self, id: &str) -> Result<bool> { Ok(ScoreBook::delete(self, id)) }
+    fn history(&self) -> Result<Vec<ScoreRecord>> { Ok(self.history.clone()) }
+    fn import(&mut self, recs: Vec<ScoreRecord>) -> Result<usize> { Ok(ScoreBook::import(self, recs)) }
+    fn save(&mut self) -> Result<()> { ScoreBook::save(self) }
+    fn backend(&self) -> &'static str { "json" }
 }
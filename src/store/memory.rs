@@ -0,0 +1,25 @@
+// In-memory score store (tests, or "store": "memory" for throwaway sessions).
+// Same semantics as the JSON book, which already derives its leaderboards in memory.
+
+use anyhow::Result;
+
+use super::json::{ScoreBook, ScoreRecord};
+use super::ScoreStore;
+
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore { pub book: ScoreBook }
+
+impl ScoreStore for MemoryStore {
+    fn insert_and_rank(&mut self, rec: ScoreRecord) -> Result<(Option<usize>, bool)> { Ok(self.book.insert_and_rank(rec)) }
+    fn top(&self, mode: &str, n: usize) -> Result<Vec<ScoreRecord>> { self.book.top(mode, n) }
+    fn lap_top(&self, n: usize) -> Result<Vec<ScoreRecord>> { Ok(self.book.lap.iter().take(n).cloned().collect()) }
+    fn get(&self, id: &str) -> Result<Option<ScoreRecord>> { Ok(self.book.get(id).cloned()) }
+    fn set_memo(&mut self, id: &str, memo: String) -> Result<bool> { Ok(self.book.set_memo(id, memo)) }
+    fn set_pinned(&mut self, id: &str, pinned: bool) -> Result<bool> { Ok(self.book.set_pinned(id, pinned)) }
+    fn delete(&mut self, id: &str) -> Result<bool> { Ok(self.book.delete(id)) }
+    fn history(&self) -> Result<Vec<ScoreRecord>> { Ok(self.book.history.clone()) }
+    fn import(&mut self, recs: Vec<ScoreRecord>) -> Result<usize> { Ok(self.book.import(recs)) }
+    // nothing to persist
+    fn save(&mut self) -> Result<()> { Ok(()) }
+    fn backend(&self) -> &'static str { "memory" }
+}
@@ -0,0 +1,130 @@
+// Merging scorebooks from other machines (import) and two-way sync through a shared folder.
+
+use std::{collections::{HashMap, HashSet}, fs, path::{Path, PathBuf}};
+
+use anyhow::{bail, Result};
+
+use super::json::{ScoreBook, ScoreRecord};
+use super::ScoreStore;
+
+/// Same session stored differently on both sides; the local record is kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict { pub id: String, pub datetime: String, pub fields: Vec<&'static str> }
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    pub added: usize,
+    pub duplicates: usize,
+    /// Memos taken over from the other side (local had none)
+    pub memos: usize,
+    pub conflicts: Vec<Conflict>,
+    /// Incoming records whose content hash does not match (edited by hand); not imported
+    pub rejected: Vec<String>,
+}
+
+impl MergeReport {
+    pub fn summary(&self) -> String {
+        let mut s = format!("追加 {} 件 / 重複 {} 件 / メモ反映 {} 件 / 競合 {} 件", self.added, self.duplicates, self.memos, self.conflicts.len());
+        if !self.rejected.is_empty() { s.push_str(&format!(" / 改ざんの疑いで除外 {} 件", self.rejected.len())); }
+        s
+    }
+    fn absorb(&mut self, o: MergeReport) {
+        self.added += o.added;
+        self.duplicates += o.duplicates;
+        self.memos += o.memos;
+        self.conflicts.extend(o.conflicts);
+        self.rejected.extend(o.rejected);
+    }
+}
+
+/// Fields that differ between two copies of one session (memo handled separately).
+fn diff(a: &ScoreRecord, b: &ScoreRecord) -> Vec<&'static str> {
+    let mut f = Vec::new();
+    if a.mode != b.mode { f.push("mode"); }
+    if a.datetime != b.datetime { f.push("datetime"); }
+    if (a.time_sec - b.time_sec).abs() > 1e-9 { f.push("time_sec"); }
+    if a.miss != b.miss { f.push("miss"); }
+    if a.splits.len() != b.splits.len() { f.push("splits"); }
+    if a.aborted != b.aborted { f.push("aborted"); }
+    if a.memo.is_some() && b.memo.is_some() && a.memo != b.memo { f.push("memo"); }
+    f
+}
+
+/// Merge `incoming` into `store`. A record is the same session when the id matches,
+/// or (for records that went through different id assignment) mode and datetime match.
+/// New sessions are added, identical ones skipped, differing ones reported and left as they are.
+/// Records failing their content hash are rejected.
+pub fn merge_into(store: &mut dyn ScoreStore, incoming: Vec<ScoreRecord>) -> Result<MergeReport> {
+    let local = store.history()?;
+    let by_id: HashMap<&str, &ScoreRecord> = local.iter().filter(|r| !r.id.is_empty()).map(|r| (r.id.as_str(), r)).collect();
+    let by_time: HashMap<(&str, &str), &ScoreRecord> = local.iter().map(|r| ((r.mode.as_str(), r.datetime.as_str()), r)).collect();
+    let mut report = MergeReport::default();
+    let mut new = Vec::new();
+    let mut memos = Vec::new();
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    for r in incoming {
+        if r.hash_ok() == Some(false) { report.rejected.push(if r.id.is_empty() { r.datetime.clone() } else { r.id.clone() }); continue; }
+        let mine = by_id.get(r.id.as_str()).or_else(|| by_time.get(&(r.mode.as_str(), r.datetime.as_str()))).copied();
+        match mine {
+            None => {
+                // the source itself may repeat a session
+                if !seen.insert((r.mode.clone(), r.datetime.clone())) { report.duplicates += 1; continue; }
+                new.push(r);
+            }
+            Some(m) => {
+                let fields = diff(m, &r);
+                if !fields.is_empty() {
+                    report.conflicts.push(Conflict { id: m.id.clone(), datetime: m.datetime.clone(), fields });
+                } else {
+                    report.duplicates += 1;
+                    if m.memo.is_none() { if let Some(memo) = r.memo { memos.push((m.id.clone(), memo)); } }
+                }
+            }
+        }
+    }
+    report.added = store.import(new)?;
+    for (id, memo) in memos { if store.set_memo(&id, memo)? { report.memos += 1; } }
+    Ok(report)
+}
+
+/// Read the sessions of another IrohaType store: a scores.json or (with the sqlite feature) a scores.db.
+pub fn read_source(path: &Path) -> Result<Vec<ScoreRecord>> {
+    if path.extension().map(|e| e == "db" || e == "sqlite").unwrap_or(false) {
+        #[cfg(feature = "sqlite")]
+        {
+            let src = super::sqlite::SqliteStore::open(path)?;
+            return src.history(None, None)?.into_iter()
+                .map(|r| Ok(src.load_by_id(&r.id)?.unwrap_or(r)))
+                .collect();
+        }
+        #[cfg(not(feature = "sqlite"))]
+        bail!("{}: SQLite の読み込みには sqlite 機能が必要です（--features sqlite）", path.display());
+    }
+    if !path.is_file() { bail!("{} が見つかりません", path.display()); }
+    if path.extension().map(|e| e == super::replay::EXT).unwrap_or(false) {
+        return Ok(vec![super::replay::ReplayFile::read(path)?.into_record()]);
+    }
+    Ok(ScoreBook::from_json(&fs::read_to_string(path)?)?.history)
+}
+
+/// This machine's file in a sync folder.
+pub fn sync_file(dir: &Path, machine: &str) -> PathBuf { dir.join(format!("{}.scores.json", machine)) }
+
+/// Two-way merge with a synced folder (Dropbox etc.): every other machine's
+/// `<machine>.scores.json` is merged in, then the combined history is written to ours.
+/// Each machine only ever writes its own file, so the sync tool never sees edit conflicts.
+pub fn sync_folder(store: &mut dyn ScoreStore, dir: &Path, machine: &str) -> Result<MergeReport> {
+    fs::create_dir_all(dir)?;
+    let mine = sync_file(dir, machine);
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)?.flatten().map(|e| e.path())
+        .filter(|p| p != &mine && p.file_name().map(|n| n.to_string_lossy().ends_with(".scores.json")).unwrap_or(false))
+        .collect();
+    files.sort();
+    let mut report = MergeReport::default();
+    for f in files { report.absorb(merge_into(store, read_source(&f)?)?); }
+    store.save()?;
+    // aborted sessions included so every machine gets the full history; replays are always loaded by collect
+    let book = ScoreBook { history: super::export::collect(store, None, true)?, ..Default::default() };
+    crate::util::persist::write_atomic(&mine, serde_json::to_string(&book)?.as_bytes())?;
+    Ok(report)
+}
@@ -1,3 +1,89 @@
+pub mod export;
 pub mod json;
+pub mod memory;
+pub mod merge;
+pub mod replay;
 #[cfg(feature = "sqlite")] pub mod sqlite;
 
+use anyhow::Result;
+
+use json::ScoreRecord;
+
+/// Score backend used by the app. Every finished session is kept in an append-only
+/// history; the leaderboards are views over it. Records are identified by `ScoreRecord::id`.
+pub trait ScoreStore {
+    /// Append a session (an id is assigned if empty) and return
+    /// (rank position 1-based within its mode if within Top-100, is_personal_best).
+    fn insert_and_rank(&mut self, rec: ScoreRecord) -> Result<(Option<usize>, bool)>;
+    /// Fastest `n` sessions of a mode, plus pinned runs of the mode beyond `n`.
+    fn top(&self, mode: &str, n: usize) -> Result<Vec<ScoreRecord>>;
+    /// Fastest single words (records carry `word_display` and `time_sec`).
+    fn lap_top(&self, n: usize) -> Result<Vec<ScoreRecord>>;
+    /// Full record (including the replay) by id.
+    fn get(&self, id: &str) -> Result<Option<ScoreRecord>>;
+    /// Empty memo clears it.
+    fn set_memo(&mut self, id: &str, memo: String) -> Result<bool>;
+    fn set_pinned(&mut self, id: &str, pinned: bool) -> Result<bool>;
+    fn delete(&mut self, id: &str) -> Result<bool>;
+    /// Every stored session, oldest first (aborted ones included).
+    fn history(&self) -> Result<Vec<ScoreRecord>>;
+    /// Add records from another store (already de-duplicated by the caller). Returns how many were added.
+    fn import(&mut self, recs: Vec<ScoreRecord>) -> Result<usize> {
+        let n = recs.len();
+        for r in recs { self.insert_and_rank(r)?; }
+        Ok(n)
+    }
+    /// Persist pending changes (no-op for backends that write through).
+    fn save(&mut self) -> Result<()>;
+    /// Short backend name for the settings screen.
+    fn backend(&self) -> &'static str;
+}
+
+/// Open the backend named in the config ("json", "sqlite" or "memory") of the active profile.
+pub fn open(kind: &str) -> Result<Box<dyn ScoreStore>> { open_profile(kind, &crate::util::paths::profile()) }
+
+/// Open the backend of a given profile.
+pub fn open_profile(kind: &str, profile: &str) -> Result<Box<dyn ScoreStore>> {
+    let dir = crate::util::paths::profile_data_dir(profile);
+    match kind {
+        "json" => Ok(Box::new(json::ScoreBook::load_from(&dir.join("scores.json"))?)),
+        "memory" => Ok(Box::new(memory::MemoryStore::default())),
+        #[cfg(feature = "sqlite")]
+        "sqlite" => {
+            let mut s = sqlite::SqliteStore::open(&dir.join("scores.db"))?;
+            // 初回のみ既存の scores.json を取り込む
+            s.migrate_from_json(&dir.join("scores.json"))?;
+            Ok(Box::new(s))
+        }
+        #[cfg(not(feature = "sqlite"))]
+        "sqlite" => anyhow::bail!("このビルドは sqlite 機能なしでコンパイルされています（--features sqlite）"),
+        other => anyhow::bail!("不明なストア種別: {}", other),
+    }
+}
+
+/// Office leaderboard: the best run of every profile in `mode`, fastest first.
+/// Profiles whose store cannot be opened are skipped.
+pub fn office_leaderboard(mode: &str) -> Vec<(String, ScoreRecord)> {
+    let mut out: Vec<(String, ScoreRecord)> = crate::util::paths::profiles().into_iter().filter_map(|p| {
+        let cfg = std::fs::read_to_string(crate::util::paths::profile_config_dir(&p).join("config.json")).ok()
+            .and_then(|s| crate::util::config::AppConfig::from_json(&s).ok()).unwrap_or_default();
+        let best = open_profile(&cfg.store, &p).ok()?.top(mode, 1).ok()?.into_iter().next()?;
+        Some((p, best))
+    }).collect();
+    out.sort_by(|a, b| a.1.time_sec.partial_cmp(&b.1.time_sec).unwrap());
+    out
+}
+
+/// The `n` most consistent completed runs of `mode` (records without rhythm are left out),
+/// ties broken by time. Records with a replay are ranked on the rhythm recomputed from it,
+/// not on the stored (unsealed) one.
+pub fn top_by_consistency(history: Vec<ScoreRecord>, mode: &str, n: usize) -> Vec<ScoreRecord> {
+    let mut v: Vec<ScoreRecord> = history.into_iter().filter(|r| r.mode == mode && !r.aborted).filter_map(|mut r| {
+        if r.replay.is_some() { r.rhythm = r.compute_rhythm(); }
+        r.rhythm.is_some().then_some(r)
+    }).collect();
+    v.sort_by(|a, b| b.consistency().partial_cmp(&a.consistency()).unwrap_or(std::cmp::Ordering::Equal)
+        .then(a.time_sec.partial_cmp(&b.time_sec).unwrap_or(std::cmp::Ordering::Equal)));
+    v.truncate(n);
+    v
+}
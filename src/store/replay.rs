@@ -0,0 +1,246 @@
+// Compact replay encoding.
+//
+// Event stream (inside scores.json as base64, and in .irohareplay files):
+//   [version u8] [count varint] then per event:
+//   [flags u8: 1=ok 2=word changed 4=non-ASCII key] [dt varint, ms since previous event]
+//   [word delta zigzag varint, only if flag 2] [key: 1 byte, or len u8 + UTF-8 if flag 4]
+// Times are kept as whole milliseconds from the session start (deltas of the rounded
+// times, so nothing drifts). A typical keystroke takes 3 bytes instead of ~40 in JSON.
+//
+// A .irohareplay file is "IRPL" + file version u8 + header length varint + header JSON
+// (the record without events, its word sequence and the romaji rules text) + event stream.
+
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{Deserialize, Serialize};
+
+use super::json::{KeyEv, ScoreRecord};
+
+pub const EVENTS_VERSION: u8 = 1;
+pub const FILE_VERSION: u8 = 1;
+pub const MAGIC: &[u8; 4] = b"IRPL";
+pub const EXT: &str = "irohareplay";
+
+const F_OK: u8 = 1;
+const F_WORD: u8 = 2;
+const F_WIDE: u8 = 4;
+
+fn put_varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 { out.push((v as u8) | 0x80); v >>= 7; }
+    out.push(v as u8);
+}
+
+struct Reader<'a> { buf: &'a [u8], pos: usize }
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8> {
+        let b = *self.buf.get(self.pos).context("リプレイデータが途中で終わっています")?;
+        self.pos += 1;
+        Ok(b)
+    }
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8]> {
+        let s = self.buf.get(self.pos..self.pos + n).context("リプレイデータが途中で終わっています")?;
+        self.pos += n;
+        Ok(s)
+    }
+    fn varint(&mut self) -> Result<u64> {
+        let mut v = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            v |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 { return Ok(v); }
+        }
+        bail!("リプレイデータの数値が不正です")
+    }
+}
+
+/// Binary event stream.
+pub fn encode(evs: &[KeyEv]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(2 + evs.len() * 3);
+    out.push(EVENTS_VERSION);
+    put_varint(&mut out, evs.len() as u64);
+    let (mut prev_ms, mut prev_w) = (0u64, 0i64);
+    for e in evs {
+        let ms = (e.t.max(0.0) * 1000.0).round() as u64;
+        let key = e.c.as_bytes();
+        let wide = key.len() != 1;
+        let dw = e.w as i64 - prev_w;
+        let flags = if e.ok { F_OK } else { 0 } | if dw != 0 { F_WORD } else { 0 } | if wide { F_WIDE } else { 0 };
+        out.push(flags);
+        put_varint(&mut out, ms.saturating_sub(prev_ms));
+        if dw != 0 { put_varint(&mut out, ((dw << 1) ^ (dw >> 63)) as u64); }
+        if wide { out.push(key.len().min(255) as u8); out.extend_from_slice(&key[..key.len().min(255)]); } else { out.push(key[0]); }
+        prev_ms = prev_ms.max(ms);
+        prev_w = e.w as i64;
+    }
+    out
+}
+
+pub fn decode(buf: &[u8]) -> Result<Vec<KeyEv>> {
+    let mut r = Reader { buf, pos: 0 };
+    let ver = r.byte()?;
+    if ver != EVENTS_VERSION { bail!("未対応のリプレイ形式です (v{})", ver); }
+    let n = r.varint()? as usize;
+    let mut evs = Vec::with_capacity(n.min(buf.len()));
+    let (mut ms, mut w) = (0u64, 0i64);
+    for _ in 0..n {
+        let flags = r.byte()?;
+        // the stream comes from files; out-of-range numbers are an error, not an overflow
+        ms = ms.checked_add(r.varint()?).context("リプレイデータの数値が不正です")?;
+        if flags & F_WORD != 0 { let z = r.varint()?; w = w.checked_add(((z >> 1) as i64) ^ -((z & 1) as i64)).context("リプレイデータの数値が不正です")?; }
+        let c = if flags & F_WIDE != 0 {
+            let len = r.byte()? as usize;
+            String::from_utf8(r.bytes(len)?.to_vec()).context("リプレイのキーが不正です")?
+        } else { (r.byte()? as char).to_string() };
+        evs.push(KeyEv { t: ms as f64 / 1000.0, c, ok: flags & F_OK != 0, w: w.max(0) as usize });
+    }
+    Ok(evs)
+}
+
+/// `ScoreRecord::replay` as a base64 string. Reading also accepts the old array of objects.
+pub mod serde_events {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored { Compact(String), Legacy(Vec<KeyEv>) }
+
+    pub fn serialize<S: Serializer>(v: &Option<Vec<KeyEv>>, s: S) -> Result<S::Ok, S::Error> {
+        match v {
+            Some(evs) => s.serialize_str(&STANDARD.encode(encode(evs))),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Vec<KeyEv>>, D::Error> {
+        match Option::<Stored>::deserialize(d)? {
+            None => Ok(None),
+            Some(Stored::Legacy(v)) => Ok(Some(v)),
+            Some(Stored::Compact(s)) => {
+                let bytes = STANDARD.decode(s).map_err(serde::de::Error::custom)?;
+                decode(&bytes).map(Some).map_err(serde::de::Error::custom)
+            }
+        }
+    }
+}
+
+/// Speed curve (correct keystrokes / s since start) rebuilt from the events,
+/// so records no longer need the per-tick `speed_series`.
+pub fn speed_series(evs: &[KeyEv], end: f64, step: f64) -> Vec<(f64, f64)> {
+    let mut out = Vec::new();
+    let (mut ok, mut i, mut t) = (0usize, 0usize, step);
+    while t <= end + step / 2.0 {
+        while i < evs.len() && evs[i].t <= t { if evs[i].ok { ok += 1; } i += 1; }
+        out.push((t, ok as f64 / t));
+        t += step;
+    }
+    out
+}
+
+/// Short stable fingerprint (FNV-1a 64) used as the rules version.
+pub fn fingerprint(s: &str) -> String {
+    let mut h: u64 = 0xcbf29ce484222325;
+    for b in s.bytes() { h ^= b as u64; h = h.wrapping_mul(0x100000001b3); }
+    format!("{:016x}", h)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    pub app_version: String,
+    /// Romaji rules YAML the run was typed with (its fingerprint is `record.rules_version`)
+    pub rules: String,
+    /// The record without events / speed curve
+    pub record: ScoreRecord,
+}
+
+/// A standalone replay: everything needed to play the run back on another machine.
+#[derive(Debug, Clone)]
+pub struct ReplayFile { pub header: ReplayHeader, pub events: Vec<KeyEv> }
+
+impl ReplayFile {
+    pub fn from_record(rec: &ScoreRecord, rules: &str) -> Result<Self> {
+        let events = rec.replay.clone().with_context(|| format!("記録 {} にはリプレイがありません", rec.datetime))?;
+        let mut record = rec.clone();
+        record.replay = None;
+        record.speed_series = None;
+        Ok(Self { header: ReplayHeader { app_version: env!("CARGO_PKG_VERSION").into(), rules: rules.into(), record }, events })
+    }
+
+    pub fn into_record(self) -> ScoreRecord {
+        let mut rec = self.header.record;
+        rec.replay = Some(self.events);
+        rec
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let head = serde_json::to_vec(&self.header)?;
+        let mut out = Vec::with_capacity(head.len() + self.events.len() * 3 + 16);
+        out.extend_from_slice(MAGIC);
+        out.push(FILE_VERSION);
+        put_varint(&mut out, head.len() as u64);
+        out.extend_from_slice(&head);
+        out.extend_from_slice(&encode(&self.events));
+        Ok(out)
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Result<Self> {
+        if buf.len() < 5 || &buf[..4] != MAGIC { bail!("リプレイファイルではありません"); }
+        if buf[4] != FILE_VERSION { bail!("未対応のリプレイファイルです (v{})", buf[4]); }
+        let mut r = Reader { buf, pos: 5 };
+        let len = r.varint()? as usize;
+        let header: ReplayHeader = serde_json::from_slice(r.bytes(len)?).context("リプレイのヘッダが壊れています")?;
+        let events = decode(&buf[r.pos..])?;
+        Ok(Self { header, events })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        crate::util::paths::ensure_parent(path)?;
+        crate::util::persist::write_atomic(path, &self.to_bytes()?)
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        Self::from_bytes(&fs::read(path).with_context(|| format!("{} を読み込めません", path.display()))?)
+            .with_context(|| path.display().to_string())
+    }
+}
+
+/// File name for a record's replay (`<id>.irohareplay`).
+pub fn file_name(rec: &ScoreRecord) -> String { format!("{}.{}", if rec.id.is_empty() { "replay" } else { rec.id.as_str() }, EXT) }
+
+// Seeking on the details screen. Positions are event indices (the last event shown).
+
+/// Last event at or before `t` (None before the first keystroke).
+pub fn event_at(evs: &[KeyEv], t: f64) -> Option<usize> { evs.iter().rposition(|e| e.t <= t) }
+
+/// First keystroke of each word, in order.
+pub fn word_starts(evs: &[KeyEv]) -> Vec<usize> {
+    (0..evs.len()).filter(|&i| i == 0 || evs[i].w != evs[i - 1].w).collect()
+}
+
+/// First keystroke of the word after the one at `idx`.
+pub fn next_word(evs: &[KeyEv], idx: usize) -> Option<usize> { word_starts(evs).into_iter().find(|&s| s > idx) }
+
+/// Start of the word at `idx`, or of the previous word when already on its first keystroke.
+pub fn prev_word(evs: &[KeyEv], idx: usize) -> Option<usize> { word_starts(evs).into_iter().rev().find(|&s| s < idx) }
+
+pub fn next_miss(evs: &[KeyEv], idx: usize) -> Option<usize> { evs.iter().enumerate().skip(idx + 1).find(|(_, e)| !e.ok).map(|(i, _)| i) }
+
+pub fn prev_miss(evs: &[KeyEv], idx: usize) -> Option<usize> { evs[..idx.min(evs.len())].iter().rposition(|e| !e.ok) }
+
+/// Keys typed for word `w` up to event `upto` (inclusive), with their correctness.
+pub fn typed_keys(evs: &[KeyEv], upto: usize, w: usize) -> Vec<(&str, bool)> {
+    evs.iter().take(upto.saturating_add(1)).filter(|e| e.w == w).map(|e| (e.c.as_str(), e.ok)).collect()
+}
+
+/// Time typed by the user: seconds ("12.5") or minutes and seconds ("1:05.2").
+pub fn parse_time(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let t = match s.split_once(':') {
+        Some((m, sec)) => m.trim().parse::<u32>().ok()? as f64 * 60.0 + sec.trim().parse::<f64>().ok().filter(|v| *v < 60.0)?,
+        None => s.parse::<f64>().ok()?,
+    };
+    (t.is_finite() && t >= 0.0).then_some(t)
+}
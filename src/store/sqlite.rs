@@ -1,2 +1,298 @@
-// Optional SQLite backend (feature = "sqlite"). Not implemented in MVP.
+// SQLite score backend (feature = "sqlite").
+// Every session is kept; splits and keystrokes live in their own tables.
+// Leaderboards (Top-N per mode, Lap-10) are queries over those tables.
 
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use super::json::{new_id, KeyEv, ScoreBook, ScoreRecord, SplitRec};
+
+/// Schema migrations; index + 1 is the resulting `user_version`.
+const MIGRATIONS: &[&str] = &[
+    // v1: sessions / splits / keystrokes
+    "CREATE TABLE sessions (
+        id INTEGER PRIMARY KEY,
+        mode TEXT NOT NULL,
+        datetime TEXT NOT NULL UNIQUE,
+        time_sec REAL NOT NULL,
+        miss INTEGER NOT NULL,
+        timeloss_sec REAL NOT NULL,
+        wpm_top REAL NOT NULL,
+        wpm_worst REAL NOT NULL,
+        rank TEXT NOT NULL,
+        memo TEXT,
+        word_display TEXT,
+        session_opts TEXT,
+        speed_series TEXT
+    );
+    CREATE TABLE splits (
+        session_id INTEGER NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+        idx INTEGER NOT NULL,
+        word TEXT NOT NULL,
+        sec REAL NOT NULL,
+        miss INTEGER NOT NULL,
+        PRIMARY KEY (session_id, idx)
+    );
+    CREATE TABLE keystrokes (
+        session_id INTEGER NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+        seq INTEGER NOT NULL,
+        t REAL NOT NULL,
+        c TEXT NOT NULL,
+        ok INTEGER NOT NULL,
+        w INTEGER NOT NULL,
+        PRIMARY KEY (session_id, seq)
+    );
+    CREATE INDEX sessions_mode_time ON sessions(mode, time_sec);
+    CREATE INDEX sessions_datetime ON sessions(datetime);
+    CREATE INDEX splits_sec ON splits(sec);",
+    // v2: key/value metadata (one-shot JSON import flag etc.)
+    "CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);",
+    // v3: stable record ids and aborted sessions
+    "ALTER TABLE sessions ADD COLUMN uid TEXT;
+    ALTER TABLE sessions ADD COLUMN aborted INTEGER NOT NULL DEFAULT 0;
+    UPDATE sessions SET uid = replace(substr(datetime, 1, 19), ':', '') || '-' || lower(hex(randomblob(4))) WHERE uid IS NULL;
+    CREATE UNIQUE INDEX sessions_uid ON sessions(uid);",
+    // v4: pinned runs
+    "ALTER TABLE sessions ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;",
+    // v5: word sequence (JSON) and romaji rules fingerprint for replay playback
+    "ALTER TABLE sessions ADD COLUMN word_seq TEXT;
+    ALTER TABLE sessions ADD COLUMN rules_version TEXT;",
+    // v6: content hash (tamper detection)
+    "ALTER TABLE sessions ADD COLUMN hash TEXT;",
+    // v7: rhythm / consistency metrics (JSON; backfilled from the keystrokes in `migrate`)
+    "ALTER TABLE sessions ADD COLUMN rhythm TEXT;",
+];
+
+pub struct SqliteStore { conn: Connection }
+
+const SESSION_COLS: &str = "id, mode, datetime, time_sec, miss, timeloss_sec, wpm_top, wpm_worst, rank, memo, word_display, session_opts, speed_series, uid, aborted, pinned, word_seq, rules_version, hash, rhythm";
+
+impl SqliteStore {
+    pub fn path() -> PathBuf { crate::util::paths::scores_db() }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent() { if !dir.as_os_str().is_empty() { std::fs::create_dir_all(dir)?; } }
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> { Self::init(Connection::open_in_memory()?) }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch("PRAGMA foreign_keys = ON; PRAGMA journal_mode = WAL;")?;
+        let mut s = Self { conn };
+        s.migrate()?;
+        Ok(s)
+    }
+
+    pub fn schema_version(&self) -> Result<u32> {
+        Ok(self.conn.pragma_query_value(None, "user_version", |r| r.get(0))?)
+    }
+
+    fn migrate(&mut self) -> Result<()> {
+        let cur = self.schema_version()? as usize;
+        for (i, sql) in MIGRATIONS.iter().enumerate().skip(cur) {
+            let tx = self.conn.transaction()?;
+            tx.execute_batch(sql)?;
+            tx.pragma_update(None, "user_version", (i + 1) as u32)?;
+            tx.commit()?;
+        }
+        if cur < 7 { self.backfill_rhythm()?; }
+        Ok(())
+    }
+
+    /// Compute the rhythm of sessions stored before v7 from their keystrokes.
+    fn backfill_rhythm(&mut self) -> Result<()> {
+        let uids: Vec<String> = self.conn.prepare("SELECT uid FROM sessions WHERE rhythm IS NULL")?
+            .query_map([], |r| r.get(0))?.collect::<rusqlite::Result<Vec<_>>>()?;
+        for uid in uids {
+            let Some(rec) = self.load_by_id(&uid)? else { continue };
+            let Some(rh) = rec.compute_rhythm() else { continue };
+            self.conn.execute("UPDATE sessions SET rhythm = ?1 WHERE uid = ?2", params![serde_json::to_string(&rh)?, uid])?;
+        }
+        Ok(())
+    }
+
+    /// Store a session with its splits and keystrokes. Returns the row id
+    /// (the existing one if a session with the same uid or datetime is already stored).
+    /// Records without an id get a fresh one.
+    pub fn insert(&mut self, rec: &ScoreRecord) -> Result<i64> {
+        let uid = if rec.id.is_empty() { new_id() } else { rec.id.clone() };
+        let tx = self.conn.transaction()?;
+        if let Some(id) = tx.query_row("SELECT id FROM sessions WHERE uid = ?1 OR datetime = ?2", [&uid, &rec.datetime], |r| r.get(0)).optional()? {
+            return Ok(id);
+        }
+        tx.execute(
+            &format!("INSERT INTO sessions ({}) VALUES (NULL, ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)", SESSION_COLS),
+            params![
+                rec.mode, rec.datetime, rec.time_sec, rec.miss, rec.timeloss_sec, rec.wpm_top, rec.wpm_worst, rec.rank,
+                rec.memo, rec.word_display,
+                rec.session.as_ref().map(serde_json::to_string).transpose()?,
+                rec.speed_series.as_ref().map(serde_json::to_string).transpose()?,
+                uid, rec.aborted, rec.pinned,
+                if rec.word_seq.is_empty() { None } else { Some(serde_json::to_string(&rec.word_seq)?) },
+                rec.rules_version, rec.hash,
+                rec.rhythm.as_ref().map(serde_json::to_string).transpose()?,
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        {
+            let mut st = tx.prepare("INSERT INTO splits (session_id, idx, word, sec, miss) VALUES (?1, ?2, ?3, ?4, ?5)")?;
+            for (i, s) in rec.splits.iter().enumerate() { st.execute(params![id, i as i64, s.word, s.sec, s.miss])?; }
+            let mut st = tx.prepare("INSERT INTO keystrokes (session_id, seq, t, c, ok, w) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?;
+            for (i, e) in rec.replay.iter().flatten().enumerate() { st.execute(params![id, i as i64, e.t, e.c, e.ok, e.w as i64])?; }
+        }
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Same contract as `ScoreBook::insert_and_rank`: rank within the mode if in the Top-100, and PB flag.
+    pub fn insert_and_rank(&mut self, rec: &ScoreRecord) -> Result<(Option<usize>, bool)> {
+        let prev_best: Option<f64> = self.conn.query_row("SELECT MIN(time_sec) FROM sessions WHERE mode = ?1 AND aborted = 0", [&rec.mode], |r| r.get(0))?;
+        self.insert(rec)?;
+        if rec.aborted { return Ok((None, false)); }
+        let faster: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM sessions WHERE mode = ?1 AND aborted = 0 AND (time_sec < ?2 OR (time_sec = ?2 AND datetime < ?3))",
+            params![rec.mode, rec.time_sec, rec.datetime], |r| r.get(0))?;
+        let rank = faster as usize + 1;
+        Ok((if rank <= 100 { Some(rank) } else { None }, prev_best.map(|b| rec.time_sec < b).unwrap_or(true)))
+    }
+
+    /// Fastest `n` sessions of a mode (with splits; keystrokes are loaded on demand).
+    pub fn top(&self, mode: &str, n: usize) -> Result<Vec<ScoreRecord>> {
+        let mut st = self.conn.prepare(&format!(
+            "SELECT {} FROM sessions WHERE mode = ?1 AND aborted = 0 AND (pinned = 1 OR id IN
+                (SELECT id FROM sessions WHERE mode = ?1 AND aborted = 0 ORDER BY time_sec, datetime LIMIT ?2))
+             ORDER BY time_sec, datetime", SESSION_COLS))?;
+        let rows = st.query_map(params![mode, n as i64], row_to_session)?.collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.into_iter().map(|(id, r)| self.with_splits(id, r)).collect()
+    }
+
+    /// Fastest single words across all sessions, shaped like `ScoreBook::lap`.
+    pub fn lap_top(&self, n: usize) -> Result<Vec<ScoreRecord>> {
+        let mut st = self.conn.prepare(
+            "SELECT s.mode, s.datetime, p.word, p.sec, p.miss, s.uid FROM splits p JOIN sessions s ON s.id = p.session_id ORDER BY p.sec LIMIT ?1")?;
+        let rows = st.query_map([n as i64], |r| Ok(ScoreRecord {
+            mode: r.get(0)?, datetime: r.get(1)?, word_display: Some(r.get(2)?), time_sec: r.get(3)?, miss: r.get(4)?, id: r.get(5)?, ..Default::default()
+        }))?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    /// Sessions between two datetimes (RFC 3339 prefixes such as "2026-10-01"; inclusive), oldest first.
+    pub fn history(&self, from: Option<&str>, to: Option<&str>) -> Result<Vec<ScoreRecord>> {
+        let mut st = self.conn.prepare(&format!(
+            "SELECT {} FROM sessions WHERE (?1 IS NULL OR datetime >= ?1) AND (?2 IS NULL OR substr(datetime, 1, length(?2)) <= ?2) ORDER BY datetime", SESSION_COLS))?;
+        let rows = st.query_map(params![from, to], row_to_session)?.collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.into_iter().map(|(id, r)| self.with_splits(id, r)).collect()
+    }
+
+    /// Full record including the keystroke replay, by datetime.
+    pub fn load(&self, datetime: &str) -> Result<Option<ScoreRecord>> { self.load_where("datetime", datetime) }
+
+    /// Full record including the keystroke replay, by record id.
+    pub fn load_by_id(&self, uid: &str) -> Result<Option<ScoreRecord>> { self.load_where("uid", uid) }
+
+    fn load_where(&self, col: &str, value: &str) -> Result<Option<ScoreRecord>> {
+        let row = self.conn.query_row(&format!("SELECT {} FROM sessions WHERE {} = ?1", SESSION_COLS, col), [value], row_to_session).optional()?;
+        let Some((id, rec)) = row else { return Ok(None) };
+        let mut rec = self.with_splits(id, rec)?;
+        let mut st = self.conn.prepare("SELECT t, c, ok, w FROM keystrokes WHERE session_id = ?1 ORDER BY seq")?;
+        let evs = st.query_map([id], |r| Ok(KeyEv { t: r.get(0)?, c: r.get(1)?, ok: r.get(2)?, w: r.get::<_, i64>(3)? as usize }))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rec.replay = if evs.is_empty() { None } else { Some(evs) };
+        Ok(Some(rec))
+    }
+
+    pub fn set_memo_by_datetime(&mut self, datetime: &str, memo: String) -> Result<bool> {
+        Ok(self.conn.execute("UPDATE sessions SET memo = ?1 WHERE datetime = ?2", params![memo, datetime])? > 0)
+    }
+
+    pub fn set_memo_by_id(&mut self, uid: &str, memo: String) -> Result<bool> {
+        Ok(self.conn.execute("UPDATE sessions SET memo = NULLIF(?1, '') WHERE uid = ?2", params![memo, uid])? > 0)
+    }
+
+    pub fn set_pinned_by_id(&mut self, uid: &str, pinned: bool) -> Result<bool> {
+        Ok(self.conn.execute("UPDATE sessions SET pinned = ?1 WHERE uid = ?2", params![pinned, uid])? > 0)
+    }
+
+    pub fn delete_by_id(&mut self, uid: &str) -> Result<bool> {
+        Ok(self.conn.execute("DELETE FROM sessions WHERE uid = ?1", [uid])? > 0)
+    }
+
+    pub fn delete_by_datetime(&mut self, datetime: &str) -> Result<bool> {
+        Ok(self.conn.execute("DELETE FROM sessions WHERE datetime = ?1", [datetime])? > 0)
+    }
+
+    pub fn count(&self) -> Result<usize> {
+        Ok(self.conn.query_row("SELECT COUNT(*) FROM sessions", [], |r| r.get::<_, i64>(0))? as usize)
+    }
+
+    /// One-shot migration from a JSON scorebook. Runs only once per database;
+    /// returns the number of sessions imported (0 when already done or no file).
+    pub fn migrate_from_json(&mut self, json_path: &Path) -> Result<usize> {
+        let done: Option<String> = self.conn.query_row("SELECT value FROM meta WHERE key = 'json_imported'", [], |r| r.get(0)).optional()?;
+        if done.is_some() || !json_path.exists() { return Ok(0); }
+        let book = ScoreBook::from_json(&std::fs::read_to_string(json_path)?)?;
+        let n = self.import_scorebook(&book)?;
+        self.conn.execute("INSERT INTO meta (key, value) VALUES ('json_imported', ?1)", [chrono::Local::now().to_rfc3339()])?;
+        Ok(n)
+    }
+
+    /// Import the history of a scorebook (Lap entries are derived from splits, so they are skipped).
+    pub fn import_scorebook(&mut self, book: &ScoreBook) -> Result<usize> {
+        let before = self.count()?;
+        for r in &book.history { self.insert(r)?; }
+        Ok(self.count()? - before)
+    }
+
+    fn with_splits(&self, id: i64, mut rec: ScoreRecord) -> Result<ScoreRecord> {
+        let mut st = self.conn.prepare("SELECT word, sec, miss FROM splits WHERE session_id = ?1 ORDER BY idx")?;
+        rec.splits = st.query_map([id], |r| Ok(SplitRec { word: r.get(0)?, sec: r.get(1)?, miss: r.get(2)? }))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rec)
+    }
+}
+
+fn row_to_session(r: &Row) -> rusqlite::Result<(i64, ScoreRecord)> {
+    let opts: Option<String> = r.get(11)?;
+    let series: Option<String> = r.get(12)?;
+    let word_seq: Option<String> = r.get(16)?;
+    let rhythm: Option<String> = r.get(19)?;
+    Ok((r.get(0)?, ScoreRecord {
+        mode: r.get(1)?,
+        datetime: r.get(2)?,
+        time_sec: r.get(3)?,
+        miss: r.get(4)?,
+        timeloss_sec: r.get(5)?,
+        wpm_top: r.get(6)?,
+        wpm_worst: r.get(7)?,
+        rank: r.get(8)?,
+        memo: r.get(9)?,
+        word_display: r.get(10)?,
+        session: opts.and_then(|s| serde_json::from_str(&s).ok()),
+        speed_series: series.and_then(|s| serde_json::from_str(&s).ok()),
+        id: r.get(13)?,
+        aborted: r.get(14)?,
+        pinned: r.get(15)?,
+        word_seq: word_seq.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or_default(),
+        rules_version: r.get(17)?,
+        hash: r.get(18)?,
+        rhythm: rhythm.and_then(|s| serde_json::from_str(&s).ok()),
+        ..Default::default()
+    }))
+}
+
+impl super::ScoreStore for SqliteStore {
+    fn insert_and_rank(&mut self, rec: ScoreRecord) -> Result<(Option<usize>, bool)> { SqliteStore::insert_and_rank(self, &rec) }
+    fn top(&self, mode: &str, n: usize) -> Result<Vec<ScoreRecord>> { SqliteStore::top(self, mode, n) }
+    fn lap_top(&self, n: usize) -> Result<Vec<ScoreRecord>> { SqliteStore::lap_top(self, n) }
+    fn get(&self, id: &str) -> Result<Option<ScoreRecord>> { self.load_by_id(id) }
+    fn set_memo(&mut self, id: &str, memo: String) -> Result<bool> { self.set_memo_by_id(id, memo) }
+    fn set_pinned(&mut self, id: &str, pinned: bool) -> Result<bool> { self.set_pinned_by_id(id, pinned) }
+    fn delete(&mut self, id: &str) -> Result<bool> { self.delete_by_id(id) }
+    fn history(&self) -> Result<Vec<ScoreRecord>> { SqliteStore::history(self, None, None) }
+    // every statement commits immediately
+    fn save(&mut self) -> Result<()> { Ok(()) }
+    fn backend(&self) -> &'static str { "sqlite" }
+}
@@ -0,0 +1,120 @@
+// asciinema v2 export: a stored replay is re-typed into a Game and drawn with the
+// play screen (ui::play::draw_game) into a headless buffer at the recorded times.
+// Each frame is written as the ANSI needed to turn the previous frame into it.
+
+use std::{fmt::Write as _, path::{Path, PathBuf}};
+
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, buffer::{Buffer, Cell}, prelude::*};
+
+use crate::engine::{game::Game, romaji::RomajiRules};
+use crate::store::json::ScoreRecord;
+
+pub const EXT: &str = "cast";
+/// Timer redraws between keystrokes (the play screen shows tenths of a second)
+const TICK: f64 = 0.1;
+/// The last frame stays on screen this long
+const HOLD: f64 = 1.5;
+
+/// Render `rec` as an asciinema v2 recording of a `width` x `height` terminal.
+pub fn render(rec: &ScoreRecord, rules: &RomajiRules, width: u16, height: u16) -> Result<String> {
+    let evs = rec.replay.as_deref().with_context(|| format!("記録 {} にはリプレイがありません", rec.datetime))?;
+    if rec.word_seq.is_empty() { anyhow::bail!("記録 {} には出題語がないため再現できません", rec.datetime); }
+    let mut g = Game::for_replay(&rec.mode, rec.word_seq.clone(), rules.clone());
+    g.start();
+    let mut term = Terminal::new(TestBackend::new(width, height))?;
+    let mut prev = Buffer::empty(Rect::new(0, 0, width, height));
+
+    let header = serde_json::json!({
+        "version": 2, "width": width, "height": height,
+        "timestamp": chrono::DateTime::parse_from_rfc3339(&rec.datetime).map(|d| d.timestamp()).unwrap_or(0),
+        "title": format!("IrohaType {} {:.3}s", rec.mode, rec.time_sec),
+        "env": { "TERM": "xterm-256color" },
+    });
+    let mut out = format!("{}\n", header);
+    let mut frame = |g: &Game, t: f64, out: &mut String, first: bool| -> Result<()> {
+        term.draw(|f| super::play::draw_game(f, f.size(), g, None, None))?;
+        let buf = term.backend().buffer().clone();
+        let mut data = if first { "\x1b[?25l\x1b[2J\x1b[H".to_string() } else { String::new() };
+        data.push_str(&ansi_diff(&prev, &buf));
+        if !data.is_empty() { out.push_str(&serde_json::to_string(&((t * 1000.0).round() / 1000.0, "o", data))?); out.push('\n'); }
+        prev = buf;
+        Ok(())
+    };
+
+    g.set_clock(0.0);
+    frame(&g, 0.0, &mut out, true)?;
+    let (mut tick, mut word_start) = (TICK, 0.0);
+    for e in evs {
+        while tick < e.t { g.set_clock(tick); frame(&g, tick, &mut out, false)?; tick += TICK; }
+        g.set_clock(e.t);
+        let before = g.splits.len();
+        if let Some(c) = e.c.chars().next() { g.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))?; }
+        // lap times as stored (the game would measure them with the wall clock)
+        if let Some(s) = g.splits.get_mut(before) {
+            s.sec = rec.splits.get(before).map(|r| r.sec).unwrap_or(e.t - word_start);
+            word_start = e.t;
+        }
+        frame(&g, e.t, &mut out, false)?;
+    }
+    let end = rec.time_sec.max(evs.last().map(|e| e.t).unwrap_or(0.0));
+    while tick < end { g.set_clock(tick); frame(&g, tick, &mut out, false)?; tick += TICK; }
+    g.set_clock(end);
+    frame(&g, end, &mut out, false)?;
+    out.push_str(&serde_json::to_string(&(((end + HOLD) * 1000.0).round() / 1000.0, "o", "\x1b[0m\x1b[?25h"))?);
+    out.push('\n');
+    Ok(out)
+}
+
+/// Write the cast next to the other exports (`<dir>/<id>.cast`).
+pub fn write(dir: &Path, rec: &ScoreRecord, rules: &RomajiRules, width: u16, height: u16) -> Result<PathBuf> {
+    let path = dir.join(format!("{}.{}", if rec.id.is_empty() { "replay" } else { rec.id.as_str() }, EXT));
+    write_to(&path, rec, rules, width, height)?;
+    Ok(path)
+}
+
+pub fn write_to(path: &Path, rec: &ScoreRecord, rules: &RomajiRules, width: u16, height: u16) -> Result<()> {
+    let text = render(rec, rules, width, height)?;
+    crate::util::paths::ensure_parent(path)?;
+    crate::util::persist::write_atomic(path, text.as_bytes())
+}
+
+/// Cursor moves + SGR + text that turn `prev` into `next`.
+fn ansi_diff(prev: &Buffer, next: &Buffer) -> String {
+    let mut out = String::new();
+    let mut cursor: Option<(u16, u16)> = None;
+    let mut style: Option<String> = None;
+    for (x, y, cell) in prev.diff(next) {
+        if cursor != Some((x, y)) { let _ = write!(out, "\x1b[{};{}H", y + 1, x + 1); }
+        let sgr = sgr(cell);
+        if style.as_deref() != Some(sgr.as_str()) { out.push_str(&sgr); style = Some(sgr); }
+        out.push_str(cell.symbol());
+        cursor = Some((x + unicode_width::UnicodeWidthStr::width(cell.symbol()).max(1) as u16, y));
+    }
+    out
+}
+
+fn sgr(cell: &Cell) -> String {
+    let mut codes = vec!["0".to_string()];
+    let m = cell.modifier;
+    for (flag, code) in [(Modifier::BOLD, "1"), (Modifier::DIM, "2"), (Modifier::ITALIC, "3"), (Modifier::UNDERLINED, "4"), (Modifier::SLOW_BLINK, "5"), (Modifier::REVERSED, "7"), (Modifier::CROSSED_OUT, "9")] {
+        if m.contains(flag) { codes.push(code.into()); }
+    }
+    if let Some(c) = color(cell.fg, false) { codes.push(c); }
+    if let Some(c) = color(cell.bg, true) { codes.push(c); }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn color(c: Color, bg: bool) -> Option<String> {
+    let base = |n: u8| Some(format!("{}", n + if bg { 10 } else { 0 }));
+    match c {
+        Color::Reset => None,
+        Color::Black => base(30), Color::Red => base(31), Color::Green => base(32), Color::Yellow => base(33),
+        Color::Blue => base(34), Color::Magenta => base(35), Color::Cyan => base(36), Color::Gray => base(37),
+        Color::DarkGray => base(90), Color::LightRed => base(91), Color::LightGreen => base(92), Color::LightYellow => base(93),
+        Color::LightBlue => base(94), Color::LightMagenta => base(95), Color::LightCyan => base(96), Color::White => base(97),
+        Color::Indexed(i) => Some(format!("{};5;{}", if bg { 48 } else { 38 }, i)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", if bg { 48 } else { 38 }, r, g, b)),
+    }
+}
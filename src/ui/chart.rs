@@ -3,7 +3,7 @@ use crate::store::json::ScoreRecord;
 
 pub fn draw_speed_chart(f: &mut Frame, area: Rect, rec: &ScoreRecord) {
     // Build datasets: speed points and moving average (2.0s window)
-    let pts: Vec<(f64,f64)> = rec.speed_series.clone().unwrap_or_default();
+    let pts = speed_points(rec);
     let ma = moving_average(&pts, 2.0);
     let data1: Vec<(f64,f64)> = pts.clone();
     let data2: Vec<(f64,f64)> = ma;
@@ -21,6 +21,32 @@ pub fn draw_speed_chart(f: &mut Frame, area: Rect, rec: &ScoreRecord) {
     f.render_widget(chart, area);
 }
 
+fn speed_points(rec: &ScoreRecord) -> Vec<(f64,f64)> {
+    rec.speed_series.clone()
+        .or_else(|| rec.replay.as_ref().map(|evs| crate::store::replay::speed_series(evs, rec.time_sec, 0.1)))
+        .unwrap_or_default()
+}
+
+/// Two runs' speed curves (2s moving average) on the same axes; `at` draws the replay position.
+pub fn draw_speed_compare(f: &mut Frame, area: Rect, a: &ScoreRecord, b: &ScoreRecord, at: Option<f64>) {
+    let da = moving_average(&speed_points(a), 2.0);
+    let db = moving_average(&speed_points(b), 2.0);
+    let max_x = da.iter().chain(db.iter()).map(|p| p.0).fold(1.0, f64::max);
+    let max_y = da.iter().chain(db.iter()).map(|p| p.1).fold(1.0, f64::max) * 1.1;
+    let cursor: Vec<(f64,f64)> = at.map(|t| (0..=10).map(|i| (t, max_y * i as f64 / 10.0)).collect()).unwrap_or_default();
+
+    let mut sets = vec![
+        Dataset::default().name("A").marker(symbols::Marker::Braille).style(Style::default().fg(Color::Cyan)).data(&da),
+        Dataset::default().name("B").marker(symbols::Marker::Braille).style(Style::default().fg(Color::Magenta)).data(&db),
+    ];
+    if !cursor.is_empty() { sets.push(Dataset::default().marker(symbols::Marker::Dot).style(Style::default().fg(Color::DarkGray)).data(&cursor)); }
+    let chart = Chart::new(sets)
+        .block(Block::default().title("速度推移 (MA 2s)").borders(Borders::ALL))
+        .x_axis(Axis::default().title("sec").bounds([0.0, max_x]))
+        .y_axis(Axis::default().title("keystrokes/s").bounds([0.0, max_y]));
+    f.render_widget(chart, area);
+}
+
 fn moving_average(pts: &[(f64,f64)], window_sec: f64) -> Vec<(f64,f64)> {
     let mut out = Vec::with_capacity(pts.len());
     for (i, (tx, _)) in pts.iter().enumerate() {
@@ -0,0 +1,74 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::app::App;
+use crate::store::{json::ScoreRecord, replay};
+
+pub fn draw(f: &mut Frame, app: &mut App) {
+    let area = f.size();
+    let stage = super::centered(area, app.cfg.stage_w, app.cfg.stage_h);
+    f.render_widget(Clear, area);
+    let v = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // header
+            Constraint::Length(6), // both replays
+            Constraint::Min(8),    // aligned splits + summary/chart
+            Constraint::Length(1), // footer
+        ])
+        .split(stage);
+    let Some(c) = &app.compare else { return };
+    let synced = c.cmp.same_words && c.a.replay.is_some() && c.b.replay.is_some();
+
+    f.render_widget(Paragraph::new(format!("[ESC] ランキングへ  —  記録比較  A: {}  /  B: {}", c.a.datetime, c.b.datetime)), v[0]);
+
+    // Top: A | B replays on one clock
+    let top = Layout::default().direction(Direction::Horizontal).constraints([Constraint::Percentage(50), Constraint::Percentage(50)]).split(v[1]);
+    for (side, rec, area, color) in [("A", &c.a, top[0], Color::Cyan), ("B", &c.b, top[1], Color::Magenta)] {
+        let title = Span::styled(format!(" {} {:.3}s  ミス{} ", side, rec.time_sec, rec.miss), Style::default().fg(color).add_modifier(Modifier::BOLD));
+        let upto = if synced { rec.replay.as_deref().and_then(|evs| replay::event_at(evs, c.replay.time)) } else { None };
+        let body = if synced || rec.replay.is_none() { Line::from(super::details::progress_spans(rec, &app.words, upto)) }
+            else { Line::from(Span::styled("語の並びが異なるため同期再生はできません", Style::default().fg(Color::Gray))) };
+        f.render_widget(Paragraph::new(body).wrap(Wrap { trim: false }).block(Block::default().borders(Borders::ALL).title(title)), area);
+    }
+
+    let mid = Layout::default().direction(Direction::Horizontal).constraints([Constraint::Percentage(55), Constraint::Percentage(45)]).split(v[2]);
+
+    // Aligned splits; the largest differences are marked
+    let big = c.cmp.largest(3);
+    let sec = |s: Option<f64>| s.map(|s| format!("{:>6.3}", s)).unwrap_or_else(|| "     -".into());
+    let rows: Vec<Row> = c.cmp.rows.iter().enumerate().skip(c.scroll).map(|(i, r)| {
+        let d = r.delta();
+        let dstyle = match d { Some(d) if d < 0.0 => Style::default().fg(Color::Green), Some(d) if d > 0.0 => Style::default().fg(Color::Red), _ => Style::default().fg(Color::Gray) };
+        let hot = big.contains(&i);
+        Row::new(vec![
+            Cell::from(format!("{:>3}", i + 1)),
+            Cell::from(if hot { "◆" } else { " " }).style(Style::default().fg(Color::Yellow)),
+            Cell::from(r.word.clone()),
+            Cell::from(format!("{}{}", sec(r.a), if r.miss_a > 0 { "*" } else { " " })),
+            Cell::from(format!("{}{}", sec(r.b), if r.miss_b > 0 { "*" } else { " " })),
+            Cell::from(d.map(|d| format!("{:>+7.3}", d)).unwrap_or_else(|| "      -".into())).style(dstyle),
+        ]).style(if hot { Style::default().add_modifier(Modifier::BOLD) } else { Style::default() })
+    }).collect();
+    let table = Table::new(rows, [Constraint::Length(3), Constraint::Length(1), Constraint::Min(6), Constraint::Length(7), Constraint::Length(7), Constraint::Length(7)])
+        .header(Row::new(vec!["#", "", "語", "A", "B", "B-A"]).style(Style::default().fg(Color::Yellow)))
+        .block(Block::default().borders(Borders::ALL).title(format!("語ごとのタイム（共通 {} 語）", c.cmp.matched())))
+        .column_spacing(1);
+    f.render_widget(table, mid[0]);
+
+    let right = Layout::default().direction(Direction::Vertical).constraints([Constraint::Length(7), Constraint::Min(6)]).split(mid[1]);
+    let delta = |d: f64, unit: &str| Span::styled(format!("{:+.3}{}", d, unit), Style::default().fg(if d < 0.0 { Color::Green } else if d > 0.0 { Color::Red } else { Color::Gray }));
+    let summary = vec![
+        Line::from(vec![Span::raw(format!("Time  {:>7.3}s → {:>7.3}s  ", c.a.time_sec, c.b.time_sec)), delta(c.cmp.time_delta, "s")]),
+        Line::from(vec![Span::raw(format!("Miss  {:>7} → {:>7}   ", c.a.miss, c.b.miss)), Span::styled(format!("{:+}", c.cmp.miss_delta), Style::default().fg(if c.cmp.miss_delta < 0 { Color::Green } else if c.cmp.miss_delta > 0 { Color::Red } else { Color::Gray }))]),
+        Line::from(format!("Avg   {:>7.3}s → {:>7.3}s", avg(&c.a), avg(&c.b))),
+        Line::from(format!("差が大きい語: {}", big.iter().map(|&i| c.cmp.rows[i].word.as_str()).collect::<Vec<_>>().join(" / "))),
+        Line::from(Span::styled(if synced { format!("同期再生 {:.1}s  x{:.2}", c.replay.time, c.replay.speed) } else { "同期再生なし（語の並びが異なる）".into() }, Style::default().fg(Color::Gray))),
+    ];
+    f.render_widget(Paragraph::new(summary).block(Block::default().borders(Borders::ALL).title("比較")), right[0]);
+    super::chart::draw_speed_compare(f, right[1], &c.a, &c.b, synced.then_some(c.replay.time));
+
+    f.render_widget(Paragraph::new("Space: 同期再生/停止  ←/→: ±1秒  Home/End  +/-: 速度  S: 最初から  ↑/↓: スクロール  X: AとBを入替  ESC/R: ランキングへ"), v[3]);
+}
+
+fn avg(rec: &ScoreRecord) -> f64 {
+    if rec.splits.is_empty() { 0.0 } else { rec.splits.iter().map(|s| s.sec).sum::<f64>() / rec.splits.len() as f64 }
+}
@@ -1,6 +1,7 @@
 use ratatui::{prelude::*, widgets::*};
-use crate::app::App;
-use crate::engine::game::WordEntry;
+use crate::app::{App, DetailPanel};
+use crate::engine::{self, game::WordEntry};
+use crate::store::{json::ScoreRecord, replay};
 // use crate::engine::romaji::{RomajiMatcher, RomajiRules};
 
 pub fn draw(f: &mut Frame, app: &mut App) {
@@ -12,21 +13,28 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         .constraints([
             Constraint::Length(1), // header
             Constraint::Length(6), // jp/roma line (replay overlay)
+            Constraint::Length(1), // timeline
             Constraint::Min(8),    // lists and metrics + chart
             Constraint::Length(1), // footer
         ])
         .split(stage);
 
-    f.render_widget(Paragraph::new("[ESC] 戻る  /  [R] ランキングへ  —  記録詳細"), v[0]);
+    let head = match &app.detail {
+        Some(rec) => format!("[ESC] 戻る  /  [R] ランキングへ  —  記録詳細 {}/{}  {}{}{}", app.detail_pos + 1, app.detail_ids.len(), rec.datetime,
+            if rec.aborted { "  (中断)" } else { "" }, rec.memo.as_deref().map(|m| format!("  「{}」", m)).unwrap_or_default()),
+        None => "[ESC] 戻る  /  [R] ランキングへ  —  記録詳細".into(),
+    };
+    f.render_widget(Paragraph::new(head), v[0]);
 
-    if let Some(rec) = &app.last_result {
+    if let Some(rec) = &app.detail {
         // Top: romaji words line + right times + replay overlay
         let top = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(78), Constraint::Percentage(22)])
             .split(v[1]);
 
-        let para = Paragraph::new(Line::from(replay_line_spans(app, rec)))
+        let inner_w = top[0].width.saturating_sub(2) as usize;
+        let para = Paragraph::new(vec![Line::from(replay_line_spans(app, rec)), typed_line(app, rec, inner_w)])
             .wrap(Wrap{ trim:false })
             .block(Block::default().borders(Borders::ALL).title("Replay"));
         f.render_widget(para, top[0]);
@@ -39,18 +47,24 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         let list = Paragraph::new(times).block(Block::default().borders(Borders::ALL).title("Time/word"));
         f.render_widget(list, top[1]);
 
+        timeline(f, v[2], app, rec);
+
         // Middle: three columns (fast, slow, metrics + chart under)
         let mid = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(34), Constraint::Percentage(34), Constraint::Percentage(32)])
-            .split(v[2]);
+            .split(v[3]);
 
         // Fastest (by sec)
         let mut sorted = rec.splits.clone();
         sorted.sort_by(|a,b| a.sec.partial_cmp(&b.sec).unwrap());
         let fast_lines: Vec<Line> = sorted.iter().take(7).map(|s| Line::from(format!("{:>6.3}s  {}", s.sec, s.word))).collect();
-        let fast = Paragraph::new(fast_lines).block(Block::default().borders(Borders::ALL).title("速い語句"));
-        f.render_widget(fast, mid[0]);
+        match app.detail_panel {
+            DetailPanel::Fastest => f.render_widget(Paragraph::new(fast_lines).block(Block::default().borders(Borders::ALL).title("速い語句")), mid[0]),
+            DetailPanel::Latency => f.render_widget(Paragraph::new(latency_lines(app)).block(Block::default().borders(Borders::ALL).title("初速・モーラ別")), mid[0]),
+            DetailPanel::Rhythm => rhythm_panel(f, mid[0], rec),
+            DetailPanel::Misses => f.render_widget(Paragraph::new(miss_lines(app)).block(Block::default().borders(Borders::ALL).title("ミスの傾向")), mid[0]),
+        }
 
         // Slow (worst)
         let mut sorted2 = rec.splits.clone();
@@ -65,9 +79,15 @@ pub fn draw(f: &mut Frame, app: &mut App) {
             Line::from(format!("Time     {:>7.3}s", rec.time_sec)),
             Line::from(format!("Miss     {:>3}", rec.miss)),
             Line::from(format!("Timeloss {:>7.3}s", rec.timeloss_sec)),
-            Line::from(format!("Level    {}", rec.rank)),
+            Line::from(format!("Level    {}   安定度 {}", rec.rank, rec.consistency().map(|c| format!("{:.1}", c)).unwrap_or_else(|| "-".into()))),
             Line::from(format!("Avg/word {:>7.3}s", avg)),
-            Line::from(format!("Top WPM  {:>6.1}", rec.wpm_top)),
+            Line::from(format!("WPM      {:>6.1} / {:.1}（最高/最低）", rec.wpm_top, rec.wpm_worst)),
+            Line::from(format!("条件     {}", rec.session.as_ref().map(|s| s.summary()).unwrap_or_else(|| "標準".into()))),
+            match &app.detail_check {
+                Some(c) if !c.ok() => Line::from(Span::styled(format!("検証     {}", c.summary()), Style::default().fg(Color::Red))),
+                Some(c) => Line::from(Span::styled(format!("検証     {}", c.summary()), Style::default().fg(if c.verified() { Color::Green } else { Color::Gray }))),
+                None => Line::from(""),
+            },
         ];
         let metrics = Paragraph::new(box_lines)
             .block(Block::default().borders(Borders::ALL).title("統計"));
@@ -78,24 +98,42 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         super::chart::draw_speed_chart(f, chart_area, rec);
 
         // Footer (legend removed, add restart hint)
-        f.render_widget(Paragraph::new("Space: 再生/停止  ←/→: 1打進む  +/-: 再生速度  S: 最初から"), v[3]);
+        let foot = match &app.status {
+            Some(msg) => Line::from(Span::styled(msg.clone(), Style::default().fg(Color::Yellow))),
+            None => match app.replay.as_ref().and_then(|r| r.goto.as_ref()) {
+                Some(t) => Line::from(vec![Span::styled("移動先の時刻（秒 または 分:秒）: ", Style::default().fg(Color::Cyan)), Span::raw(format!("{}_", t)), Span::raw("   Enter: 移動  Esc: 取消")]),
+                None => Line::from("Space: 再生/停止  ←/→: 1打  ,/.: ±1秒  [/]: 前/次の語  n/N: 次/前のミス  G: 時刻へ  L: ループ  A: 分析切替  +/-: 速度  S: 最初から  ↑/↓: 前/次の記録  W: 保存  C: .cast"),
+            },
+        };
+        f.render_widget(Paragraph::new(foot), v[4]);
     }
 }
 
-fn replay_line_spans(app: &App, rec: &crate::store::json::ScoreRecord) -> Vec<Span<'static>> {
+/// Words of the run: the stored sequence when present, else the split words.
+fn run_words(rec: &ScoreRecord) -> Vec<(String, usize)> {
+    if rec.word_seq.is_empty() { rec.splits.iter().map(|s| (s.word.clone(), 0)).collect() }
+    else { rec.word_seq.iter().map(|w| (w.jp.clone(), w.romas.iter().map(|r| r.len()).min().unwrap_or(0))).collect() }
+}
+
+fn replay_line_spans(app: &App, rec: &ScoreRecord) -> Vec<Span<'static>> { progress_spans(rec, &app.words, app.replay.as_ref().map(|r| r.ev_idx)) }
+
+/// The run's kana coloured by replay progress: events up to `upto` (inclusive) are typed.
+pub fn progress_spans(rec: &ScoreRecord, dict: &[WordEntry], upto: Option<usize>) -> Vec<Span<'static>> {
     let mut spans: Vec<Span> = Vec::new();
-    let (ev_idx, replay_opt) = if let Some(rp) = &app.replay { (rp.ev_idx, rec.replay.as_ref()) } else { (usize::MAX, None) };
+    let words = run_words(rec);
     // Build typed lengths per word up to ev_idx
-    let mut typed_map: Vec<usize> = vec![0; rec.splits.len()];
+    let mut typed_map: Vec<usize> = vec![0; words.len()];
     let mut cur_w = 0usize;
     let mut last_miss = false;
-    if let Some(evs) = replay_opt {
-        let upto = ev_idx.min(evs.len().saturating_sub(1));
-        for i in 0..=upto { if evs.is_empty() { break; } let e=&evs[i]; cur_w = e.w; if e.ok { typed_map[e.w] = typed_map[e.w].saturating_add(1); } last_miss = !e.ok; }
+    if let (Some(evs), Some(upto)) = (&rec.replay, upto) {
+        for e in evs.iter().take(upto.saturating_add(1)) {
+            cur_w = e.w;
+            if e.ok { if let Some(n) = typed_map.get_mut(e.w) { *n += 1; } }
+            last_miss = !e.ok;
+        }
     }
-    for (i, s) in rec.splits.iter().enumerate() {
-        let jp = &s.word;
-        let roma_len = find_roma_len(&app.words, jp).max(1);
+    for (i, (jp, roma_len)) in words.iter().enumerate() {
+        let roma_len = if *roma_len > 0 { *roma_len } else { find_roma_len(dict, jp) }.max(1);
         let jp_chars: Vec<char> = jp.chars().collect();
         let pos = ((typed_map[i] as f64 / roma_len as f64) * jp_chars.len() as f64).floor() as usize;
         for (j, ch) in jp_chars.into_iter().enumerate() {
@@ -112,6 +150,122 @@ fn replay_line_spans(app: &App, rec: &crate::store::json::ScoreRecord) -> Vec<Sp
     spans
 }
 
-fn find_roma_len(words: &Vec<WordEntry>, jp: &str) -> usize {
+/// Misses of the session classified by cause.
+fn miss_lines(app: &App) -> Vec<Line<'static>> {
+    let Some(misses) = &app.detail_misses else { return vec![Line::from(Span::styled("リプレイを再生できないため分類できません", Style::default().fg(Color::Gray)))] };
+    if misses.is_empty() { return vec![Line::from(Span::styled("ミスなし", Style::default().fg(Color::Green)))] }
+    let r = engine::misses::report(misses, 1);
+    let mut lines = vec![Line::from(r.by_kind.iter().map(|(k, n)| format!("{} {}", k.label(), n)).collect::<Vec<_>>().join("  "))];
+    lines.extend(r.patterns.iter().take(6).map(pattern_line));
+    lines
+}
+
+/// "隣のキー k→l ×3  例: かき / けむり"
+pub fn pattern_line(p: &engine::misses::Pattern) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("{}{}", p.kind.label(), " ".repeat(10usize.saturating_sub(crate::util::width::display_width(p.kind.label())))), Style::default().fg(Color::Yellow)),
+        Span::raw(format!(" {}→{} ", p.expected.map(String::from).unwrap_or_else(|| "?".into()), p.typed)),
+        Span::styled(format!("×{:<3}", p.count), Style::default().fg(Color::Red)),
+        Span::styled(format!(" 例: {}", p.examples.join(" / ")), Style::default().fg(Color::Gray)),
+    ])
+}
+
+/// Inter-key interval statistics and their histogram.
+fn rhythm_panel(f: &mut Frame, area: Rect, rec: &ScoreRecord) {
+    let block = Block::default().borders(Borders::ALL).title("リズム・安定度");
+    let Some(r) = rec.rhythm.clone().or_else(|| rec.compute_rhythm()) else {
+        f.render_widget(Paragraph::new(Span::styled("リプレイがないため分析できません", Style::default().fg(Color::Gray))).block(block), area);
+        return;
+    };
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let rows = Layout::default().direction(Direction::Vertical).constraints([Constraint::Length(3), Constraint::Min(3)]).split(inner);
+    let lines = vec![
+        Line::from(format!("打鍵間隔 平均 {:.3}s  σ {:.3}s", r.mean, r.std)),
+        Line::from(format!("変動係数 {:.2}  最長の間 {:.2}s", r.cv, r.longest_pause)),
+        Line::from(Span::styled(format!("安定度 {:.1} / 100", r.consistency), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))),
+    ];
+    f.render_widget(Paragraph::new(lines), rows[0]);
+    // bins labelled by their lower bound in 100ms steps; the last one is "and slower"
+    let labels: Vec<String> = (0..r.histogram.len()).map(|i| {
+        let ms = i as u32 * crate::engine::stats::RHYTHM_BIN_MS;
+        if i + 1 == r.histogram.len() { "+".into() } else if ms.is_multiple_of(100) { (ms / 100).to_string() } else { String::new() }
+    }).collect();
+    let bars: Vec<(&str, u64)> = labels.iter().zip(&r.histogram).map(|(l, n)| (l.as_str(), *n as u64)).collect();
+    f.render_widget(BarChart::default().data(&bars).bar_width(1).bar_gap(1).bar_style(Style::default().fg(Color::Cyan))
+        .value_style(Style::default().fg(Color::Black).bg(Color::Cyan)), rows[1]);
+}
+
+/// 初速, hesitations and the mora kinds that cost the most time.
+fn latency_lines(app: &App) -> Vec<Line<'static>> {
+    let Some(a) = &app.detail_latency else { return vec![Line::from(Span::styled("リプレイがないため分析できません", Style::default().fg(Color::Gray)))] };
+    let mut lines = vec![
+        Line::from(format!("初速 平均 {:>6.3}s  最遅 {:.3}s", a.mean_first_key(), a.first_key.iter().copied().fold(0.0, f64::max))),
+        Line::from(Span::styled(format!("ためらい {}回（{}ms超）", a.hesitations.len(), app.cfg.hesitation_ms), Style::default().fg(Color::Magenta))),
+        Line::from(format!("モーラ平均 {:>6.3}s", a.mean_mora())),
+    ];
+    let mean = a.mean_mora();
+    for k in a.by_kind().into_iter().take(4) {
+        let st = if mean > 0.0 && k.mean > mean * 1.2 { Style::default().fg(Color::Red) } else { Style::default() };
+        lines.push(Line::from(Span::styled(format!("{:<10} {:>6.3}s ×{}", k.kind.label(), k.mean, k.count), st)));
+    }
+    if let Some(m) = a.slowest(1).first() {
+        let word = app.detail.as_ref().and_then(|r| r.word_seq.get(m.word)).map(|w| w.jp.clone()).unwrap_or_default();
+        lines.push(Line::from(Span::styled(format!("最遅 {}（{}） {:.3}s", m.mora, word, m.sec), Style::default().fg(Color::Gray))));
+    }
+    lines
+}
+
+/// Romaji actually typed (wrong keys in red), newest words last, cut to `width` columns.
+fn typed_line(app: &App, rec: &ScoreRecord, width: usize) -> Line<'static> {
+    let (Some(rep), Some(evs)) = (&app.replay, rec.replay.as_deref()) else { return Line::from("") };
+    if evs.is_empty() { return Line::from(""); }
+    let upto = rep.ev_idx.min(evs.len() - 1);
+    let mut spans: Vec<Span> = Vec::new();
+    let mut used = 0usize;
+    // walk back from the current word until the line is full
+    let hes = app.detail_latency.as_ref();
+    for w in (0..=evs[upto].w).rev() {
+        let keys = replay::typed_keys(evs, upto, w);
+        // a word's events are contiguous
+        let first = evs.iter().position(|e| e.w == w).unwrap_or(0);
+        if keys.is_empty() && w != evs[upto].w { continue; }
+        let len = keys.len() + 1;
+        if used + len > width && !spans.is_empty() { break; }
+        used += len;
+        let mut word: Vec<Span> = keys.into_iter().enumerate().map(|(k, (c, ok))| {
+            let c = if c == " " { "␣".to_string() } else { c.to_string() };
+            let st = if ok { Style::default().fg(Color::Cyan) } else { Style::default().fg(Color::Red).add_modifier(Modifier::CROSSED_OUT) };
+            // key typed after a hesitation
+            Span::styled(c, if hes.is_some_and(|h| h.is_hesitation(first + k)) { st.bg(Color::Magenta) } else { st })
+        }).collect();
+        word.push(Span::raw(" "));
+        spans.splice(0..0, word);
+    }
+    Line::from(spans)
+}
+
+/// One-row scrubber: word boundaries │, hesitations ▲ (where the pause began), misses ×, the playback position ●, and the time.
+fn timeline(f: &mut Frame, area: Rect, app: &App, rec: &ScoreRecord) {
+    let (Some(rep), Some(evs)) = (&app.replay, rec.replay.as_deref()) else { return };
+    let end = evs.last().map(|e| e.t).unwrap_or(0.0).max(0.001);
+    let label = format!(" {:>6.2}/{:.2}s {} x{:.2}{}", rep.time, end, if rep.playing { "▶" } else { "⏸" }, rep.speed, if app.cfg.replay_loop { " ⟳" } else { "" });
+    let h = Layout::default().direction(Direction::Horizontal)
+        .constraints([Constraint::Min(10), Constraint::Length(label.chars().count() as u16 + 1)]).split(area);
+    let w = h[0].width.max(1) as usize;
+    let col = |t: f64| (((t / end) * (w - 1) as f64).round() as usize).min(w - 1);
+    let mut cells: Vec<(char, Style)> = vec![('─', Style::default().fg(Color::DarkGray)); w];
+    for i in replay::word_starts(evs).into_iter().skip(1) { cells[col(evs[i].t)] = ('│', Style::default().fg(Color::Gray)); }
+    if let Some(a) = &app.detail_latency {
+        for h in &a.hesitations { cells[col(evs[h.ev].t - h.gap)] = ('▲', Style::default().fg(Color::Magenta)); }
+    }
+    for e in evs.iter().filter(|e| !e.ok) { cells[col(e.t)] = ('×', Style::default().fg(Color::Red)); }
+    cells[col(rep.time)] = ('●', Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+    let spans: Vec<Span> = cells.into_iter().map(|(c, st)| Span::styled(c.to_string(), st)).collect();
+    f.render_widget(Paragraph::new(Line::from(spans)), h[0]);
+    f.render_widget(Paragraph::new(label), h[1]);
+}
+
+fn find_roma_len(words: &[WordEntry], jp: &str) -> usize {
     if let Some(w) = words.iter().find(|w| w.jp == jp) { w.romas.iter().map(|s| s.len()).min().unwrap_or(1) } else { 1 }
 }
@@ -0,0 +1,76 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::app::App;
+use crate::engine::difficulty::{self, Grade};
+
+pub fn draw(f: &mut Frame, app: &mut App) {
+    let area = f.size();
+    let stage = super::centered(area, app.cfg.stage_w, app.cfg.stage_h);
+    f.render_widget(Clear, area);
+    let v = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(8), Constraint::Length(1)])
+        .split(stage);
+
+    f.render_widget(Paragraph::new("[↑/↓] 選択  [Enter] 使用する  [E] 編集  [D] 出題難度  [ESC] 戻る  — 辞書"), v[0]);
+
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(v[1]);
+
+    // Left: dictionaries
+    let rows: Vec<Row> = app.dicts.iter().enumerate().map(|(i, d)| {
+        let active = d.path == app.cfg.dictionary;
+        let mut style = if d.error.is_some() { Style::default().fg(Color::Red) } else { Style::default().fg(Color::White) };
+        if i == app.dict_sel { style = style.add_modifier(Modifier::REVERSED); }
+        Row::new(vec![
+            Cell::from(if active { "*" } else { " " }),
+            Cell::from(d.title.clone()),
+            Cell::from(format!("{:>5}", d.words.len())),
+        ]).style(style)
+    }).collect();
+    let table = Table::new(rows, [Constraint::Length(1), Constraint::Min(10), Constraint::Length(6)])
+        .block(Block::default().borders(Borders::ALL).title("辞書一覧"))
+        .header(Row::new(vec!["", "タイトル", "語数"]).style(Style::default().fg(Color::Yellow)))
+        .column_spacing(1);
+    f.render_widget(table, cols[0]);
+
+    // Right: difficulty distribution of the highlighted dictionary
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(6), Constraint::Min(6)])
+        .split(cols[1]);
+    let Some(d) = app.dicts.get(app.dict_sel) else { return; };
+    if let Some(err) = &d.error {
+        let p = Paragraph::new(err.clone()).wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title("読み込みエラー"));
+        f.render_widget(p, cols[1]);
+        return;
+    }
+    let bounds = difficulty::grade_bounds(&d.words);
+    let mut per_grade = [0usize; 3];
+    for w in &d.words {
+        match difficulty::grade_of(difficulty::score(w, None), bounds) { Grade::Easy => per_grade[0] += 1, Grade::Normal => per_grade[1] += 1, Grade::Hard => per_grade[2] += 1 }
+    }
+    let sel = app.cfg.session.grade.map(|g| g.label()).unwrap_or("全て");
+    let info = vec![
+        Line::from(format!("易 < {:.1} ≦ 中 < {:.1} ≦ 難", bounds.0, bounds.1)),
+        Line::from(format!("易 {}語  中 {}語  難 {}語", per_grade[0], per_grade[1], per_grade[2])),
+        Line::from(vec![Span::raw("出題難度: "), Span::styled(sel, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))]),
+    ];
+    f.render_widget(Paragraph::new(info).block(Block::default().borders(Borders::ALL).title("難度")), right[0]);
+
+    let dist = difficulty::distribution(&d.words, 5.0);
+    let labels: Vec<String> = dist.iter().map(|(x, _)| format!("{:.0}", x)).collect();
+    let data: Vec<(&str, u64)> = labels.iter().map(|s| s.as_str()).zip(dist.iter().map(|(_, c)| *c)).collect();
+    let chart = BarChart::default()
+        .block(Block::default().borders(Borders::ALL).title("難度分布（スコア5刻み）"))
+        .data(&data)
+        .bar_width(3)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Cyan))
+        .value_style(Style::default().fg(Color::Black).bg(Color::Cyan));
+    f.render_widget(chart, right[1]);
+
+    f.render_widget(Paragraph::new(format!("ユーザー辞書: {}", crate::util::paths::words_dir().join("*.json").display())), v[2]);
+}
@@ -0,0 +1,90 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::app::{App, EditorMode};
+use crate::engine::romaji::RomaCheck;
+
+pub fn draw(f: &mut Frame, app: &mut App) {
+    let area = f.size();
+    let stage = super::centered(area, app.cfg.stage_w, app.cfg.stage_h);
+    f.render_widget(Clear, area);
+    let Some(ed) = &app.editor else { return; };
+    let v = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // key guide
+            Constraint::Length(1), // search
+            Constraint::Min(5),    // entries
+            Constraint::Length(7), // edit form
+            Constraint::Length(1), // message
+        ])
+        .split(stage);
+
+    let guide = match ed.mode {
+        EditorMode::Browse => "[/]検索 [A]追加 [E]編集 [X]削除 [T]試し打ち [S]保存 [ESC]戻る — 辞書エディタ",
+        EditorMode::Search => "検索語を入力  [Enter/ESC]確定",
+        EditorMode::Edit => "[Tab]項目移動  [Enter]確定  [ESC]取消",
+        EditorMode::ConfirmDelete => "削除しますか？ [Y]はい / その他のキーで取消",
+    };
+    f.render_widget(Paragraph::new(guide), v[0]);
+
+    let dirty = if ed.dirty { " *未保存" } else { "" };
+    let search = Line::from(vec![
+        Span::styled("検索: ", Style::default().fg(Color::Gray)),
+        Span::styled(ed.query.clone(), Style::default().fg(Color::Yellow)),
+        Span::styled(if ed.mode == EditorMode::Search { "▏" } else { "" }, Style::default().fg(Color::Yellow)),
+        Span::raw(format!("   {} ({}語){}", ed.file.title, ed.file.entries.len(), dirty)),
+    ]);
+    f.render_widget(Paragraph::new(search), v[1]);
+
+    // Entries (scrolled so the cursor stays visible)
+    let visible = ed.visible();
+    let height = (v[2].height as usize).saturating_sub(3).max(1);
+    let offset = ed.sel.saturating_sub(height - 1);
+    let rows: Vec<Row> = visible.iter().enumerate().skip(offset).take(height).map(|(vi, &i)| {
+        let e = &ed.file.entries[i];
+        let mut style = Style::default().fg(Color::White);
+        if vi == ed.sel { style = style.add_modifier(Modifier::REVERSED); }
+        Row::new(vec![
+            Cell::from(format!("{:>4}", i + 1)),
+            Cell::from(e.jp.clone()),
+            Cell::from(e.romas.join(", ")),
+            Cell::from(e.tags.join(",")),
+        ]).style(style)
+    }).collect();
+    let table = Table::new(rows, [Constraint::Length(4), Constraint::Percentage(30), Constraint::Percentage(45), Constraint::Min(6)])
+        .block(Block::default().borders(Borders::ALL).title(format!("語 {}/{}", visible.len(), ed.file.entries.len())))
+        .header(Row::new(vec!["#", "日本語", "ローマ字", "タグ"]).style(Style::default().fg(Color::Yellow)))
+        .column_spacing(1);
+    f.render_widget(table, v[2]);
+
+    // Edit form with live validation
+    let mut lines: Vec<Line> = Vec::new();
+    if ed.mode == EditorMode::Edit {
+        for (i, label) in ["日本語  ", "ローマ字", "タグ    "].iter().enumerate() {
+            let cur = if i == ed.field { Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD) } else { Style::default() };
+            lines.push(Line::from(vec![
+                Span::styled(format!("{} ", label), Style::default().fg(Color::Gray)),
+                Span::styled(ed.form[i].clone(), cur),
+                Span::styled(if i == ed.field { "▏" } else { "" }, cur),
+            ]));
+        }
+        for (r, c) in ed.form_checks(&app.rules) {
+            let (mark, color, msg) = match c {
+                RomaCheck::Ok => ("✓", Color::Green, "読みと一致".to_string()),
+                RomaCheck::Unverified => ("?", Color::Yellow, "漢字を含むため読みは未検証".to_string()),
+                RomaCheck::Invalid(m) => ("✗", Color::Red, m),
+            };
+            lines.push(Line::from(Span::styled(format!("{} {}  {}", mark, r, msg), Style::default().fg(color))));
+        }
+    } else if let Some(e) = ed.selected().and_then(|i| ed.file.entries.get(i)) {
+        lines.push(Line::from(format!("日本語   {}", e.jp)));
+        lines.push(Line::from(format!("ローマ字 {}", e.romas.join(", "))));
+        lines.push(Line::from(format!("タグ     {}", e.tags.join(", "))));
+        if let Some(w) = e.weight { lines.push(Line::from(format!("重み     {}", w))); }
+    }
+    let title = match (ed.mode, ed.editing) { (EditorMode::Edit, None) => "新規", (EditorMode::Edit, Some(_)) => "編集", _ => "選択中" };
+    f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)), v[3]);
+
+    if let Some(m) = &ed.message {
+        f.render_widget(Paragraph::new(Span::styled(m.clone(), Style::default().fg(Color::Cyan))), v[4]);
+    }
+}
@@ -4,6 +4,13 @@ pub mod ranking;
 pub mod settings;
 pub mod chart;
 pub mod details;
+pub mod compare;
+pub mod stats;
+pub mod dictionary;
+pub mod editor;
+pub mod profiles;
+pub mod recovery;
+pub mod cast;
 
 use ratatui::prelude::*;
 
@@ -6,170 +6,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     // Centered stage for play screen (inspired by TypeWell window size)
     let stage = super::centered(area, app.cfg.stage_w.into(), app.cfg.stage_h.into());
     f.render_widget(Clear, area);
-    let v = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),      // header (GO/READY/タイム)
-            Constraint::Percentage(38), // JP grid
-            Constraint::Length(1),      // progress blocks
-            Constraint::Length(1),      // info row
-            Constraint::Percentage(40), // ROMA + right sidebar
-            Constraint::Length(1),      // gauge
-        ])
-        .split(stage);
-
-    // ヘッダー
-    if let Some(g) = &app.game {
-        let header_cols = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Length(16),
-                Constraint::Min(10),
-                Constraint::Length(18),
-            ])
-            .split(v[0]);
-
-        let btns = if app.countdown_until.is_some() {
-            Paragraph::new(Line::from(vec![
-                Span::styled(" GO ", Style::default().fg(Color::Black).bg(Color::Gray)),
-                Span::raw(" "),
-                Span::styled(" READY ", Style::default().fg(Color::White).bg(Color::Blue).add_modifier(Modifier::BOLD)),
-            ]))
-        } else {
-            Paragraph::new(Line::from(vec![
-                Span::styled(" GO! ", Style::default().fg(Color::White).bg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::raw(" "),
-                Span::styled(" READY ", Style::default().fg(Color::Black).bg(Color::Gray)),
-            ]))
-        };
-        f.render_widget(btns, header_cols[0]);
-
-        let title = Paragraph::new(Line::from(vec![
-            Span::styled("【 基本常用語 】", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
-        ])).alignment(Alignment::Center);
-        f.render_widget(title, header_cols[1]);
-
-        let tbox = if app.countdown_until.is_some() {
-            Paragraph::new(Line::from(vec![
-                Span::raw("タイム: "),
-                Span::styled("[--]", Style::default().fg(Color::Gray)),
-                Span::raw("  Keys "),
-                Span::styled(format!("{:>3}/400", g.current_typed_total()), Style::default().fg(Color::LightGreen)),
-            ])).alignment(Alignment::Right)
-        } else {
-            Paragraph::new(Line::from(vec![
-                Span::raw("タイム: "),
-                Span::styled(format!("[{:.1}]", g.elapsed_secs()), Style::default().fg(Color::Yellow)),
-                Span::raw("  Keys "),
-                Span::styled(format!("{:>3}/400", g.current_typed_total()), Style::default().fg(Color::LightGreen)),
-            ])).alignment(Alignment::Right)
-        };
-        f.render_widget(tbox, header_cols[2]);
-        let is_cd = app.countdown_until.is_some();
-        // JP グリッド（カウントダウン中は単語非表示のままカウントダウン表示）
-        if is_cd {
-            let until = app.countdown_until.unwrap();
-            let now = std::time::Instant::now();
-            let rem = if until > now { (until - now).as_secs_f64() } else { 0.0 };
-            let disp = rem.ceil().max(0.0) as u64;
-            let msg = if disp == 0 { "READY".to_string() } else { format!("開始まで {}", disp) };
-            let jp_line = Paragraph::new(Line::from(vec![
-                Span::styled(msg, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-            ])).alignment(Alignment::Center)
-              .block(Block::default().borders(Borders::ALL).title("かな/漢字"));
-            f.render_widget(jp_line, v[1]);
-        } else {
-            let jp_line = Paragraph::new(Line::from(jp_spans_grid(g)))
-                .wrap(Wrap { trim: false })
-                .block(Block::default().borders(Borders::ALL).title("かな/漢字"));
-            f.render_widget(jp_line, v[1]);
-        }
-
-        // プログレスブロック（常時表示）
-        let blocks = progress_blocks(g.progress_ratio(), 28);
-        f.render_widget(Paragraph::new(blocks), v[2]);
-
-        // 情報行（常時表示）
-        let info = Line::from(vec![
-            Span::styled("目標=400打 ", Style::default().fg(Color::Red)),
-            Span::raw("  "),
-            Span::styled(format!("レベル {} ", g.current_level()), Style::default().fg(Color::Yellow)),
-            Span::raw("  "),
-            Span::styled(format!("ミス {}", g.miss()), Style::default().fg(Color::Red)),
-        ]);
-        f.render_widget(Paragraph::new(info), v[3]);
-
-        // ROMA line: カウントダウン中は非表示プレースホルダ、以降は通常表示
-        let mut roma_spans: Vec<Span> = Vec::new();
-        if is_cd {
-            roma_spans.push(Span::styled("…", Style::default().fg(Color::Gray)));
-        } else {
-            let idx = g.current_index();
-            for i in 0..g.words_len() {
-                let roma = g.roma_for_index(i);
-                if i < idx {
-                    roma_spans.push(Span::styled(roma, Style::default().fg(Color::Green)));
-                } else if i == idx {
-                    let typed_len = g.current_typed_len();
-                    let (a,b) = roma.split_at(typed_len.min(roma.len()));
-                    if !a.is_empty() {
-                        roma_spans.push(Span::styled(a.to_string(), Style::default().fg(Color::Green)));
-                    }
-                    if !b.is_empty() {
-                        if g.last_miss_char().is_some() {
-                            let mut chs = b.chars();
-                            if let Some(expected) = chs.next() {
-                                roma_spans.push(Span::styled(expected.to_string(), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)));
-                            }
-                            let rest: String = chs.collect();
-                            if !rest.is_empty() { roma_spans.push(Span::raw(rest)); }
-                        } else {
-                            roma_spans.push(Span::raw(b.to_string()));
-                        }
-                    }
-                } else {
-                    roma_spans.push(Span::raw(roma));
-                }
-                if i + 1 < g.words_len() { roma_spans.push(Span::raw(" ")); }
-            }
-        }
-
-        let main_cols = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(75), Constraint::Percentage(25)])
-            .split(v[4]);
-        // ROMA only
-        let roma_line = Paragraph::new(Line::from(roma_spans))
-            .wrap(Wrap { trim: false })
-            .block(Block::default().borders(Borders::ALL).title("ローマ字"));
-        f.render_widget(roma_line, main_cols[0]);
-
-        // 下部: スプリットテーブル（最大限エリアを埋める）
-        let rows: Vec<Row> = g
-            .splits
-            .iter()
-            .enumerate()
-            .map(|(i, s)| {
-                let mut row = Row::new(vec![
-                    Cell::from(format!("{:>2}", i + 1)),
-                    Cell::from(format!("{:>6.3}", s.sec)),
-                ]);
-                if s.miss > 0 { row = row.style(Style::default().fg(Color::Red)); }
-                row
-            })
-            .collect();
-        let table = Table::new(rows, [Constraint::Length(3), Constraint::Length(8)])
-            .block(Block::default().borders(Borders::ALL).title("Lap"))
-            .header(Row::new(vec!["#", "秒"]).style(Style::default().fg(Color::Yellow)))
-            .column_spacing(1);
-        f.render_widget(table, main_cols[1]);
-
-        // 下: 進行ゲージ 1行
-        let gauge = Gauge::default()
-            .gauge_style(Style::default().fg(Color::Cyan))
-            .ratio(g.progress_ratio());
-        f.render_widget(gauge, v[5]);
-    }
+    if let Some(g) = &app.game { draw_game(f, stage, g, app.countdown_until, app.long_text_note.as_deref()); }
 
     // Overlay: 終了時ダイアログ（新記録/順位/名前入力）
     if let Some(prompt) = &app.rec_prompt {
@@ -208,6 +45,177 @@ pub fn draw(f: &mut Frame, app: &mut App) {
 
 // (unused) truncate helper was removed to silence warnings
 
+/// Play screen for one game state inside `stage`; `note` (what the long-text import left
+/// out) goes next to the title. Also used to render replays headlessly (cast export), so it
+/// reads nothing from the app.
+pub fn draw_game(f: &mut Frame, stage: Rect, g: &crate::engine::game::Game, countdown: Option<std::time::Instant>, note: Option<&str>) {
+    let v = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),      // header (GO/READY/タイム)
+            Constraint::Percentage(38), // JP grid
+            Constraint::Length(1),      // progress blocks
+            Constraint::Length(1),      // info row
+            Constraint::Percentage(40), // ROMA + right sidebar
+            Constraint::Length(1),      // gauge
+        ])
+        .split(stage);
+
+    // ヘッダー
+    let header_cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(16),
+            Constraint::Min(10),
+            Constraint::Length(18),
+        ])
+        .split(v[0]);
+
+    let btns = if countdown.is_some() {
+        Paragraph::new(Line::from(vec![
+            Span::styled(" GO ", Style::default().fg(Color::Black).bg(Color::Gray)),
+            Span::raw(" "),
+            Span::styled(" READY ", Style::default().fg(Color::White).bg(Color::Blue).add_modifier(Modifier::BOLD)),
+        ]))
+    } else {
+        Paragraph::new(Line::from(vec![
+            Span::styled(" GO! ", Style::default().fg(Color::White).bg(Color::Red).add_modifier(Modifier::BOLD)),
+            Span::raw(" "),
+            Span::styled(" READY ", Style::default().fg(Color::Black).bg(Color::Gray)),
+        ]))
+    };
+    f.render_widget(btns, header_cols[0]);
+
+    let long_text = g.mode() == crate::engine::game::MODE_LONG_TEXT;
+    let mut title_spans = vec![
+        Span::styled(if long_text { "【 長文 】" } else { "【 基本常用語 】" }, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+    ];
+    if let Some(note) = note.filter(|_| long_text) { title_spans.push(Span::styled(format!(" {note}"), Style::default().fg(Color::Yellow))); }
+    let title = Paragraph::new(Line::from(title_spans)).alignment(Alignment::Center);
+    f.render_widget(title, header_cols[1]);
+
+    let tbox = if countdown.is_some() {
+        Paragraph::new(Line::from(vec![
+            Span::raw("タイム: "),
+            Span::styled("[--]", Style::default().fg(Color::Gray)),
+            Span::raw("  Keys "),
+            Span::styled(format!("{:>3}/400", g.current_typed_total()), Style::default().fg(Color::LightGreen)),
+        ])).alignment(Alignment::Right)
+    } else {
+        Paragraph::new(Line::from(vec![
+            Span::raw("タイム: "),
+            Span::styled(format!("[{:.1}]", g.elapsed_secs()), Style::default().fg(Color::Yellow)),
+            Span::raw("  Keys "),
+            Span::styled(format!("{:>3}/400", g.current_typed_total()), Style::default().fg(Color::LightGreen)),
+        ])).alignment(Alignment::Right)
+    };
+    f.render_widget(tbox, header_cols[2]);
+    let is_cd = countdown.is_some();
+    // JP グリッド（カウントダウン中は単語非表示のままカウントダウン表示）
+    if is_cd {
+        let until = countdown.unwrap();
+        let now = std::time::Instant::now();
+        let rem = if until > now { (until - now).as_secs_f64() } else { 0.0 };
+        let disp = rem.ceil().max(0.0) as u64;
+        let msg = if disp == 0 { "READY".to_string() } else { format!("開始まで {}", disp) };
+        let jp_line = Paragraph::new(Line::from(vec![
+            Span::styled(msg, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        ])).alignment(Alignment::Center)
+          .block(Block::default().borders(Borders::ALL).title("かな/漢字"));
+        f.render_widget(jp_line, v[1]);
+    } else {
+        let jp_line = Paragraph::new(Line::from(jp_spans_grid(g)))
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title("かな/漢字"));
+        f.render_widget(jp_line, v[1]);
+    }
+
+    // プログレスブロック（常時表示）
+    let blocks = progress_blocks(g.progress_ratio(), 28);
+    f.render_widget(Paragraph::new(blocks), v[2]);
+
+    // 情報行（常時表示）
+    let info = Line::from(vec![
+        Span::styled("目標=400打 ", Style::default().fg(Color::Red)),
+        Span::raw("  "),
+        Span::styled(format!("レベル {} ", g.current_level()), Style::default().fg(Color::Yellow)),
+        Span::raw("  "),
+        Span::styled(format!("ミス {}", g.miss()), Style::default().fg(Color::Red)),
+    ]);
+    f.render_widget(Paragraph::new(info), v[3]);
+
+    // ROMA line: カウントダウン中は非表示プレースホルダ、以降は通常表示
+    let mut roma_spans: Vec<Span> = Vec::new();
+    if is_cd {
+        roma_spans.push(Span::styled("…", Style::default().fg(Color::Gray)));
+    } else {
+        let idx = g.current_index();
+        for i in 0..g.words_len() {
+            let roma = g.roma_for_index(i);
+            if i < idx {
+                roma_spans.push(Span::styled(roma, Style::default().fg(Color::Green)));
+            } else if i == idx {
+                let typed_len = g.current_typed_len();
+                let (a,b) = roma.split_at(typed_len.min(roma.len()));
+                if !a.is_empty() {
+                    roma_spans.push(Span::styled(a.to_string(), Style::default().fg(Color::Green)));
+                }
+                if !b.is_empty() {
+                    if g.last_miss_char().is_some() {
+                        let mut chs = b.chars();
+                        if let Some(expected) = chs.next() {
+                            roma_spans.push(Span::styled(expected.to_string(), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)));
+                        }
+                        let rest: String = chs.collect();
+                        if !rest.is_empty() { roma_spans.push(Span::raw(rest)); }
+                    } else {
+                        roma_spans.push(Span::raw(b.to_string()));
+                    }
+                }
+            } else {
+                roma_spans.push(Span::raw(roma));
+            }
+            if i + 1 < g.words_len() { roma_spans.push(Span::raw(" ")); }
+        }
+    }
+
+    let main_cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(75), Constraint::Percentage(25)])
+        .split(v[4]);
+    // ROMA only
+    let roma_line = Paragraph::new(Line::from(roma_spans))
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title("ローマ字"));
+    f.render_widget(roma_line, main_cols[0]);
+
+    // 下部: スプリットテーブル（最大限エリアを埋める）
+    let rows: Vec<Row> = g
+        .splits
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let mut row = Row::new(vec![
+                Cell::from(format!("{:>2}", i + 1)),
+                Cell::from(format!("{:>6.3}", s.sec)),
+            ]);
+            if s.miss > 0 { row = row.style(Style::default().fg(Color::Red)); }
+            row
+        })
+        .collect();
+    let table = Table::new(rows, [Constraint::Length(3), Constraint::Length(8)])
+        .block(Block::default().borders(Borders::ALL).title("Lap"))
+        .header(Row::new(vec!["#", "秒"]).style(Style::default().fg(Color::Yellow)))
+        .column_spacing(1);
+    f.render_widget(table, main_cols[1]);
+
+    // 下: 進行ゲージ 1行
+    let gauge = Gauge::default()
+        .gauge_style(Style::default().fg(Color::Cyan))
+        .ratio(g.progress_ratio());
+    f.render_widget(gauge, v[5]);
+}
+
 fn progress_blocks(ratio: f64, cells: usize) -> Line<'static> {
     let filled = ((ratio.clamp(0.0, 1.0)) * cells as f64).round() as usize;
     let mut spans: Vec<Span> = Vec::with_capacity(cells);
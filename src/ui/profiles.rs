@@ -0,0 +1,56 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::app::App;
+
+pub fn draw(f: &mut Frame, app: &mut App) {
+    let area = f.size();
+    let stage = super::centered(area, app.cfg.stage_w, app.cfg.stage_h);
+    f.render_widget(Clear, area);
+    let v = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(8), Constraint::Length(1)])
+        .split(stage);
+
+    let guide = if app.profile_input.is_some() { "新しいプロファイル名を入力  [Enter]作成  [ESC]取消" } else { "[↑/↓] 選択  [Enter] 切替  [N] 新規  [Tab] モード切替  [ESC] 戻る  — プロファイル" };
+    f.render_widget(Paragraph::new(guide), v[0]);
+
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(v[1]);
+
+    // Left: profiles
+    let cur = crate::util::paths::profile();
+    let rows: Vec<Row> = app.profiles.iter().enumerate().map(|(i, p)| {
+        let mut style = Style::default().fg(Color::White);
+        if i == app.profile_sel { style = style.add_modifier(Modifier::REVERSED); }
+        Row::new(vec![Cell::from(if *p == cur { "*" } else { " " }), Cell::from(p.clone())]).style(style)
+    }).collect();
+    let table = Table::new(rows, [Constraint::Length(1), Constraint::Min(10)])
+        .block(Block::default().borders(Borders::ALL).title("プロファイル"))
+        .column_spacing(1);
+    f.render_widget(table, cols[0]);
+
+    // Right: office leaderboard (best run per profile)
+    let rows: Vec<Row> = app.office.iter().enumerate().map(|(i, (p, r))| {
+        let color = match i { 0 => Color::Yellow, 1 => Color::White, 2 => Color::LightRed, _ => Color::Gray };
+        Row::new(vec![
+            Cell::from(format!("{:>2}", i + 1)),
+            Cell::from(p.clone()),
+            Cell::from(format!("{:>8.3}", r.time_sec)),
+            Cell::from(format!("{:>3}", r.miss)),
+            Cell::from(r.datetime.get(..10).unwrap_or("").to_string()),
+        ]).style(Style::default().fg(color))
+    }).collect();
+    let board = Table::new(rows, [Constraint::Length(2), Constraint::Min(8), Constraint::Length(8), Constraint::Length(3), Constraint::Length(10)])
+        .block(Block::default().borders(Borders::ALL).title(format!("オフィスランキング [{}]", app.ranking_mode)))
+        .header(Row::new(vec!["#", "名前", "タイム", "ミス", "日付"]).style(Style::default().fg(Color::Yellow)))
+        .column_spacing(1);
+    f.render_widget(board, cols[1]);
+
+    if let Some(name) = &app.profile_input {
+        f.render_widget(Paragraph::new(Line::from(vec![
+            Span::styled("名前: ", Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}▏", name), Style::default().fg(Color::Yellow)),
+        ])), v[2]);
+    }
+}
@@ -1,11 +1,11 @@
 use ratatui::{prelude::*, widgets::*};
-use crate::app::App;
+use crate::app::{App, RankingInput};
 
 pub fn draw(f: &mut Frame, app: &mut App) {
     let area = f.size();
     let stage = super::centered(area, app.cfg.stage_w.into(), app.cfg.stage_h.into());
     f.render_widget(Clear, area);
-    // 上: TOP 15 RANKING + 右パネル, 下: TOP Lap 10
+    // 上: RANKING (Top-100, cursor) + 右パネル, 下: TOP Lap 10
     let v = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -23,27 +23,36 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         .constraints([Constraint::Percentage(78), Constraint::Percentage(22)])
         .split(v[1]);
 
-    // TOP 15 (Time-centric)
-    let rows_top: Vec<Row> = app.scorebook.top.iter().take(15).enumerate().map(|(i,r)|{
+    // TOP 100 (Time-centric); the cursor scrolls the window. * = pinned
+    let rows_top: Vec<Row> = app.ranking_top.iter().enumerate().map(|(i,r)|{
         Row::new(vec![
-            Cell::from(format!("{:>2}", i+1)),
+            Cell::from(format!("{:>3}", i+1)),
+            if app.compare_mark.as_deref() == Some(r.id.as_str()) { Cell::from("A").style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)) }
+            else { Cell::from(if r.pinned { "*" } else { " " }).style(Style::default().fg(Color::Yellow)) },
             Cell::from(format!("{:>7.3}", r.time_sec)),
-            Cell::from(r.rank.clone()),
+            Cell::from(if r.aborted { "中断".into() } else { r.rank.clone() }),
             Cell::from(format!("{:>3}", r.miss)),
-            Cell::from(truncate(&r.datetime, (top_cols[0].width as usize).saturating_sub(28))),
+            Cell::from(r.consistency().map(|c| format!("{:>5.1}", c)).unwrap_or_else(|| "    -".into())),
+            Cell::from(truncate(&r.datetime, 19)),
+            Cell::from(truncate(r.memo.as_deref().unwrap_or(""), (top_cols[0].width as usize).saturating_sub(61))).style(Style::default().fg(Color::Gray)),
         ]).style(Style::default().fg(Color::White))
     }).collect();
     let table_top = Table::new(rows_top, [
         Constraint::Length(4), // Rk
+        Constraint::Length(1), // Pin
         Constraint::Length(10), // Time
         Constraint::Length(4), // Lv
         Constraint::Length(5), // Ms
-        Constraint::Min(12),   // Date
+        Constraint::Length(5), // Cons
+        Constraint::Length(19), // Date
+        Constraint::Min(6),    // Memo
     ])
-    .block(Block::default().borders(Borders::ALL).title(Span::styled(" TOP 15  RANKING ", Style::default().fg(Color::White).bg(Color::Blue).add_modifier(Modifier::BOLD))))
-    .header(Row::new(vec!["Rk","Time","Lv","Ms","Date"]).style(Style::default().fg(Color::Yellow)))
+    .block(Block::default().borders(Borders::ALL).title(Span::styled(format!(" {}  [{}]  {}件 ", if app.ranking_history { "HISTORY".to_string() } else { format!("RANKING {}", app.ranking_sort.label()) }, app.ranking_mode, app.ranking_top.len()), Style::default().fg(Color::White).bg(Color::Blue).add_modifier(Modifier::BOLD))))
+    .header(Row::new(vec!["Rk","","Time","Lv","Ms","Cons","Date","Memo"]).style(Style::default().fg(Color::Yellow)))
+    .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan))
     .column_spacing(1);
-    f.render_widget(table_top, top_cols[0]);
+    let mut state = TableState::default().with_selected((!app.ranking_top.is_empty()).then_some(app.ranking_sel));
+    f.render_stateful_widget(table_top, top_cols[0], &mut state);
 
     // Right panel: summary for last result
     let mut panel_lines: Vec<Line> = Vec::new();
@@ -64,7 +73,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
 
     // BOTTOM: TOP Lap 10 across columns 1..10
     let mut lap_cells: Vec<Span> = Vec::new();
-    for (i, r) in app.scorebook.lap.iter().take(10).enumerate() {
+    for (i, r) in app.ranking_lap.iter().take(10).enumerate() {
         if i>0 { lap_cells.push(Span::raw("  ")); }
         lap_cells.push(Span::styled(format!("{:>1}", i+1), Style::default().fg(Color::Gray)));
         lap_cells.push(Span::raw(" "));
@@ -75,7 +84,13 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     f.render_widget(lap_block, v[2]);
 
     // Footer line
-    f.render_widget(Paragraph::new("OK: ESC"), v[3]);
+    let footer = match &app.ranking_input {
+        Some(RankingInput::Memo(text)) => Line::from(vec![Span::styled("メモ: ", Style::default().fg(Color::Cyan)), Span::raw(text.clone()), Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)), Span::styled("  (Enter: 保存 / Esc: 取消 / 空で削除)", Style::default().fg(Color::Gray))]),
+        Some(RankingInput::ConfirmDelete) => Line::from(Span::styled("この記録を削除しますか？ [Y] 削除 / その他: 取消", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))),
+        None if app.status.is_some() => Line::from(Span::styled(app.status.clone().unwrap_or_default(), Style::default().fg(Color::Yellow))),
+        None => Line::from("ESC: 戻る / ↑↓: 選択 / Enter: 詳細 / H: 履歴⇔Top / S: タイム⇔安定度 / E: メモ / P: ピン留め / D: 削除 / U: 元に戻す / C: 2件を比較 / Tab: モード / X: 書き出し"),
+    };
+    f.render_widget(Paragraph::new(footer), v[3]);
 }
 
 fn truncate(s: &str, max_w: usize) -> String {
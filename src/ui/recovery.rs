@@ -0,0 +1,35 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::app::App;
+
+/// Popup asking how to recover a corrupted config/score file (drawn over any screen).
+pub fn draw(f: &mut Frame, app: &mut App) {
+    let Some(r) = app.recovery.first() else { return; };
+    let dlg = super::centered(f.size(), 72, 10 + r.backups.len().max(1) as u16);
+    f.render_widget(Clear, dlg);
+    let title = Span::styled(format!(" {}ファイルが壊れています ", r.label), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+    let mut lines = vec![
+        Line::from(r.path.display().to_string()),
+        Line::from(Span::styled(r.error.clone(), Style::default().fg(Color::Gray))),
+        Line::from(format!("退避先: {}", r.moved_to.display())),
+        Line::from(""),
+    ];
+    if r.backups.is_empty() {
+        lines.push(Line::from(Span::styled("使えるバックアップがありません。", Style::default().fg(Color::Yellow))));
+        lines.push(Line::from(""));
+        lines.push(Line::from("[ESC] 初期状態で開始"));
+    } else {
+        lines.push(Line::from("復元するバックアップを選んでください:"));
+        for (i, b) in r.backups.iter().enumerate() {
+            let when = std::fs::metadata(b).and_then(|m| m.modified()).ok()
+                .map(|t| chrono::DateTime::<chrono::Local>::from(t).format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_default();
+            let name = b.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            let mut style = Style::default();
+            if i == r.sel { style = style.add_modifier(Modifier::REVERSED); }
+            lines.push(Line::from(Span::styled(format!("  {}  {}", name, when), style)));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from("[↑/↓] 選択  [Enter] 復元  [ESC] 初期状態で開始"));
+    }
+    let p = Paragraph::new(lines).wrap(Wrap { trim: false }).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(p, dlg);
+}
@@ -7,10 +7,10 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     f.render_widget(Clear, area);
     let v = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .constraints([Constraint::Length(1), Constraint::Min(1), Constraint::Length(1), Constraint::Length(8)])
         .split(stage);
 
-    let header = Paragraph::new("[←/→]幅  [↑/↓]高  [+/-]ロスms  [F]固定打鍵  [[]/]]打鍵数  [C/X]CD秒  [M]サウンド  [O]音モード  [ESC]戻る  — 設定")
+    let header = Paragraph::new("[←/→]幅  [↑/↓]高  [+/-]ロスms  [F]固定打鍵  [[]/]]打鍵数  [C/X]CD秒  [M]サウンド  [O]音モード  [A]中断記録  [L]リプレイのループ  [K]キー配列  [ESC]戻る  — 設定")
         .block(Block::default().borders(Borders::NONE));
     f.render_widget(header, v[0]);
 
@@ -24,10 +24,39 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         Row::new(vec![Cell::from("countdown_sec"), Cell::from(app.cfg.countdown_sec.to_string())]),
         Row::new(vec![Cell::from("sound_enabled"), Cell::from(if app.cfg.sound_enabled { "true" } else { "false" })]),
         Row::new(vec![Cell::from("sound_mode"), Cell::from(match app.cfg.sound_mode { crate::util::config::SoundMode::Off=>"off", crate::util::config::SoundMode::Miss=>"miss", crate::util::config::SoundMode::All=>"all" })]),
-        Row::new(vec![Cell::from("保存先"), Cell::from("data/")]),
+        Row::new(vec![Cell::from("dictionary"), Cell::from(app.cfg.dictionary.clone().unwrap_or_else(|| "(内蔵)".into()))]),
+        Row::new(vec![Cell::from("rules_path"), Cell::from(app.cfg.rules_path.clone().unwrap_or_else(|| "(内蔵)".into()))]),
+        Row::new(vec![Cell::from("long_text_path"), Cell::from(app.cfg.long_text_path.clone().unwrap_or_else(|| "(未設定)".into()))]),
+        Row::new(vec![Cell::from("long_text_sentences"), Cell::from(app.cfg.long_text_sentences.to_string())]),
+        Row::new(vec![Cell::from("store"), Cell::from(app.store.backend())]),
+        Row::new(vec![Cell::from("keep_aborted"), Cell::from(if app.cfg.keep_aborted { "true" } else { "false" })]),
+        Row::new(vec![Cell::from("replay_loop"), Cell::from(if app.cfg.replay_loop { "true" } else { "false" })]),
+        Row::new(vec![Cell::from("hesitation_ms"), Cell::from(app.cfg.hesitation_ms.to_string())]),
+        Row::new(vec![Cell::from("keyboard_layout"), Cell::from(app.cfg.keyboard_layout.name())]),
+        Row::new(vec![Cell::from("finger_map"), Cell::from(if app.cfg.finger_map.is_empty() { "-".to_string() } else { app.cfg.finger_map.iter().map(|(k, f)| format!("{}:{}", k, f.label())).collect::<Vec<_>>().join(" ") })]),
+        Row::new(vec![Cell::from("設定ファイル"), Cell::from(crate::util::paths::config_file().display().to_string())]),
+        Row::new(vec![Cell::from("プロファイル"), Cell::from(crate::util::paths::profile())]),
+        Row::new(vec![Cell::from("保存先"), Cell::from(crate::util::paths::profile_data_dir(&crate::util::paths::profile()).display().to_string())]),
     ];
     let table = Table::new(rows, [Constraint::Length(20), Constraint::Min(10)])
         .block(Block::default().borders(Borders::ALL).title("現在の設定"))
         .column_spacing(1);
     f.render_widget(table, v[1]);
+
+    // 出題条件（通常モードのセッションに適用、記録にも保存）
+    let s = &app.cfg.session;
+    f.render_widget(Paragraph::new("[W]重み付き  [T]含むタグ  [E]除くタグ  [1/2]最小打鍵  [3/4]最大打鍵  [5/6]重複禁止語数"), v[2]);
+    let none = || "-".to_string();
+    let srows = vec![
+        Row::new(vec![Cell::from("weighted"), Cell::from(s.weighted.to_string())]),
+        Row::new(vec![Cell::from("include_tags"), Cell::from(if s.include_tags.is_empty() { none() } else { s.include_tags.join(",") })]),
+        Row::new(vec![Cell::from("exclude_tags"), Cell::from(if s.exclude_tags.is_empty() { none() } else { s.exclude_tags.join(",") })]),
+        Row::new(vec![Cell::from("min_keys"), Cell::from(s.min_keys.map(|v| v.to_string()).unwrap_or_else(none))]),
+        Row::new(vec![Cell::from("max_keys"), Cell::from(s.max_keys.map(|v| v.to_string()).unwrap_or_else(none))]),
+        Row::new(vec![Cell::from("no_repeat_within"), Cell::from(s.no_repeat_within.to_string())]),
+    ];
+    let stable = Table::new(srows, [Constraint::Length(20), Constraint::Min(10)])
+        .block(Block::default().borders(Borders::ALL).title(format!("出題条件（対象 {} 語）", crate::engine::game::filter_words(&app.words, s).len())))
+        .column_spacing(1);
+    f.render_widget(stable, v[3]);
 }
@@ -0,0 +1,187 @@
+use ratatui::{prelude::*, widgets::*};
+use crate::app::{App, StatsView};
+use crate::engine::fingers::HandUsage;
+use crate::engine::progress::{self, DayStat};
+use crate::util::keymap::Finger;
+
+pub fn draw(f: &mut Frame, app: &mut App) {
+    let area = f.size();
+    let stage = super::centered(area, app.cfg.stage_w, app.cfg.stage_h);
+    f.render_widget(Clear, area);
+    let v = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),       // header
+            Constraint::Fill(1),         // time / KPM
+            Constraint::Fill(1),         // miss rate / consistency / sessions
+            Constraint::Length(9),       // ranks / calendar (7 weekdays)
+            Constraint::Length(1),       // footer
+        ])
+        .split(stage);
+    let Some(p) = &app.stats else { return };
+
+    let span = match (p.days.first(), p.days.last()) {
+        (Some(a), Some(b)) => format!("{} 〜 {}", a.date, b.date),
+        _ => "記録なし".into(),
+    };
+    f.render_widget(Paragraph::new(format!("[ESC] 戻る  —  統計 [{}]  {}  {}セッション（完走 {}）", app.ranking_mode, span, p.sessions(), p.completed())), v[0]);
+
+    let foot = match &app.status {
+        Some(msg) => Line::from(Span::styled(msg.clone(), Style::default().fg(Color::Yellow))),
+        None => Line::from("ESC: 戻る  Tab: モード切替  M: ミスの傾向  F: 指・手の使い方（もう一度で推移に戻る）"),
+    };
+    f.render_widget(Paragraph::new(foot), v[4]);
+
+    let body = Rect { height: v[3].y + v[3].height - v[1].y, ..v[1] };
+    match app.stats_view {
+        StatsView::Misses => { if let Some(r) = &app.stats_misses { misses(f, body, r); } return; }
+        StatsView::Fingers => { if let Some((all, latest)) = &app.stats_hands { fingers(f, body, app, all, latest.as_ref()); } return; }
+        StatsView::Trends => {}
+    }
+
+    let row = |r: Rect| Layout::default().direction(Direction::Horizontal).constraints([Constraint::Percentage(50), Constraint::Percentage(50)]).split(r);
+    let (r1, r3) = (row(v[1]), row(v[3]));
+    let r2 = Layout::default().direction(Direction::Horizontal).constraints([Constraint::Ratio(1, 3), Constraint::Ratio(1, 3), Constraint::Ratio(1, 3)]).split(v[2]);
+    let done: Vec<&DayStat> = p.days.iter().filter(|d| d.best > 0.0).collect();
+
+    let best: Vec<(f64, f64)> = done.iter().map(|d| (x_of(p, d), d.best)).collect();
+    let med: Vec<(f64, f64)> = done.iter().map(|d| (x_of(p, d), d.median)).collect();
+    line_chart(f, r1[0], p, "タイム（秒）", vec![("ベスト", Color::Green, &best), ("中央値", Color::Yellow, &med)]);
+
+    let kpm: Vec<(f64, f64)> = done.iter().filter_map(|d| Some((x_of(p, d), d.kpm?))).collect();
+    line_chart(f, r1[1], p, "KPM（中央値）", vec![("KPM", Color::Cyan, &kpm)]);
+
+    let miss: Vec<(f64, f64)> = done.iter().filter_map(|d| Some((x_of(p, d), d.miss_rate? * 100.0))).collect();
+    line_chart(f, r2[0], p, "ミス率（%）", vec![("miss%", Color::Red, &miss)]);
+
+    let cons: Vec<(f64, f64)> = done.iter().filter_map(|d| Some((x_of(p, d), d.consistency?))).collect();
+    line_chart(f, r2[1], p, "安定度（中央値）", vec![("安定度", Color::Magenta, &cons)]);
+
+    // sessions per day: the most recent days that fit
+    let fit = (r2[2].width.saturating_sub(2) / 4).max(1) as usize;
+    let labels: Vec<String> = p.days.iter().rev().take(fit).rev().map(|d| d.date.format("%d").to_string()).collect();
+    let bars: Vec<(&str, u64)> = labels.iter().zip(p.days.iter().rev().take(fit).rev()).map(|(l, d)| (l.as_str(), d.sessions as u64)).collect();
+    f.render_widget(BarChart::default().block(Block::default().borders(Borders::ALL).title("日別セッション数"))
+        .data(&bars).bar_width(3).bar_gap(1).bar_style(Style::default().fg(Color::Blue)).value_style(Style::default().fg(Color::White).bg(Color::Blue)), r2[2]);
+
+    let bw = ((r3[0].width.saturating_sub(2) as usize / p.ranks.len().max(1)).saturating_sub(1)).clamp(1, 4) as u16;
+    f.render_widget(BarChart::default().block(Block::default().borders(Borders::ALL).title("レベル分布"))
+        .data(&p.ranks).bar_width(bw).bar_gap(1).bar_style(Style::default().fg(Color::Magenta)).value_style(Style::default().fg(Color::White).bg(Color::Magenta)), r3[0]);
+
+    calendar(f, r3[1], &p.activity);
+}
+
+/// Miss kinds of the whole history and the most frequent patterns.
+fn misses(f: &mut Frame, area: Rect, r: &crate::engine::misses::Report) {
+    let h = Layout::default().direction(Direction::Horizontal).constraints([Constraint::Length(34), Constraint::Min(20)]).split(area);
+    let kinds: Vec<Line> = if r.misses == 0 { vec![Line::from(Span::styled("分類できるミスがありません", Style::default().fg(Color::Gray)))] }
+        else { r.by_kind.iter().map(|(k, n)| {
+            let w = (*n * 16).div_ceil(r.misses);
+            Line::from(vec![Span::raw(format!("{:>5} ", n)), Span::styled("█".repeat(w), Style::default().fg(Color::Red)), Span::raw(format!(" {}", k.label()))])
+        }).collect() };
+    f.render_widget(Paragraph::new(kinds).block(Block::default().borders(Borders::ALL).title(format!("ミスの種類（{}回 / {}セッション）", r.misses, r.sessions))), h[0]);
+    let lines: Vec<Line> = r.patterns.iter().take(area.height.saturating_sub(2) as usize).map(super::details::pattern_line).collect();
+    f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("よくあるミス（期待→入力）")), h[1]);
+}
+
+/// Legend name, colour and points of one trend line.
+type Series<'a> = (&'a str, Color, &'a [(f64, f64)]);
+
+/// Days since the first day in the history (x axis of the trend charts).
+fn x_of(p: &progress::Progress, d: &DayStat) -> f64 {
+    p.days.first().map(|f| (d.date - f.date).num_days() as f64).unwrap_or(0.0)
+}
+
+fn line_chart(f: &mut Frame, area: Rect, p: &progress::Progress, title: &str, series: Vec<Series>) {
+    let max_x = p.days.last().map(|d| x_of(p, d)).unwrap_or(0.0).max(1.0);
+    let max_y = series.iter().flat_map(|s| s.2.iter()).map(|p| p.1).fold(1.0, f64::max) * 1.1;
+    let first = p.days.first().map(|d| d.date.format("%m/%d").to_string()).unwrap_or_default();
+    let last = p.days.last().map(|d| d.date.format("%m/%d").to_string()).unwrap_or_default();
+    let sets: Vec<Dataset> = series.into_iter().map(|(name, color, data)| {
+        Dataset::default().name(name).marker(symbols::Marker::Braille).graph_type(GraphType::Line).style(Style::default().fg(color)).data(data)
+    }).collect();
+    let chart = Chart::new(sets)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .x_axis(Axis::default().bounds([0.0, max_x]).labels(vec![Span::raw(first), Span::raw(last)]))
+        .y_axis(Axis::default().bounds([0.0, max_y]).labels(vec![Span::raw("0"), Span::raw(format!("{:.0}", max_y))]));
+    f.render_widget(chart, area);
+}
+
+/// GitHub-style activity grid: one column per week, Monday on top, darker = more sessions.
+fn calendar(f: &mut Frame, area: Rect, activity: &std::collections::BTreeMap<chrono::NaiveDate, usize>) {
+    let weeks = (area.width.saturating_sub(5) / 2).max(1) as usize;
+    let grid = progress::heatmap(activity, chrono::Local::now().date_naive(), weeks);
+    let max = grid.iter().flatten().flatten().copied().max().unwrap_or(0).max(1);
+    let names = ["月", "火", "水", "木", "金", "土", "日"];
+    let lines: Vec<Line> = (0..7).map(|d| {
+        let mut spans = vec![Span::styled(format!("{} ", names[d]), Style::default().fg(Color::Gray))];
+        for col in &grid {
+            spans.push(match col[d] {
+                None => Span::raw("  "),
+                Some(0) => Span::styled("■ ", Style::default().fg(Color::DarkGray)),
+                Some(n) => Span::styled("■ ", Style::default().fg(shade(n, max))),
+            });
+        }
+        Line::from(spans)
+    }).collect();
+    let total: usize = grid.iter().flatten().flatten().sum();
+    f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!("練習カレンダー（{}週 {}回）", weeks, total))), area);
+}
+
+fn shade(n: usize, max: usize) -> Color {
+    match n * 4 / max { 0 | 1 => Color::Rgb(14, 68, 41), 2 => Color::Rgb(0, 109, 50), 3 => Color::Rgb(38, 166, 65), _ => Color::Rgb(57, 211, 83) }
+}
+
+fn finger_color(f: Finger) -> Color {
+    match f {
+        Finger::LPinky | Finger::RPinky => Color::Red, Finger::LRing | Finger::RRing => Color::Yellow,
+        Finger::LMiddle | Finger::RMiddle => Color::Green, Finger::LIndex => Color::Cyan, Finger::RIndex => Color::LightBlue,
+    }
+}
+
+/// Keyboard diagram coloured by finger, per-finger load / miss rate / latency and hand figures.
+fn fingers(f: &mut Frame, area: Rect, app: &App, all: &HandUsage, latest: Option<&HandUsage>) {
+    let v = Layout::default().direction(Direction::Vertical).constraints([Constraint::Length(6), Constraint::Min(4)]).split(area);
+    let top = Layout::default().direction(Direction::Horizontal).constraints([Constraint::Length(52), Constraint::Min(20)]).split(v[0]);
+
+    // rows staggered like a real keyboard; keys never pressed are dimmed
+    let map = app.cfg.fingers();
+    let lines: Vec<Line> = map.rows().iter().zip([0usize, 2, 3, 5]).map(|(row, indent)| {
+        let mut spans = vec![Span::raw(" ".repeat(indent))];
+        for &(k, fg) in row {
+            let used = all.keys.get(&k).copied().unwrap_or(0) > 0;
+            let style = if used { Style::default().fg(Color::Black).bg(finger_color(fg)).add_modifier(Modifier::BOLD) } else { Style::default().fg(finger_color(fg)) };
+            spans.push(Span::styled(format!(" {} ", k), style));
+            spans.push(Span::raw(" "));
+        }
+        Line::from(spans)
+    }).collect();
+    f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!("キー配列 {}", app.cfg.keyboard_layout.name()))), top[0]);
+
+    let pct = |x: Option<f64>| x.map(|x| format!("{:.0}%", x * 100.0)).unwrap_or_else(|| "-".into());
+    let mut summary = vec![
+        Line::from(format!("交互打鍵 {}（{}/{}組）", pct(all.alternation()), all.alternations, all.pairs)),
+        Line::from(format!("左手 {} / 右手 {}", pct(all.left_share()), pct(all.left_share().map(|l| 1.0 - l)))),
+        Line::from(format!("同指連続 {}回（1セッション平均 {:.1}）", all.same_finger, all.same_finger_per_session())),
+    ];
+    if let Some(l) = latest {
+        summary.push(Line::from(Span::styled(format!("直近: 交互 {}  同指連続 {}回", pct(l.alternation()), l.same_finger), Style::default().fg(Color::Cyan))));
+    }
+    f.render_widget(Paragraph::new(summary).block(Block::default().borders(Borders::ALL).title(format!("手の使い方（{}セッション）", all.sessions))), top[1]);
+
+    let rows: Vec<Row> = all.fingers.iter().map(|s| {
+        let load = all.load(s.finger);
+        Row::new(vec![
+            Cell::from(s.finger.label()).style(Style::default().fg(finger_color(s.finger))),
+            Cell::from(format!("{:>6}", s.keys)),
+            Cell::from(format!("{:<20} {:>3.0}%", "█".repeat((load * 40.0).round() as usize), load * 100.0)),
+            Cell::from(format!("{:>6}", pct(s.miss_rate()))).style(Style::default().fg(Color::Red)),
+            Cell::from(s.mean_latency().map(|t| format!("{:>5.0}ms", t * 1000.0)).unwrap_or_else(|| "     -".into())),
+        ])
+    }).collect();
+    let table = Table::new(rows, [Constraint::Length(6), Constraint::Length(6), Constraint::Length(25), Constraint::Length(6), Constraint::Length(7)])
+        .header(Row::new(vec!["指", "打鍵", "負荷", "ミス率", "間隔"]).style(Style::default().fg(Color::Yellow)))
+        .block(Block::default().borders(Borders::ALL).title(if all.unmapped > 0 { format!("指ごと（配列にないキー {}打）", all.unmapped) } else { "指ごと".into() }))
+        .column_spacing(1);
+    f.render_widget(table, v[1]);
+}
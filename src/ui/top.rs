@@ -18,7 +18,11 @@ pub fn draw(f: &mut Frame, app: &mut App) {
 
     let guide = Line::from(vec![
         Span::styled("[G] Start ", Style::default().fg(Color::Green)),
+        Span::styled("[L] 長文 ", Style::default().fg(Color::LightGreen)),
+        Span::styled("[D] 辞書 ", Style::default().fg(Color::LightBlue)),
+        Span::styled("[P] プロファイル ", Style::default().fg(Color::Magenta)),
         Span::styled("[R] Ranking ", Style::default().fg(Color::Yellow)),
+        Span::styled("[T] 統計 ", Style::default().fg(Color::LightYellow)),
         Span::styled("[S] Settings ", Style::default().fg(Color::Cyan)),
         Span::styled("[Q] Quit", Style::default().fg(Color::Red)),
     ]);
@@ -31,10 +35,13 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     let mut body: Vec<Line> = ascii_title_lines(&app.cfg.app_name);
     body.push(Line::from(""));
     body.push(Line::from("Japanese Typing TUI").style(Style::default().fg(Color::Gray)));
+    body.push(Line::from(format!("プロファイル: {}", crate::util::paths::profile())).style(Style::default().fg(Color::Magenta)));
     let title = Paragraph::new(body).alignment(Alignment::Center);
     f.render_widget(title, center);
 
-    let foot = if let Some(r) = &app.last_result {
+    let foot = if let Some(msg) = &app.status {
+        Paragraph::new(Line::from(Span::styled(msg.clone(), Style::default().fg(Color::Yellow)))).alignment(Alignment::Center)
+    } else if let Some(r) = &app.last_result {
         Paragraph::new(Line::from(format!(
             "Last  {:.3}s  Miss {}  Rank {}  Top {:.1} WPM",
             r.time_sec, r.miss, r.rank, r.wpm_top
@@ -1,13 +1,21 @@
-use std::{fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, path::PathBuf};
 use anyhow::Result;
 use serde::{Serialize, Deserialize};
 
+use crate::engine::game::SessionOptions;
+use super::keymap::{Finger, FingerMap, KeyboardLayout};
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum SoundMode { Off, Miss, All }
 
+/// Current `AppConfig::version`.
+pub const CONFIG_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// Schema version of the file (see `CONFIG_VERSION`)
+    #[serde(default)] pub version: u32,
     pub loss_ms_per_miss: u64,
     pub theme: String,
     #[serde(default = "default_app_name")] pub app_name: String,
@@ -18,19 +26,59 @@ pub struct AppConfig {
     #[serde(default = "default_countdown_sec")] pub countdown_sec: u64,
     #[serde(default = "default_sound_enabled")] pub sound_enabled: bool,
     #[serde(default = "default_sound_mode")] pub sound_mode: SoundMode,
+    /// Aozora Bunko .txt (or WordsFile .json) used by the long-text mode
+    #[serde(default)] pub long_text_path: Option<String>,
+    #[serde(default = "default_long_text_sentences")] pub long_text_sentences: u32,
+    /// Bookmark: index of the next sentence to type
+    #[serde(default)] pub long_text_pos: u32,
+    /// Active dictionary (WordsFile .json); None = built-in basic_common
+    #[serde(default)] pub dictionary: Option<String>,
+    /// romaji.yaml to use instead of the built-in rules (hot-reloaded)
+    #[serde(default)] pub rules_path: Option<String>,
+    /// Word selection options for normal sessions
+    #[serde(default)] pub session: SessionOptions,
+    /// Score backend: "json" (data/scores.json), "sqlite" (data/scores.db, needs the sqlite feature) or "memory"
+    #[serde(default = "default_store")] pub store: String,
+    /// Also keep sessions abandoned with ESC in the history (never ranked)
+    #[serde(default)] pub keep_aborted: bool,
+    /// Details screen: start the replay over when it reaches the end
+    #[serde(default = "default_replay_loop")] pub replay_loop: bool,
+    /// Pauses inside a word longer than this (ms) are marked as hesitations in the analysis
+    #[serde(default = "default_hesitation_ms")] pub hesitation_ms: u64,
+    /// Keyboard layout the finger analytics map keys with
+    #[serde(default)] pub keyboard_layout: KeyboardLayout,
+    /// Per-key finger overrides on top of the layout's touch-typing map, e.g. {"b": "r_index"}
+    #[serde(default)] pub finger_map: BTreeMap<String, Finger>,
+    /// Name of this machine's file in a sync folder (set on first sync)
+    #[serde(default)] pub machine_id: Option<String>,
 }
 
 impl Default for AppConfig {
-    fn default() -> Self { Self{ loss_ms_per_miss: 200, theme: "default".into(), app_name: default_app_name(), stage_w: default_stage_w(), stage_h: default_stage_h(), fixed_chars: default_fixed_chars(), target_chars: default_target_chars(), countdown_sec: default_countdown_sec(), sound_enabled: default_sound_enabled(), sound_mode: default_sound_mode() } }
+    fn default() -> Self { Self{ version: CONFIG_VERSION, loss_ms_per_miss: 200, theme: "default".into(), app_name: default_app_name(), stage_w: default_stage_w(), stage_h: default_stage_h(), fixed_chars: default_fixed_chars(), target_chars: default_target_chars(), countdown_sec: default_countdown_sec(), sound_enabled: default_sound_enabled(), sound_mode: default_sound_mode(), long_text_path: None, long_text_sentences: default_long_text_sentences(), long_text_pos: 0, dictionary: None, rules_path: None, session: SessionOptions::default(), store: default_store(), keep_aborted: false, replay_loop: default_replay_loop(), hesitation_ms: default_hesitation_ms(), keyboard_layout: KeyboardLayout::default(), finger_map: BTreeMap::new(), machine_id: None } }
 }
 
 impl AppConfig {
-    pub fn path() -> PathBuf { PathBuf::from("data/config.json") }
+    pub fn path() -> PathBuf { super::paths::config_file() }
     pub fn load_or_default() -> Result<Self> {
         let p = Self::path();
-        if let Ok(s) = fs::read_to_string(&p) { Ok(serde_json::from_str(&s).unwrap_or_default()) } else { Ok(Self::default()) }
+        if let Ok(s) = fs::read_to_string(&p) { Self::from_json(&s) } else { Ok(Self::default()) }
+    }
+    /// Parse a config file, migrating older schema versions (none yet).
+    pub fn from_json(s: &str) -> Result<Self> {
+        Ok(serde_json::from_value(super::persist::migrate(serde_json::from_str(s)?, CONFIG_VERSION, &[])?)?)
+    }
+    /// This machine's sync name: the configured one, else the host name, else a random id.
+    pub fn machine_name(&self) -> String {
+        if let Some(m) = &self.machine_id { return m.clone(); }
+        let host = std::env::var("HOSTNAME").or_else(|_| std::env::var("COMPUTERNAME")).ok()
+            .or_else(|| fs::read_to_string("/etc/hostname").ok())
+            .map(|h| h.trim().chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_').collect::<String>())
+            .unwrap_or_default();
+        if host.is_empty() { format!("pc-{:08x}", rand::random::<u32>()) } else { host }
     }
-    pub fn save(&self) -> Result<()> { fs::create_dir_all("data")?; fs::write(Self::path(), serde_json::to_string_pretty(self)?)?; Ok(()) }
+    /// Finger of each key for the configured layout and overrides.
+    pub fn fingers(&self) -> FingerMap { FingerMap::new(self.keyboard_layout, &self.finger_map) }
+    pub fn save(&self) -> Result<()> { super::persist::save_with_backups_every(&Self::path(), serde_json::to_string_pretty(self)?.as_bytes(), super::persist::BACKUP_INTERVAL) }
 }
 
 fn default_stage_w() -> u16 { 88 }
@@ -41,3 +89,7 @@ fn default_app_name() -> String { "IrohaType".into() }
 fn default_countdown_sec() -> u64 { 3 }
 fn default_sound_enabled() -> bool { true }
 fn default_sound_mode() -> SoundMode { SoundMode::All }
+fn default_long_text_sentences() -> u32 { 10 }
+fn default_store() -> String { "json".into() }
+fn default_replay_loop() -> bool { true }
+fn default_hesitation_ms() -> u64 { crate::engine::latency::DEFAULT_HESITATION_MS }
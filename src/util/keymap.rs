@@ -1,2 +1,97 @@
-// Placeholder for key mapping utilities if needed later.
+// Key positions (finger / row). Difficulty scoring and miss classification use QWERTY;
+// the finger analytics use a `FingerMap` of the configured layout.
 
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Finger { LPinky, LRing, LMiddle, LIndex, RIndex, RMiddle, RRing, RPinky }
+
+impl Finger {
+    /// Left pinky to right pinky.
+    pub const ALL: [Finger; 8] = [Finger::LPinky, Finger::LRing, Finger::LMiddle, Finger::LIndex, Finger::RIndex, Finger::RMiddle, Finger::RRing, Finger::RPinky];
+    pub fn is_left(self) -> bool { matches!(self, Finger::LPinky | Finger::LRing | Finger::LMiddle | Finger::LIndex) }
+    pub fn label(self) -> &'static str {
+        match self {
+            Finger::LPinky => "左小指", Finger::LRing => "左薬指", Finger::LMiddle => "左中指", Finger::LIndex => "左人差",
+            Finger::RIndex => "右人差", Finger::RMiddle => "右中指", Finger::RRing => "右薬指", Finger::RPinky => "右小指",
+        }
+    }
+    /// Touch-typing finger of a column (0 = leftmost key of a row).
+    fn of_column(col: usize) -> Self {
+        use Finger::*;
+        match col { 0 => LPinky, 1 => LRing, 2 => LMiddle, 3 | 4 => LIndex, 5 | 6 => RIndex, 7 => RMiddle, 8 => RRing, _ => RPinky }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyboardLayout { #[default] Qwerty, Dvorak, Colemak }
+
+impl KeyboardLayout {
+    /// Number, top, home and bottom row, left to right.
+    pub fn rows(self) -> [&'static str; 4] {
+        match self {
+            Self::Qwerty => ROWS,
+            Self::Dvorak => ["1234567890[", "',.pyfgcrl", "aoeuidhtns-", ";qjkxbmwvz"],
+            Self::Colemak => ["1234567890-", "qwfpgjluy;", "arstdhneio", "zxcvbkm,./"],
+        }
+    }
+    pub fn next(self) -> Self { match self { Self::Qwerty => Self::Dvorak, Self::Dvorak => Self::Colemak, Self::Colemak => Self::Qwerty } }
+    pub fn name(self) -> &'static str { match self { Self::Qwerty => "qwerty", Self::Dvorak => "dvorak", Self::Colemak => "colemak" } }
+}
+
+/// The finger of every key of a layout: the standard touch-typing columns, then the
+/// per-key overrides of the config (`AppConfig::finger_map`, e.g. `{"b": "r_index"}`).
+#[derive(Debug, Clone)]
+pub struct FingerMap { rows: Vec<Vec<(char, Finger)>> }
+
+impl FingerMap {
+    pub fn new(layout: KeyboardLayout, overrides: &BTreeMap<String, Finger>) -> Self {
+        let rows = layout.rows().iter().map(|r| r.chars().enumerate().map(|(col, k)| {
+            (k, overrides.get(&k.to_string()).copied().unwrap_or_else(|| Finger::of_column(col)))
+        }).collect()).collect();
+        Self { rows }
+    }
+    pub fn finger(&self, c: char) -> Option<Finger> {
+        let c = c.to_ascii_lowercase();
+        self.rows.iter().flatten().find(|(k, _)| *k == c).map(|(_, f)| *f)
+    }
+    /// Keys with their fingers, number row first.
+    pub fn rows(&self) -> &[Vec<(char, Finger)>] { &self.rows }
+}
+
+const ROWS: [&str; 4] = ["1234567890-", "qwertyuiop", "asdfghjkl;", "zxcvbnm,./"];
+
+/// Row index: 0 = number row, 1 = top, 2 = home, 3 = bottom.
+pub fn row_of(c: char) -> Option<u8> {
+    let c = c.to_ascii_lowercase();
+    ROWS.iter().position(|r| r.contains(c)).map(|i| i as u8)
+}
+
+/// Touch-typing finger for a key on a QWERTY keyboard.
+pub fn finger_of(c: char) -> Option<Finger> {
+    let c = c.to_ascii_lowercase();
+    let row = ROWS.iter().find(|r| r.contains(c))?;
+    let col = row.chars().position(|k| k == c)?;
+    Some(Finger::of_column(col))
+}
+
+/// (row, column) of a key.
+fn pos_of(c: char) -> Option<(usize, usize)> {
+    let c = c.to_ascii_lowercase();
+    ROWS.iter().enumerate().find_map(|(r, keys)| keys.chars().position(|k| k == c).map(|col| (r, col)))
+}
+
+/// Whether two keys touch on a staggered QWERTY keyboard (same row next to each other, or
+/// one row apart: a key sits below the upper row's keys at the same and the next column).
+pub fn adjacent(a: char, b: char) -> bool {
+    let (Some((ra, ca)), Some((rb, cb))) = (pos_of(a), pos_of(b)) else { return false };
+    match ra.abs_diff(rb) {
+        0 => ca.abs_diff(cb) == 1,
+        1 => { let (lo, up) = if ra > rb { (ca, cb) } else { (cb, ca) }; up == lo || up == lo + 1 }
+        _ => false,
+    }
+}
@@ -2,3 +2,6 @@ pub mod width;
 pub mod keymap;
 pub mod config;
 pub mod sound;
+pub mod paths;
+pub mod persist;
+pub mod watch;
@@ -0,0 +1,170 @@
+// Where config and data files live.
+// Priority: --data-dir > $IROHATYPE_DATA_DIR > platform dirs (XDG on Linux) > ./data.
+// With an explicit directory, config and data share it (same layout as the legacy ./data).
+// Profiles: the default profile uses the top-level files; others live in <data>/profiles/<name>/
+// (config.json, scores.json / scores.db). Dictionaries under <data>/words are shared.
+
+use std::{fs, path::{Path, PathBuf}, sync::{OnceLock, RwLock}};
+
+use anyhow::Result;
+use directories::ProjectDirs;
+
+pub const ENV_DATA_DIR: &str = "IROHATYPE_DATA_DIR";
+/// Folder used by versions that wrote next to the working directory
+pub const LEGACY_DIR: &str = "data";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dirs {
+    pub config: PathBuf,
+    pub data: PathBuf,
+    /// Set by --data-dir or the environment (no legacy migration then)
+    pub overridden: bool,
+}
+
+static DIRS: OnceLock<Dirs> = OnceLock::new();
+/// Active profile ("" = default)
+static PROFILE: RwLock<String> = RwLock::new(String::new());
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+impl Dirs {
+    /// Resolve the directories; `cli` is the value of --data-dir.
+    pub fn resolve(cli: Option<PathBuf>) -> Self {
+        let explicit = cli.or_else(|| std::env::var_os(ENV_DATA_DIR).filter(|v| !v.is_empty()).map(PathBuf::from));
+        if let Some(d) = explicit { return Self { config: d.clone(), data: d, overridden: true }; }
+        match ProjectDirs::from("", "", "irohatype") {
+            Some(p) => Self { config: p.config_dir().to_path_buf(), data: p.data_dir().to_path_buf(), overridden: false },
+            None => Self { config: PathBuf::from(LEGACY_DIR), data: PathBuf::from(LEGACY_DIR), overridden: false },
+        }
+    }
+}
+
+/// Fix the directories for this process. Later calls (and lookups before it) keep the first value.
+pub fn init(cli: Option<PathBuf>) -> &'static Dirs { DIRS.get_or_init(|| Dirs::resolve(cli)) }
+
+pub fn dirs() -> &'static Dirs { init(None) }
+pub fn config_file() -> PathBuf { profile_config_dir(&profile()).join("config.json") }
+pub fn scores_json() -> PathBuf { profile_data_dir(&profile()).join("scores.json") }
+pub fn scores_db() -> PathBuf { profile_data_dir(&profile()).join("scores.db") }
+pub fn words_dir() -> PathBuf { dirs().data.join("words") }
+
+pub fn profile() -> String {
+    let p = PROFILE.read().unwrap();
+    if p.is_empty() { DEFAULT_PROFILE.into() } else { p.clone() }
+}
+
+/// Switch the active profile (paths above follow it).
+pub fn set_profile(name: &str) { *PROFILE.write().unwrap() = if name == DEFAULT_PROFILE { String::new() } else { name.into() }; }
+
+pub fn profile_config_dir(name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE { dirs().config.clone() } else { dirs().data.join("profiles").join(name) }
+}
+pub fn profile_data_dir(name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE { dirs().data.clone() } else { dirs().data.join("profiles").join(name) }
+}
+
+/// Default profile first, then the others by name.
+pub fn profiles() -> Vec<String> {
+    let mut v: Vec<String> = fs::read_dir(dirs().data.join("profiles")).map(|rd| {
+        rd.flatten().filter(|e| e.path().is_dir()).map(|e| e.file_name().to_string_lossy().into_owned()).collect()
+    }).unwrap_or_default();
+    v.sort();
+    v.retain(|n| n != DEFAULT_PROFILE);
+    v.insert(0, DEFAULT_PROFILE.into());
+    v
+}
+
+/// Profile names become folder names: no separators, dots or control characters.
+pub fn check_profile_name(name: &str) -> Result<()> {
+    if name.trim().is_empty() || name.chars().count() > 32 { anyhow::bail!("プロファイル名は1〜32文字にしてください"); }
+    if name.starts_with('.') || name.chars().any(|c| c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')) {
+        anyhow::bail!("プロファイル名に使えない文字が含まれています: {}", name);
+    }
+    Ok(())
+}
+
+pub fn create_profile(name: &str) -> Result<()> {
+    check_profile_name(name)?;
+    let dir = profile_data_dir(name);
+    if name == DEFAULT_PROFILE || dir.exists() { anyhow::bail!("プロファイル {} は既にあります", name); }
+    fs::create_dir_all(dir)?;
+    Ok(())
+}
+
+/// File remembering the last used profile.
+fn active_profile_file() -> PathBuf { dirs().config.join("profile") }
+
+/// Activate the last used profile (falls back to the default if it is gone).
+pub fn restore_active_profile() {
+    let name = fs::read_to_string(active_profile_file()).map(|s| s.trim().to_string()).unwrap_or_default();
+    if !name.is_empty() && check_profile_name(&name).is_ok() && profile_data_dir(&name).is_dir() { set_profile(&name); }
+}
+
+pub fn save_active_profile() -> Result<()> { super::persist::write_atomic(&active_profile_file(), profile().as_bytes()) }
+
+/// Create the parent directory of a file about to be written.
+pub fn ensure_parent(path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() { if !dir.as_os_str().is_empty() { fs::create_dir_all(dir)?; } }
+    Ok(())
+}
+
+/// Marker in the data directory written once the legacy folder has been looked at.
+pub const MIGRATED_MARKER: &str = ".legacy-migrated";
+
+/// One-time copy of a legacy ./data folder into the resolved directories.
+/// Runs only while the data directory has no `MIGRATED_MARKER`, and copies nothing into
+/// directories that already hold a config or scorebook; either way the marker is written,
+/// so later starts (even after files were deleted) leave the destination alone.
+/// Files already present at the destination are never overwritten. Dictionary paths in the
+/// copied config that pointed into the legacy words folder are rewritten. Returns the
+/// copied destination paths.
+pub fn migrate_legacy(legacy: &Path, dirs: &Dirs) -> Result<Vec<PathBuf>> {
+    let mut copied = Vec::new();
+    if !legacy.is_dir() || same_dir(legacy, &dirs.data) { return Ok(copied); }
+    let marker = dirs.data.join(MIGRATED_MARKER);
+    if marker.exists() { return Ok(copied); }
+    let existing = [dirs.config.join("config.json"), dirs.data.join("scores.json"), dirs.data.join("scores.db")];
+    if existing.iter().any(|p| p.exists()) {
+        ensure_parent(&marker)?;
+        fs::write(&marker, "")?;
+        return Ok(copied);
+    }
+    let mut copy = |from: PathBuf, to: PathBuf| -> Result<()> {
+        if from.is_file() && !to.exists() {
+            ensure_parent(&to)?;
+            fs::copy(&from, &to)?;
+            copied.push(to);
+        }
+        Ok(())
+    };
+    copy(legacy.join("config.json"), dirs.config.join("config.json"))?;
+    copy(legacy.join("scores.json"), dirs.data.join("scores.json"))?;
+    copy(legacy.join("scores.db"), dirs.data.join("scores.db"))?;
+    if let Ok(rd) = fs::read_dir(legacy.join("words")) {
+        for e in rd.flatten() {
+            let name = e.file_name();
+            // the bundled dictionary is built in; no need for a user copy
+            if name == "basic_common.json" { continue; }
+            copy(e.path(), dirs.data.join("words").join(name))?;
+        }
+    }
+    let cfg_path = dirs.config.join("config.json");
+    if copied.contains(&cfg_path) {
+        let mut cfg: super::config::AppConfig = serde_json::from_str(&fs::read_to_string(&cfg_path)?).unwrap_or_default();
+        let old = legacy.join("words");
+        let moved = cfg.dictionary.as_deref().and_then(|d| Path::new(d).strip_prefix(&old).ok()).map(|rest| {
+            if rest == Path::new("basic_common.json") { None } else { Some(dirs.data.join("words").join(rest).to_string_lossy().into_owned()) }
+        });
+        if let Some(d) = moved {
+            cfg.dictionary = d;
+            fs::write(&cfg_path, serde_json::to_string_pretty(&cfg)?)?;
+        }
+    }
+    ensure_parent(&marker)?;
+    fs::write(&marker, "")?;
+    Ok(copied)
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) { (Ok(a), Ok(b)) => a == b, _ => a == b }
+}
@@ -0,0 +1,117 @@
+// Crash-safe file persistence: atomic writes, rotating backups, schema versions
+// and detection of corrupted files at startup.
+
+use std::{fs, io::Write, path::{Path, PathBuf}, time::Duration};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+/// Number of `<file>.bakN` copies kept (bak1 = newest).
+pub const KEEP_BACKUPS: usize = 3;
+
+/// Minimum age of bak1 before the backups of the config / scorebook rotate again. Settings are
+/// saved on every keypress; without this the backups would all hold the last few keystrokes.
+pub const BACKUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Write a file atomically: write to a sibling temp file, fsync, then rename over the target.
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    if let Some(dir) = path.parent() { if !dir.as_os_str().is_empty() { fs::create_dir_all(dir)?; } }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = Path::new(&tmp);
+    {
+        let mut f = fs::File::create(tmp)?;
+        f.write_all(data)?;
+        f.sync_all()?;
+    }
+    fs::rename(tmp, path)?;
+    Ok(())
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_owned();
+    s.push(suffix);
+    PathBuf::from(s)
+}
+
+pub fn backup_path(path: &Path, n: usize) -> PathBuf { with_suffix(path, &format!(".bak{}", n)) }
+
+/// Existing backups of `path`, newest first.
+pub fn backups(path: &Path) -> Vec<PathBuf> {
+    (1..=KEEP_BACKUPS).map(|n| backup_path(path, n)).filter(|p| p.is_file()).collect()
+}
+
+/// Rotate backups (bak1 → bak2 …), copy the current file to bak1, then write atomically.
+/// The current file is copied rather than moved so a crash never leaves it missing.
+pub fn save_with_backups(path: &Path, data: &[u8]) -> Result<()> { save_with_backups_every(path, data, Duration::ZERO) }
+
+/// `save_with_backups`, rotating only when bak1 is missing or older than `min_age`;
+/// otherwise the file is just written (bak1 keeps the older state).
+pub fn save_with_backups_every(path: &Path, data: &[u8], min_age: Duration) -> Result<()> {
+    let due = fs::metadata(backup_path(path, 1)).and_then(|m| m.modified()).ok()
+        .and_then(|t| t.elapsed().ok()).is_none_or(|age| age >= min_age);
+    if due && path.is_file() {
+        for n in (1..KEEP_BACKUPS).rev() {
+            let from = backup_path(path, n);
+            if from.is_file() { fs::rename(&from, backup_path(path, n + 1))?; }
+        }
+        fs::copy(path, backup_path(path, 1))?;
+    }
+    write_atomic(path, data)
+}
+
+/// The file was written by a newer IrohaType; it must not be touched.
+#[derive(Debug, thiserror::Error)]
+#[error("{path} はより新しいバージョン (v{found}) で作成されています（対応: v{supported}まで）")]
+pub struct NewerVersion { pub path: String, pub found: u32, pub supported: u32 }
+
+/// Bring a JSON document up to `current` by applying `steps` (steps[i] upgrades v(i+1) → v(i+2)).
+/// A missing `version` means 1. The result carries `"version": current`.
+pub fn migrate(mut v: Value, current: u32, steps: &[fn(&mut Value)]) -> Result<Value> {
+    let found = v.as_object().ok_or_else(|| anyhow!("JSONオブジェクトではありません"))?
+        .get("version").and_then(|x| x.as_u64()).unwrap_or(1) as u32;
+    if found > current { return Err(NewerVersion { path: String::new(), found, supported: current }.into()); }
+    for step in steps.iter().take(current.saturating_sub(1) as usize).skip(found.saturating_sub(1) as usize) { step(&mut v); }
+    v["version"] = current.into();
+    Ok(v)
+}
+
+/// A corrupted file found at startup. The broken file has been moved to `moved_to`.
+#[derive(Debug, Clone)]
+pub struct Recovery {
+    /// What the file holds (設定 / 記録)
+    pub label: &'static str,
+    pub path: PathBuf,
+    pub moved_to: PathBuf,
+    pub error: String,
+    /// Backups that parse, newest first
+    pub backups: Vec<PathBuf>,
+    pub sel: usize,
+}
+
+/// Check that `path` parses with `parse`. A corrupted file is moved aside
+/// (`<file>.corrupt-<timestamp>`) so the app can start from defaults without
+/// overwriting it, and a `Recovery` listing the usable backups is returned.
+/// Files from a newer version are reported as an error instead.
+pub fn check(label: &'static str, path: &Path, parse: impl Fn(&str) -> Result<()>) -> Result<Option<Recovery>> {
+    let Ok(bytes) = fs::read(path) else { return Ok(None) };
+    let parsed = String::from_utf8(bytes).map_err(anyhow::Error::from).and_then(|s| parse(&s));
+    let err = match parsed {
+        Ok(()) => return Ok(None),
+        Err(e) => match e.downcast::<NewerVersion>() {
+            Ok(nv) => return Err(NewerVersion { path: path.display().to_string(), ..nv }.into()),
+            Err(e) => e,
+        },
+    };
+    let moved_to = with_suffix(path, &format!(".corrupt-{}", chrono::Local::now().format("%Y%m%d%H%M%S")));
+    fs::rename(path, &moved_to)?;
+    let backups = backups(path).into_iter()
+        .filter(|b| fs::read_to_string(b).map_err(anyhow::Error::from).and_then(|s| parse(&s)).is_ok())
+        .collect();
+    Ok(Some(Recovery { label, path: path.to_path_buf(), moved_to, error: format!("{:#}", err), backups, sel: 0 }))
+}
+
+/// Put a backup back in place of the (moved-aside) corrupted file.
+pub fn restore(rec: &Recovery, backup: &Path) -> Result<()> {
+    write_atomic(&rec.path, &fs::read(backup)?)
+}
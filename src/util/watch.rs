@@ -0,0 +1,39 @@
+use std::{fs, path::{Path, PathBuf}, time::{Duration, Instant, SystemTime}};
+
+// Polling file watcher (mtime/size checks) so hot-reload works on every platform.
+#[derive(Debug, Clone)]
+pub struct FileWatch {
+    pub path: PathBuf,
+    stamp: Option<(SystemTime, u64)>,
+    last_check: Instant,
+    interval: Duration,
+}
+
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let m = fs::metadata(path).ok()?;
+    Some((m.modified().ok()?, m.len()))
+}
+
+impl FileWatch {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let stamp = stamp(&path);
+        Self { path, stamp, last_check: Instant::now(), interval: Duration::from_millis(1000) }
+    }
+
+    /// True once per on-disk change. Checks at most once per interval.
+    pub fn poll(&mut self) -> bool {
+        if self.last_check.elapsed() < self.interval { return false; }
+        self.force_poll()
+    }
+
+    /// Check now, ignoring the interval.
+    pub fn force_poll(&mut self) -> bool {
+        self.last_check = Instant::now();
+        let cur = stamp(&self.path);
+        // a missing file (mid-save) is not a change; wait for it to reappear
+        if cur.is_none() || cur == self.stamp { return false; }
+        self.stamp = cur;
+        true
+    }
+}
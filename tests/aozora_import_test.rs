@@ -0,0 +1,92 @@
+use irohatype::engine::aozora::{load_long_text, parse, resolve_ruby, strip_annotations, to_words_file};
+use irohatype::engine::kana::kana_to_romaji;
+
+const SAMPLE: &str = "\u{feff}走れメロス
+太宰治
+
+-------------------------------------------------------
+【テキスト中に現れる記号について】
+
+《》：ルビ
+（例）邪智暴虐《じゃちぼうぎゃく》
+-------------------------------------------------------
+
+　メロスは激怒《げきど》した。必ず、かの｜邪智暴虐《じゃちぼうぎゃく》の王を除かなければならぬと決意した。［＃「決意した」に傍点］
+　「はい。」と答えた。
+　メロスには政治がわからぬ。
+
+底本：「太宰治全集3」筑摩書房
+";
+
+#[test]
+fn ruby_implicit_and_explicit() {
+    let (d, r) = resolve_ruby("激怒《げきど》した");
+    assert_eq!(d, "激怒した");
+    assert_eq!(r, "げきどした");
+    let (d, r) = resolve_ruby("かの｜邪智暴虐《じゃちぼうぎゃく》の王");
+    assert_eq!(d, "かの邪智暴虐の王");
+    assert_eq!(r, "かのじゃちぼうぎゃくの王");
+    let (_, r) = resolve_ruby("漢字《かんじ》大人《おとな》");
+    assert_eq!(r, "かんじおとな");
+}
+
+#[test]
+fn annotations_are_stripped() {
+    assert_eq!(strip_annotations("決意した。［＃「決意した」に傍点］"), "決意した。");
+}
+
+#[test]
+fn header_footer_and_sentences() {
+    let doc = parse(SAMPLE);
+    assert_eq!(doc.title, "走れメロス");
+    assert_eq!(doc.author, "太宰治");
+    let displays: Vec<&str> = doc.sentences.iter().map(|(d, _)| d.as_str()).collect();
+    assert_eq!(displays, vec![
+        "メロスは激怒した。",
+        "必ず、かの邪智暴虐の王を除かなければならぬと決意した。",
+        "「はい。」と答えた。",
+        "メロスには政治がわからぬ。",
+    ]);
+    // sentences with kanji lacking ruby are kept: the kanji are shown but only the kana typed
+    let (wf, st) = to_words_file(&doc);
+    assert_eq!((st.sentences, st.partial, st.skipped, st.filled), (4, 3, 0, 0));
+    assert_eq!(wf.entries.len(), 4);
+    assert_eq!(wf.entries[0].romas[0], "merosuhagekidoshita.");
+    assert_eq!(wf.entries[3].jp, "メロスには政治がわからぬ。");
+    assert_eq!(wf.entries[3].romas[0], "merosunihagawakaranu.");
+    assert!(st.note().unwrap().contains("3 文は仮名のみ"));
+}
+
+#[test]
+fn ruby_given_once_reads_later_occurrences() {
+    let doc = parse("題\n著者\n\n　政治《せいじ》の話《はなし》。\n　メロスには政治がわからぬ。\n");
+    let (wf, st) = to_words_file(&doc);
+    assert_eq!(wf.entries[1].romas[0], "merosunihaseijigawakaranu.");
+    assert_eq!((st.filled, st.partial, st.untyped), (1, 0, 0));
+    assert!(st.note().is_none());
+}
+
+#[test]
+fn mostly_untypable_texts_are_rejected() {
+    let dir = std::env::temp_dir().join(format!("irohatype-aozora-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let kanji = dir.join("kanji.txt");
+    std::fs::write(&kanji, "千字文\n周興嗣\n\n　天地玄黄、宇宙洪荒の日。\n　日月盈昃。\n").unwrap();
+    assert!(load_long_text(&kanji).unwrap_err().to_string().contains("読みの分からない文字"));
+    let ok = dir.join("ok.txt");
+    std::fs::write(&ok, SAMPLE).unwrap();
+    let (wf, st) = load_long_text(&ok).unwrap();
+    assert_eq!(wf.entries.len(), 4);
+    assert!(st.typable_share() > 0.5);
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn kana_romaji_edge_cases() {
+    assert_eq!(kana_to_romaji("がっこう").as_deref(), Some("gakkou"));
+    assert_eq!(kana_to_romaji("しんよう").as_deref(), Some("shinnyou"));
+    assert_eq!(kana_to_romaji("こんぶ").as_deref(), Some("konbu"));
+    assert_eq!(kana_to_romaji("コーヒー").as_deref(), Some("ko-hi-"));
+    assert_eq!(kana_to_romaji("ほん").as_deref(), Some("honn"));
+    assert_eq!(kana_to_romaji("本"), None);
+}
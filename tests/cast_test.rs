@@ -0,0 +1,65 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use irohatype::engine::game::{Game, GameConfig, WordEntry};
+use irohatype::engine::romaji::RomajiRules;
+use irohatype::store::json::ScoreRecord;
+use irohatype::ui::cast;
+
+fn rules() -> RomajiRules {
+    RomajiRules::from_yaml_file(std::path::Path::new("data/rules/romaji.yaml")).unwrap()
+}
+
+fn played() -> ScoreRecord {
+    let words = vec![WordEntry { jp: "あい".into(), romas: vec!["ai".into()], ..Default::default() }, WordEntry { jp: "いえ".into(), romas: vec!["ie".into()], ..Default::default() }];
+    let mut g = Game::new_with_rules(GameConfig { max_words: 2, time_limit_sec: f64::INFINITY, ..Default::default() }, words, rules()).unwrap();
+    g.start();
+    g.begin_now();
+    let seq: Vec<char> = g.words.iter().flat_map(|w| w.romas[0].chars().collect::<Vec<_>>()).collect();
+    for (i, c) in seq.into_iter().enumerate() {
+        if i == 1 { g.handle_key(KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE)).unwrap(); }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        g.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)).unwrap();
+    }
+    g.finish_record()
+}
+
+#[test]
+fn cast_is_asciinema_v2() {
+    let rec = played();
+    let text = cast::render(&rec, &rules(), 80, 24).unwrap();
+    let mut lines = text.lines();
+    let header: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+    assert_eq!((header["version"].as_u64(), header["width"].as_u64(), header["height"].as_u64()), (Some(2), Some(80), Some(24)));
+
+    let events: Vec<(f64, String, String)> = lines.map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert!(events.iter().all(|(_, kind, _)| kind == "o"));
+    assert!(events.windows(2).all(|w| w[0].0 <= w[1].0), "timestamps must not go back");
+    assert_eq!(events[0].0, 0.0);
+    // the first frame draws the play screen from a cleared terminal
+    assert!(events[0].2.starts_with("\x1b[?25l\x1b[2J"));
+    assert!(events[0].2.contains("ローマ字"));
+    // ends after the run with the final state held
+    assert!(events.last().unwrap().0 > rec.time_sec);
+    // one frame per keystroke at its recorded time (plus timer ticks)
+    for e in rec.replay.as_ref().unwrap() {
+        let t = (e.t * 1000.0).round() / 1000.0;
+        assert!(events.iter().any(|(et, _, _)| (et - t).abs() < 1e-9), "no frame at {}", t);
+    }
+    // the miss is shown in red bold
+    assert!(events.iter().any(|(_, _, d)| d.contains("\x1b[0;1;31m")));
+}
+
+#[test]
+fn records_without_replay_cannot_be_cast() {
+    let rec = ScoreRecord { datetime: "2026-10-01T10:00:00".into(), ..Default::default() };
+    assert!(cast::render(&rec, &rules(), 80, 24).is_err());
+}
+
+#[test]
+fn writes_id_named_file() {
+    let rec = played();
+    let dir = std::env::temp_dir().join(format!("irohatype-cast-{}", std::process::id()));
+    let path = cast::write(&dir, &rec, &rules(), 60, 20).unwrap();
+    assert_eq!(path.file_name().unwrap().to_string_lossy(), format!("{}.cast", rec.id));
+    assert!(std::fs::read_to_string(&path).unwrap().starts_with("{\""));
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,51 @@
+use irohatype::engine::compare::{self, WordDelta};
+use irohatype::engine::game::WordEntry;
+use irohatype::store::json::{ScoreRecord, SplitRec};
+
+fn rec(words: &[(&str, f64, u32)], seq: bool) -> ScoreRecord {
+    let splits: Vec<SplitRec> = words.iter().map(|(w, s, m)| SplitRec { word: w.to_string(), sec: *s, miss: *m }).collect();
+    ScoreRecord {
+        time_sec: splits.iter().map(|s| s.sec).sum(),
+        miss: splits.iter().map(|s| s.miss).sum(),
+        word_seq: if seq { words.iter().map(|(w, _, _)| WordEntry { jp: w.to_string(), ..Default::default() }).collect() } else { Vec::new() },
+        splits,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn same_sequence_aligns_by_position() {
+    let a = rec(&[("あい", 0.5, 0), ("うえ", 0.8, 1), ("あい", 0.4, 0)], true);
+    let b = rec(&[("あい", 0.4, 0), ("うえ", 1.2, 0), ("あい", 0.4, 0)], true);
+    let c = compare::compare(&a, &b);
+    assert!(c.same_words);
+    assert_eq!(c.rows.len(), 3);
+    assert!((c.rows[1].delta().unwrap() - 0.4).abs() < 1e-9);
+    assert_eq!((c.rows[1].miss_a, c.rows[1].miss_b), (1, 0));
+    assert!((c.time_delta - 0.3).abs() < 1e-9);
+    assert_eq!(c.miss_delta, -1);
+    // largest first; equal words are never "largest"
+    assert_eq!(c.largest(5), vec![1, 0]);
+}
+
+#[test]
+fn different_sequences_align_common_words() {
+    let a = rec(&[("いぬ", 0.5, 0), ("ねこ", 0.6, 0), ("とり", 0.7, 0)], true);
+    let b = rec(&[("ねこ", 0.5, 0), ("さる", 0.9, 0), ("とり", 0.6, 0)], true);
+    let c = compare::compare(&a, &b);
+    assert!(!c.same_words);
+    let words: Vec<(&str, bool, bool)> = c.rows.iter().map(|r| (r.word.as_str(), r.a.is_some(), r.b.is_some())).collect();
+    assert_eq!(words, vec![("いぬ", true, false), ("ねこ", true, true), ("さる", false, true), ("とり", true, true)]);
+    assert_eq!(c.matched(), 2);
+    assert_eq!(c.rows[0].delta(), None);
+}
+
+#[test]
+fn aborted_run_is_compared_on_its_prefix() {
+    let a = rec(&[("あい", 0.5, 0), ("うえ", 0.8, 0), ("おか", 0.6, 0)], false);
+    let b = rec(&[("あい", 0.7, 0), ("うえ", 0.6, 0)], false);
+    let c = compare::compare(&a, &b);
+    assert!(!c.same_words);
+    assert_eq!(c.rows.len(), 3);
+    assert_eq!(c.rows[2], WordDelta { word: "おか".into(), a: Some(0.6), b: None, miss_a: 0, miss_b: 0 });
+}
@@ -0,0 +1,46 @@
+use irohatype::engine::difficulty::{features, grade_bounds, grade_of, score, Grade, WordTimes};
+use irohatype::engine::game::{filter_words, SessionOptions, WordEntry};
+use irohatype::store::json::{ScoreRecord, SplitRec};
+
+fn w(jp: &str, roma: &str) -> WordEntry { WordEntry { jp: jp.into(), romas: vec![roma.into()], ..Default::default() } }
+
+#[test]
+fn edge_cases_are_counted() {
+    assert_eq!(features("ai").edge_cases, 0);
+    assert_eq!(features("kyaku").edge_cases, 1); // きゃ
+    assert_eq!(features("gakkou").edge_cases, 1); // っ
+    assert_eq!(features("konbu").edge_cases, 1); // ん
+    assert_eq!(features("shashin").edge_cases, 1); // しゃ (語末 n は数えない)
+    // "ed" is typed with the left middle finger twice
+    assert_eq!(features("ed").same_finger, 1);
+    // q (top row) -> z (bottom row)
+    assert_eq!(features("qz").row_jumps, 1);
+}
+
+#[test]
+fn explicit_difficulty_wins_and_history_scales() {
+    let mut e = w("がっこう", "gakkou");
+    let base = score(&e, None);
+    e.difficulty = Some(1.0);
+    assert_eq!(score(&e, None), 1.0);
+
+    let words = vec![w("あい", "ai"), w("がっこう", "gakkou")];
+    let rec = ScoreRecord { splits: vec![
+        SplitRec { word: "あい".into(), sec: 0.2, miss: 0 },
+        SplitRec { word: "がっこう".into(), sec: 3.0, miss: 0 },
+    ], ..Default::default() };
+    let hist = WordTimes::from_records([rec].iter(), &words);
+    assert!(score(&words[1], Some(&hist)) > base);
+    assert!(score(&words[0], Some(&hist)) < score(&words[0], None));
+}
+
+#[test]
+fn grades_split_the_dictionary() {
+    let words = vec![w("あ", "a"), w("い", "i"), w("かいしゃ", "kaisha"), w("がっこう", "gakkou"), w("しゅっちょう", "shutchou"), w("きっぷ", "kippu")];
+    let b = grade_bounds(&words);
+    assert_eq!(grade_of(score(&words[0], None), b), Grade::Easy);
+    assert_eq!(grade_of(score(&words[4], None), b), Grade::Hard);
+    let hard = filter_words(&words, &SessionOptions { grade: Some(Grade::Hard), ..Default::default() });
+    assert!(hard.iter().any(|e| e.jp == "しゅっちょう"));
+    assert!(hard.iter().all(|e| e.jp != "あ"));
+}
@@ -0,0 +1,66 @@
+use irohatype::store::export::{collect, markdown_report, render, to_csv, to_jsonl, Format};
+use irohatype::store::json::{KeyEv, ScoreRecord, SplitRec};
+use irohatype::store::memory::MemoryStore;
+use irohatype::store::ScoreStore;
+
+fn rec() -> ScoreRecord {
+    ScoreRecord {
+        id: "r1".into(), mode: "basic_common".into(), datetime: "2026-10-01T10:00:00+09:00".into(),
+        time_sec: 2.0, miss: 1, timeloss_sec: 0.2, rank: "B".into(), memo: Some("手が冷たい, \"寒い\"".into()),
+        splits: vec![SplitRec { word: "ねこ".into(), sec: 0.5, miss: 0 }, SplitRec { word: "いぬ|犬".into(), sec: 1.5, miss: 1 }],
+        replay: Some((0..4).map(|i| KeyEv { t: i as f64 * 0.5, c: "a".into(), ok: i != 1, w: 0 }).collect()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn csv_has_header_quoting_and_kpm() {
+    let csv = to_csv(&[rec()]);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("id,datetime,mode,time_sec,miss,timeloss_sec,rank,kpm"));
+    // 3 correct keys in 2 s = 90 KPM; memo with comma/quotes is quoted
+    assert!(lines[1].starts_with("r1,2026-10-01T10:00:00+09:00,basic_common,2.000,1,0.200,B,90.0,"));
+    assert!(lines[1].contains("\"手が冷たい, \"\"寒い\"\"\""));
+    assert!(lines[1].ends_with("ねこ:0.500:0|いぬ|犬:1.500:1"));
+}
+
+#[test]
+fn jsonl_is_one_object_per_line_without_replay() {
+    let out = to_jsonl(&[rec(), rec()]).unwrap();
+    assert_eq!(out.lines().count(), 2);
+    let v: serde_json::Value = serde_json::from_str(out.lines().next().unwrap()).unwrap();
+    assert_eq!(v["kpm"].as_f64(), Some(90.0));
+    assert_eq!(v["splits"].as_array().unwrap().len(), 2);
+    assert!(v.get("replay").is_none());
+}
+
+#[test]
+fn markdown_report_lists_splits_and_extremes() {
+    let md = markdown_report(&rec());
+    assert!(md.contains("| タイム | 2.000 秒 |"));
+    assert!(md.contains("| 2 | いぬ\\|犬 | 1.500 | 1 |"));
+    let fast = md.split("## 速い語句").nth(1).unwrap();
+    assert!(fast.trim_start().starts_with("- 0.500s ねこ"));
+    let slow = md.split("## 苦手語句").nth(1).unwrap();
+    assert!(slow.trim_start().starts_with("- 1.500s いぬ|犬"));
+    assert!(render(&[], Format::Markdown).is_err());
+}
+
+#[test]
+fn collect_filters_mode_and_aborted() {
+    let mut store = MemoryStore::default();
+    store.insert_and_rank(rec()).unwrap();
+    let mut other = rec();
+    other.id = "r2".into();
+    other.mode = "long_text".into();
+    store.insert_and_rank(other).unwrap();
+    let mut ab = rec();
+    ab.id = "r3".into();
+    ab.aborted = true;
+    store.insert_and_rank(ab).unwrap();
+    assert_eq!(collect(&store, None, false).unwrap().len(), 2);
+    assert_eq!(collect(&store, Some("long_text"), false).unwrap().len(), 1);
+    assert_eq!(collect(&store, None, true).unwrap().len(), 3);
+    assert_eq!(Format::parse("MD").unwrap(), Format::Markdown);
+}
@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use irohatype::engine::fingers;
+use irohatype::store::json::{KeyEv, ScoreRecord};
+use irohatype::util::config::AppConfig;
+use irohatype::util::keymap::{Finger, FingerMap, KeyboardLayout};
+
+fn ev(t: f64, c: &str, ok: bool, w: usize) -> KeyEv { KeyEv { t, c: c.into(), ok, w } }
+
+fn qwerty() -> FingerMap { FingerMap::new(KeyboardLayout::Qwerty, &BTreeMap::new()) }
+
+#[test]
+fn finger_map_follows_layout_and_overrides() {
+    let q = qwerty();
+    assert_eq!(q.finger('j'), Some(Finger::RIndex));
+    assert_eq!(q.finger('A'), Some(Finger::LPinky));
+    assert_eq!(q.finger('-'), Some(Finger::RPinky));
+    assert_eq!(q.finger(' '), None);
+    let d = FingerMap::new(KeyboardLayout::Dvorak, &BTreeMap::new());
+    assert_eq!(d.finger('u'), Some(Finger::LIndex));
+    assert_eq!(d.finger('s'), Some(Finger::RPinky));
+    let o = FingerMap::new(KeyboardLayout::Qwerty, &BTreeMap::from([("b".to_string(), Finger::RIndex)]));
+    assert_eq!(q.finger('b'), Some(Finger::LIndex));
+    assert_eq!(o.finger('b'), Some(Finger::RIndex));
+}
+
+#[test]
+fn config_selects_layout_and_overrides() {
+    let cfg = AppConfig::from_json(r#"{"loss_ms_per_miss":200,"theme":"default","keyboard_layout":"colemak","finger_map":{"b":"r_index"}}"#).unwrap();
+    assert_eq!(cfg.keyboard_layout, KeyboardLayout::Colemak);
+    let map = cfg.fingers();
+    assert_eq!(map.finger('n'), Some(Finger::RIndex));
+    assert_eq!(map.finger('b'), Some(Finger::RIndex));
+    assert_eq!(AppConfig::default().keyboard_layout, KeyboardLayout::Qwerty);
+}
+
+#[test]
+fn usage_of_a_session() {
+    // かき: k a (l = miss, penalty 0.2) k i; いか: i k a
+    let evs = [ev(0.5, "k", true, 0), ev(0.6, "a", true, 0), ev(0.9, "l", false, 0), ev(1.0, "k", true, 0), ev(1.1, "i", true, 0),
+               ev(1.6, "i", true, 1), ev(1.7, "k", true, 1), ev(1.8, "a", true, 1)];
+    let u = fingers::analyze(&evs, &qwerty(), 0.2);
+    assert_eq!(u.stat(Finger::RMiddle).keys, 5);
+    assert_eq!(u.stat(Finger::RRing).misses, 1);
+    assert_eq!(u.stat(Finger::RRing).miss_rate(), Some(1.0));
+    assert!((u.load(Finger::LPinky) - 2.0 / 8.0).abs() < 1e-9);
+    // k-a, a-k, k-i | i-k, k-a: k-i and i-k are the same finger on different keys
+    assert_eq!((u.pairs, u.alternations, u.same_finger), (5, 3, 2));
+    assert!((u.alternation().unwrap() - 0.6).abs() < 1e-9);
+    // the first key of each word is not timed; the miss's gap is 0.3 - 0.2
+    let ring = u.stat(Finger::RRing);
+    assert_eq!(ring.timed, 1);
+    assert!((ring.mean_latency().unwrap() - 0.1).abs() < 1e-9);
+    assert_eq!(u.stat(Finger::RMiddle).timed, 3);
+}
+
+#[test]
+fn history_is_summed_and_the_newest_session_kept() {
+    let rec = |dt: &str, mode: &str, keys: &[&str]| ScoreRecord {
+        mode: mode.into(), datetime: dt.into(),
+        replay: Some(keys.iter().enumerate().map(|(i, k)| ev(0.1 * (i + 1) as f64, k, true, 0)).collect()),
+        ..Default::default()
+    };
+    let history = vec![
+        rec("2026-10-02T10:00:00+09:00", "basic_common", &["j", "f"]),
+        rec("2026-10-01T10:00:00+09:00", "basic_common", &["d", "e", " "]),
+        rec("2026-10-03T10:00:00+09:00", "long_text", &["a"]),
+        ScoreRecord { mode: "basic_common".into(), datetime: "2026-10-04T10:00:00+09:00".into(), ..Default::default() },
+    ];
+    let (all, latest) = fingers::over_history(&history, "basic_common", &qwerty());
+    assert_eq!((all.sessions, all.total_keys(), all.unmapped), (2, 4, 1));
+    assert_eq!(all.same_finger, 1);
+    assert_eq!(all.same_finger_per_session(), 0.5);
+    let latest = latest.unwrap();
+    assert_eq!(latest.keys.keys().copied().collect::<Vec<_>>(), vec!['f', 'j']);
+    assert_eq!(latest.alternation(), Some(1.0));
+}
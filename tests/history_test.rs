@@ -0,0 +1,57 @@
+use irohatype::store::json::{ScoreBook, ScoreRecord, SplitRec};
+
+fn rec(dt: &str, t: f64) -> ScoreRecord {
+    ScoreRecord {
+        mode: "basic_common".into(), datetime: dt.into(), time_sec: t,
+        splits: vec![SplitRec { word: "あ".into(), sec: t, miss: 0 }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn history_keeps_every_session() {
+    let mut book = ScoreBook::default();
+    for i in 0..150 { book.insert_and_rank(rec(&format!("2026-10-01T10:{:02}:{:02}", i / 60, i % 60), 100.0 - i as f64 * 0.1)); }
+    assert_eq!(book.history.len(), 150);
+    assert_eq!(book.top.len(), 100);
+    assert_eq!(book.lap.len(), 10);
+    // ids are unique
+    let mut ids: Vec<&str> = book.history.iter().map(|r| r.id.as_str()).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 150);
+    // a slow run is outside the Top-100 but still in the history
+    assert_eq!(book.insert_and_rank(rec("2026-10-02T00:00:00", 500.0)), (None, false));
+    assert_eq!(book.history.len(), 151);
+}
+
+#[test]
+fn saved_file_contains_only_history() {
+    let mut book = ScoreBook::default();
+    book.insert_and_rank(rec("2026-10-01T10:00:00", 30.0));
+    let json = serde_json::to_value(&book).unwrap();
+    assert!(json.get("history").is_some());
+    assert!(json.get("top").is_none());
+    let back = ScoreBook::from_json(&json.to_string()).unwrap();
+    assert_eq!(back.top.len(), 1);
+    assert_eq!(back.top[0].id, book.history[0].id);
+}
+
+#[test]
+fn legacy_top_lap_file_is_upgraded() {
+    let legacy = r#"{
+        "top": [
+            {"mode":"basic_common","datetime":"2026-09-02T10:00:00","time_sec":20.0,"miss":0,"timeloss_sec":0.0,"splits":[],"wpm_top":0.0,"wpm_worst":0.0,"rank":"A"},
+            {"mode":"basic_common","datetime":"2026-09-01T10:00:00","time_sec":25.0,"miss":1,"timeloss_sec":0.2,"splits":[],"wpm_top":0.0,"wpm_worst":0.0,"rank":"B"}
+        ],
+        "lap": [
+            {"mode":"","datetime":"","time_sec":0.4,"miss":0,"timeloss_sec":0.0,"splits":[],"wpm_top":0.0,"wpm_worst":0.0,"rank":"","word_display":"いぬ"}
+        ]
+    }"#;
+    let book = ScoreBook::from_json(legacy).unwrap();
+    let dts: Vec<&str> = book.history.iter().map(|r| r.datetime.as_str()).collect();
+    assert_eq!(dts, vec!["2026-09-01T10:00:00", "2026-09-02T10:00:00"]);
+    assert!(book.history.iter().all(|r| !r.id.is_empty()));
+    assert_eq!(book.top[0].time_sec, 20.0);
+    assert_eq!(book.lap[0].word_display.as_deref(), Some("いぬ"));
+}
@@ -0,0 +1,35 @@
+use irohatype::app::load_words_checked;
+use irohatype::util::watch::FileWatch;
+
+fn tmp(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("irohatype-watch-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join("words.json")
+}
+
+#[test]
+fn watch_reports_each_change_once() {
+    let p = tmp("poll");
+    std::fs::write(&p, r#"{"title":"t","version":1,"entries":[]}"#).unwrap();
+    let mut w = FileWatch::new(&p);
+    assert!(!w.force_poll());
+    std::fs::write(&p, r#"{"title":"t2","version":1,"entries":[]}"#).unwrap();
+    assert!(w.force_poll());
+    assert!(!w.force_poll());
+    // deleted mid-save: not a change
+    std::fs::remove_file(&p).unwrap();
+    assert!(!w.force_poll());
+    std::fs::remove_dir_all(p.parent().unwrap()).ok();
+}
+
+#[test]
+fn invalid_words_are_rejected() {
+    let p = tmp("validate");
+    std::fs::write(&p, r#"{"title":"t","version":1,"entries":[{"jp":"あい","romas":["ai"]}]}"#).unwrap();
+    assert_eq!(load_words_checked(&p).unwrap().len(), 1);
+    std::fs::write(&p, r#"{"title":"t","version":1,"entries":[{"jp":"あい""#).unwrap();
+    assert!(load_words_checked(&p).is_err());
+    std::fs::write(&p, r#"{"title":"t","version":1,"entries":[{"jp":"あい","romas":[]}]}"#).unwrap();
+    assert!(load_words_checked(&p).is_err());
+    std::fs::remove_dir_all(p.parent().unwrap()).ok();
+}
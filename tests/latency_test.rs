@@ -0,0 +1,70 @@
+use irohatype::engine::game::WordEntry;
+use irohatype::engine::latency::{self, MoraKind};
+use irohatype::store::json::{KeyEv, ScoreRecord, SplitRec};
+
+fn seg(jp: &str, typed: &str) -> Option<Vec<(String, usize)>> { latency::segment(jp, typed) }
+fn pairs(v: &[(&str, usize)]) -> Option<Vec<(String, usize)>> { Some(v.iter().map(|(m, n)| (m.to_string(), *n)).collect()) }
+
+#[test]
+fn keys_are_split_into_morae() {
+    assert_eq!(seg("しゃしん", "shasinn"), pairs(&[("しゃ", 3), ("し", 2), ("ん", 2)]));
+    assert_eq!(seg("がっこう", "gakkou"), pairs(&[("が", 2), ("っ", 1), ("こ", 2), ("う", 1)]));
+    assert_eq!(seg("コーヒー", "ko-hi-"), pairs(&[("こ", 2), ("ー", 1), ("ひ", 2), ("ー", 1)]));
+    // single n before a consonant, nn before a vowel
+    assert_eq!(seg("こんにちは", "konnnitiha"), pairs(&[("こ", 2), ("ん", 2), ("に", 2), ("ち", 2), ("は", 2)]));
+    assert_eq!(seg("さんぽ", "sanpo"), pairs(&[("さ", 2), ("ん", 1), ("ぽ", 2)]));
+    assert_eq!(seg("きょう", "kyou"), pairs(&[("きょ", 3), ("う", 1)]));
+    assert_eq!(seg("ちゃ", "cya"), pairs(&[("ちゃ", 3)]));
+    // not the word / kanji
+    assert_eq!(seg("しゃしん", "shashi"), None);
+    assert_eq!(seg("写真", "shashin"), None);
+}
+
+#[test]
+fn mora_kinds() {
+    assert_eq!(MoraKind::of("っ"), MoraKind::Sokuon);
+    assert_eq!(MoraKind::of("ん"), MoraKind::Hatsuon);
+    assert_eq!(MoraKind::of("きょ"), MoraKind::Youon);
+    assert_eq!(MoraKind::of("ぱ"), MoraKind::Voiced);
+    assert_eq!(MoraKind::of("か"), MoraKind::Plain);
+    assert_eq!(MoraKind::of("ー"), MoraKind::Chouon);
+}
+
+fn ev(t: f64, c: &str, ok: bool, w: usize) -> KeyEv { KeyEv { t, c: c.into(), ok, w } }
+
+fn record() -> ScoreRecord {
+    // きって: k(0.5) i(0.6) t(1.4, hesitation) t(1.5) e(1.6); いぬ: i(2.3) x(2.9 miss, +0.2 penalty) n(3.0) u(3.1)
+    let replay = vec![ev(0.5, "k", true, 0), ev(0.6, "i", true, 0), ev(1.4, "t", true, 0), ev(1.5, "t", true, 0), ev(1.6, "e", true, 0),
+                      ev(2.3, "i", true, 1), ev(2.9, "x", false, 1), ev(3.0, "n", true, 1), ev(3.1, "u", true, 1)];
+    ScoreRecord {
+        time_sec: 3.1, miss: 1, timeloss_sec: 0.2,
+        splits: vec![SplitRec { word: "きって".into(), sec: 1.6, miss: 0 }, SplitRec { word: "いぬ".into(), sec: 1.5, miss: 1 }],
+        word_seq: vec![WordEntry { jp: "きって".into(), romas: vec!["kitte".into()], ..Default::default() }, WordEntry { jp: "いぬ".into(), romas: vec!["inu".into()], ..Default::default() }],
+        replay: Some(replay),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn analysis_attributes_time_to_morae() {
+    let a = latency::analyze(&record(), 0.45).unwrap();
+    assert_eq!(a.first_key.len(), 2);
+    assert!((a.first_key[0] - 0.5).abs() < 1e-9 && (a.first_key[1] - 0.7).abs() < 1e-9);
+    assert_eq!(a.unsegmented, 0);
+    let got: Vec<(&str, f64)> = a.moras.iter().map(|m| (m.mora.as_str(), (m.sec * 1000.0).round() / 1000.0)).collect();
+    // い (one key, first mora of いぬ) has no time of its own
+    assert_eq!(got, vec![("き", 0.1), ("っ", 0.8), ("て", 0.2), ("ぬ", 0.8)]);
+    assert_eq!(a.by_kind()[0].kind, MoraKind::Sokuon);
+    // the pause before the miss is 0.6s minus the 0.2s penalty
+    let h: Vec<(usize, &str, f64)> = a.hesitations.iter().map(|h| (h.ev, h.mora.as_str(), (h.gap * 1000.0).round() / 1000.0)).collect();
+    assert_eq!(h, vec![(2, "っ", 0.8)]);
+    assert!(a.is_hesitation(2) && !a.is_hesitation(6));
+    assert_eq!(latency::analyze(&record(), 0.3).unwrap().hesitations.len(), 2);
+}
+
+#[test]
+fn records_without_replay_are_not_analysed() {
+    let mut r = record();
+    r.replay = None;
+    assert!(latency::analyze(&r, 0.4).is_none());
+}
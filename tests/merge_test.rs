@@ -0,0 +1,82 @@
+use std::fs;
+
+use irohatype::store::json::{ScoreBook, ScoreRecord, SplitRec};
+use irohatype::store::memory::MemoryStore;
+use irohatype::store::merge::{merge_into, read_source, sync_file, sync_folder};
+use irohatype::store::ScoreStore;
+
+fn rec(id: &str, dt: &str, t: f64) -> ScoreRecord {
+    ScoreRecord {
+        id: id.into(), mode: "basic_common".into(), datetime: dt.into(), time_sec: t,
+        splits: vec![SplitRec { word: "あ".into(), sec: t / 10.0, miss: 0 }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn merge_dedupes_and_reports_conflicts() {
+    let mut local = MemoryStore::default();
+    local.insert_and_rank(rec("a", "2026-10-01T10:00:00", 30.0)).unwrap();
+    local.insert_and_rank(rec("b", "2026-10-03T10:00:00", 20.0)).unwrap();
+
+    let mut with_memo = rec("a", "2026-10-01T10:00:00", 30.0);
+    with_memo.memo = Some("ノートPC".into());
+    let incoming = vec![
+        with_memo,                                   // duplicate by id, memo adopted
+        rec("other-id", "2026-10-03T10:00:00", 20.0), // duplicate by mode + datetime
+        rec("b", "2026-10-03T10:00:00", 25.0),        // same id, different time: conflict
+        rec("c", "2026-10-02T10:00:00", 10.0),        // new
+        rec("c2", "2026-10-02T10:00:00", 10.0),       // repeated within the source
+    ];
+    let r = merge_into(&mut local, incoming).unwrap();
+    assert_eq!((r.added, r.duplicates, r.memos), (1, 3, 1));
+    assert_eq!(r.conflicts.len(), 1);
+    assert_eq!(r.conflicts[0].id, "b");
+    assert_eq!(r.conflicts[0].fields, vec!["time_sec"]);
+
+    // history stays ordered and the views are recomputed
+    let hist: Vec<String> = local.history().unwrap().into_iter().map(|r| r.id).collect();
+    assert_eq!(hist, vec!["a", "c", "b"]);
+    assert_eq!(local.top("basic_common", 1).unwrap()[0].id, "c");
+    assert_eq!(local.lap_top(1).unwrap()[0].time_sec, 1.0);
+    assert_eq!(local.get("a").unwrap().unwrap().memo.as_deref(), Some("ノートPC"));
+
+    // merging the same data again changes nothing
+    let again = merge_into(&mut local, vec![rec("c", "2026-10-02T10:00:00", 10.0)]).unwrap();
+    assert_eq!((again.added, again.duplicates), (0, 1));
+}
+
+#[test]
+fn two_way_sync_through_folder() {
+    let dir = std::env::temp_dir().join(format!("irohatype-sync-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    let mut laptop = MemoryStore::default();
+    laptop.insert_and_rank(rec("l1", "2026-10-01T08:00:00", 40.0)).unwrap();
+    let mut desktop = MemoryStore::default();
+    desktop.insert_and_rank(rec("d1", "2026-10-01T20:00:00", 35.0)).unwrap();
+
+    assert_eq!(sync_folder(&mut laptop, &dir, "laptop").unwrap().added, 0);
+    assert_eq!(sync_folder(&mut desktop, &dir, "desktop").unwrap().added, 1);
+    assert_eq!(sync_folder(&mut laptop, &dir, "laptop").unwrap().added, 1);
+    assert_eq!(laptop.history().unwrap().len(), 2);
+    assert_eq!(desktop.history().unwrap().len(), 2);
+    assert_eq!(read_source(&sync_file(&dir, "laptop")).unwrap().len(), 2);
+    assert_eq!(ScoreBook::from_json(&fs::read_to_string(sync_file(&dir, "desktop")).unwrap()).unwrap().top.len(), 2);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_source_is_readable() {
+    let dir = std::env::temp_dir().join(format!("irohatype-merge-db-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let db = dir.join("scores.db");
+    let mut s = irohatype::store::sqlite::SqliteStore::open(&db).unwrap();
+    s.insert(&rec("x1", "2026-10-05T10:00:00", 12.0)).unwrap();
+    drop(s);
+    let recs = read_source(&db).unwrap();
+    assert_eq!(recs.len(), 1);
+    assert_eq!(recs[0].id, "x1");
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,71 @@
+use irohatype::engine::game::WordEntry;
+use irohatype::engine::misses::{self, MissKind};
+use irohatype::engine::romaji::RomajiRules;
+use irohatype::store::json::{KeyEv, ScoreRecord};
+use irohatype::util::keymap;
+
+fn rules() -> RomajiRules {
+    RomajiRules::from_yaml_file(std::path::Path::new("data/rules/romaji.yaml")).unwrap()
+}
+
+fn w(jp: &str, roma: &str) -> WordEntry { WordEntry { jp: jp.into(), romas: vec![roma.into()], ..Default::default() } }
+
+/// One-word record; keys prefixed with '!' are misses.
+fn typed(jp: &str, roma: &str, keys: &[&str]) -> ScoreRecord {
+    let replay = keys.iter().enumerate().map(|(i, k)| {
+        let (c, ok) = match k.strip_prefix('!') { Some(c) => (c, false), None => (*k, true) };
+        KeyEv { t: 0.1 * (i + 1) as f64, c: c.into(), ok, w: 0 }
+    }).collect();
+    ScoreRecord { mode: "basic_common".into(), word_seq: vec![w(jp, roma)], replay: Some(replay), ..Default::default() }
+}
+
+fn kinds(rec: &ScoreRecord) -> Vec<MissKind> { misses::classify(rec, &rules()).unwrap().iter().map(|m| m.kind).collect() }
+
+#[test]
+fn keyboard_neighbours() {
+    assert!(keymap::adjacent('i', 'o'));
+    assert!(keymap::adjacent('j', 'u') && keymap::adjacent('j', 'i'));
+    assert!(keymap::adjacent('n', 'h') && keymap::adjacent('n', 'j'));
+    assert!(!keymap::adjacent('n', 'k'));
+    assert!(!keymap::adjacent('q', 'p'));
+}
+
+#[test]
+fn misses_are_classified() {
+    assert_eq!(kinds(&typed("あい", "ai", &["a", "!a", "i"])), vec![MissKind::Doubled]);
+    assert_eq!(kinds(&typed("しか", "shika", &["s", "h", "!k", "i", "k", "a"])), vec![MissKind::Transposition]);
+    assert_eq!(kinds(&typed("たかい", "takai", &["t", "a", "!i", "k", "a", "i"])), vec![MissKind::EarlyFinger]);
+    assert_eq!(kinds(&typed("あい", "ai", &["a", "!o", "i"])), vec![MissKind::Adjacent]);
+    assert_eq!(kinds(&typed("あい", "ai", &["a", "!z", "i"])), vec![MissKind::Other]);
+    // ん before a vowel needs nn: a single n is a spelling the rules do not take
+    assert_eq!(kinds(&typed("かんい", "kanni", &["k", "a", "n", "!i", "n", "i"])), vec![MissKind::Variant]);
+    let m = &misses::classify(&typed("あい", "ai", &["a", "!o", "i"]), &rules()).unwrap()[0];
+    assert_eq!((m.ev, m.expected, m.typed, m.jp.as_str()), (1, Some('i'), 'o', "あい"));
+}
+
+#[test]
+fn unplayable_replays_are_not_classified() {
+    // the stored judgement disagrees with the rules
+    assert!(misses::classify(&typed("あい", "ai", &["a", "i", "!x"]), &rules()).is_none());
+    let mut rec = typed("あい", "ai", &["a", "i"]);
+    rec.rules_version = Some("0000000000000000".into());
+    assert!(misses::classify(&rec, &rules()).is_none());
+    rec.replay = None;
+    assert!(misses::classify(&rec, &rules()).is_none());
+}
+
+#[test]
+fn patterns_are_aggregated_across_history() {
+    let history = vec![
+        typed("あい", "ai", &["a", "!o", "i"]),
+        typed("いい", "ii", &["i", "!o", "i"]),
+        typed("あい", "ai", &["a", "!a", "i"]),
+        ScoreRecord { mode: "long_text".into(), ..typed("あい", "ai", &["a", "!o", "i"]) },
+    ];
+    let r = misses::aggregate(&history, "basic_common", &rules());
+    assert_eq!((r.misses, r.sessions), (3, 3));
+    assert_eq!(r.by_kind, vec![(MissKind::Adjacent, 2), (MissKind::Doubled, 1)]);
+    let top = &r.patterns[0];
+    assert_eq!((top.kind, top.expected, top.typed, top.count), (MissKind::Adjacent, Some('i'), 'o', 2));
+    assert_eq!(top.examples, vec!["あい".to_string(), "いい".to_string()]);
+}
@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::PathBuf;
+
+use irohatype::util::config::AppConfig;
+use irohatype::util::paths::{migrate_legacy, Dirs, ENV_DATA_DIR, MIGRATED_MARKER};
+
+fn tmp(name: &str) -> PathBuf {
+    let d = std::env::temp_dir().join(format!("irohatype-paths-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&d);
+    fs::create_dir_all(&d).unwrap();
+    d
+}
+
+#[test]
+fn override_order() {
+    let d = Dirs::resolve(Some("/x/y".into()));
+    assert_eq!((d.config.as_path(), d.data.as_path(), d.overridden), (std::path::Path::new("/x/y"), std::path::Path::new("/x/y"), true));
+    std::env::set_var(ENV_DATA_DIR, "/from/env");
+    assert_eq!(Dirs::resolve(None).data, PathBuf::from("/from/env"));
+    // the flag wins over the environment
+    assert_eq!(Dirs::resolve(Some("/cli".into())).data, PathBuf::from("/cli"));
+    std::env::remove_var(ENV_DATA_DIR);
+    assert!(!Dirs::resolve(None).overridden);
+}
+
+#[test]
+fn legacy_folder_is_copied_once() {
+    let root = tmp("legacy");
+    let legacy = root.join("data");
+    fs::create_dir_all(legacy.join("words")).unwrap();
+    let cfg = AppConfig { dictionary: Some(legacy.join("words/mine.json").to_string_lossy().into_owned()), ..Default::default() };
+    fs::write(legacy.join("config.json"), serde_json::to_string(&cfg).unwrap()).unwrap();
+    fs::write(legacy.join("scores.json"), "{}").unwrap();
+    fs::write(legacy.join("words/mine.json"), "{}").unwrap();
+    fs::write(legacy.join("words/basic_common.json"), "{}").unwrap();
+
+    let dirs = Dirs { config: root.join("cfg"), data: root.join("share"), overridden: false };
+    let copied = migrate_legacy(&legacy, &dirs).unwrap();
+    assert_eq!(copied.len(), 3);
+    assert!(dirs.data.join("scores.json").exists());
+    assert!(dirs.data.join("words/mine.json").exists());
+    assert!(!dirs.data.join("words/basic_common.json").exists());
+    let moved: AppConfig = serde_json::from_str(&fs::read_to_string(dirs.config.join("config.json")).unwrap()).unwrap();
+    assert_eq!(moved.dictionary.map(PathBuf::from), Some(dirs.data.join("words/mine.json")));
+
+    // second run copies nothing and keeps newer files
+    fs::write(dirs.data.join("scores.json"), "{\"history\":[]}").unwrap();
+    assert!(migrate_legacy(&legacy, &dirs).unwrap().is_empty());
+    assert_eq!(fs::read_to_string(dirs.data.join("scores.json")).unwrap(), "{\"history\":[]}");
+    assert!(dirs.data.join(MIGRATED_MARKER).exists());
+    // later starts do nothing, even when files were deleted or added since
+    fs::remove_file(dirs.data.join("scores.json")).unwrap();
+    fs::remove_file(dirs.data.join("words/mine.json")).unwrap();
+    fs::write(legacy.join("words/new.json"), "{}").unwrap();
+    assert!(migrate_legacy(&legacy, &dirs).unwrap().is_empty());
+    assert!(!dirs.data.join("scores.json").exists());
+    assert!(!dirs.data.join("words/mine.json").exists());
+    assert!(!dirs.data.join("words/new.json").exists());
+    fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn legacy_folder_is_not_merged_into_an_existing_install() {
+    let root = tmp("existing");
+    let legacy = root.join("data");
+    fs::create_dir_all(legacy.join("words")).unwrap();
+    fs::write(legacy.join("scores.json"), "{}").unwrap();
+    fs::write(legacy.join("words/mine.json"), "{}").unwrap();
+    let dirs = Dirs { config: root.join("cfg"), data: root.join("share"), overridden: false };
+    fs::create_dir_all(&dirs.config).unwrap();
+    fs::write(dirs.config.join("config.json"), "{}").unwrap();
+    assert!(migrate_legacy(&legacy, &dirs).unwrap().is_empty());
+    assert!(!dirs.data.join("scores.json").exists());
+    assert!(dirs.data.join(MIGRATED_MARKER).exists());
+    fs::remove_dir_all(&root).ok();
+}
@@ -0,0 +1,85 @@
+use std::fs;
+use std::path::PathBuf;
+
+use irohatype::store::json::{ScoreBook, SCOREBOOK_VERSION};
+use irohatype::util::config::AppConfig;
+use irohatype::util::persist::{backup_path, backups, check, migrate, restore, save_with_backups, save_with_backups_every, NewerVersion, BACKUP_INTERVAL, KEEP_BACKUPS};
+
+fn tmp(name: &str) -> PathBuf {
+    let d = std::env::temp_dir().join(format!("irohatype-persist-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&d);
+    fs::create_dir_all(&d).unwrap();
+    d
+}
+
+#[test]
+fn backups_rotate() {
+    let dir = tmp("rotate");
+    let p = dir.join("scores.json");
+    for i in 0..5 { save_with_backups(&p, format!("{}", i).as_bytes()).unwrap(); }
+    assert_eq!(fs::read_to_string(&p).unwrap(), "4");
+    assert_eq!(backups(&p).len(), KEEP_BACKUPS);
+    assert_eq!(fs::read_to_string(backup_path(&p, 1)).unwrap(), "3");
+    assert_eq!(fs::read_to_string(backup_path(&p, 3)).unwrap(), "1");
+    assert!(!dir.join("scores.json.tmp").exists());
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn frequent_saves_rotate_once_per_interval() {
+    let dir = tmp("interval");
+    let p = dir.join("config.json");
+    for i in 0..5 { save_with_backups_every(&p, format!("{}", i).as_bytes(), BACKUP_INTERVAL).unwrap(); }
+    // only the first save with a previous file made a backup
+    assert_eq!(fs::read_to_string(&p).unwrap(), "4");
+    assert_eq!(backups(&p), vec![backup_path(&p, 1)]);
+    assert_eq!(fs::read_to_string(backup_path(&p, 1)).unwrap(), "0");
+    // once bak1 is old enough the next save rotates
+    let old = std::time::SystemTime::now() - BACKUP_INTERVAL * 2;
+    fs::File::options().write(true).open(backup_path(&p, 1)).unwrap().set_modified(old).unwrap();
+    save_with_backups_every(&p, b"5", BACKUP_INTERVAL).unwrap();
+    assert_eq!(fs::read_to_string(backup_path(&p, 1)).unwrap(), "4");
+    assert_eq!(fs::read_to_string(backup_path(&p, 2)).unwrap(), "0");
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn versions_migrate_and_newer_is_refused() {
+    fn add_x(v: &mut serde_json::Value) { v["x"] = 1.into(); }
+    let v = migrate(serde_json::json!({}), 2, &[add_x]).unwrap();
+    assert_eq!((v["version"].as_u64(), v["x"].as_u64()), (Some(2), Some(1)));
+    // already current: no steps run
+    let v = migrate(serde_json::json!({"version": 2}), 2, &[add_x]).unwrap();
+    assert!(v.get("x").is_none());
+    let e = migrate(serde_json::json!({"version": 9}), 2, &[add_x]).unwrap_err();
+    assert!(e.is::<NewerVersion>());
+
+    let book = ScoreBook::from_json(r#"{"top":[],"lap":[]}"#).unwrap();
+    assert_eq!(book.version, SCOREBOOK_VERSION);
+    let cfg = AppConfig::from_json(r#"{"loss_ms_per_miss":100,"theme":"x"}"#).unwrap();
+    assert_eq!(cfg.version, 1);
+}
+
+#[test]
+fn corrupted_file_is_moved_aside_and_restorable() {
+    let dir = tmp("corrupt");
+    let p = dir.join("scores.json");
+    let parse = |s: &str| ScoreBook::from_json(s).map(|_| ());
+    save_with_backups(&p, serde_json::to_string(&ScoreBook::default()).unwrap().as_bytes()).unwrap();
+    save_with_backups(&p, b"{ broken").unwrap(); // bak1 = good, current = broken
+    save_with_backups(&p, b"{ broken").unwrap(); // bak1 = broken, bak2 = good
+
+    let rec = check("記録", &p, parse).unwrap().expect("corruption detected");
+    assert!(!p.exists());
+    assert_eq!(fs::read_to_string(&rec.moved_to).unwrap(), "{ broken");
+    // only backups that parse are offered
+    assert_eq!(rec.backups, vec![backup_path(&p, 2)]);
+    restore(&rec, &rec.backups[0]).unwrap();
+    assert!(check("記録", &p, parse).unwrap().is_none());
+
+    // a file from a newer version is an error, not corruption
+    fs::write(&p, r#"{"version": 99, "history": []}"#).unwrap();
+    assert!(check("記録", &p, parse).is_err());
+    assert!(p.exists());
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,56 @@
+use std::fs;
+
+use irohatype::store::json::ScoreRecord;
+use irohatype::store;
+use irohatype::util::config::AppConfig;
+use irohatype::util::paths;
+
+fn rec(dt: &str, t: f64) -> ScoreRecord {
+    ScoreRecord { mode: "basic_common".into(), datetime: dt.into(), time_sec: t, ..Default::default() }
+}
+
+// Paths and the active profile are process-wide, so everything runs in one test.
+#[test]
+fn profiles_have_their_own_files_and_share_a_leaderboard() {
+    let dir = std::env::temp_dir().join(format!("irohatype-profiles-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    paths::init(Some(dir.clone()));
+    assert_eq!(paths::profile(), "default");
+    assert_eq!(paths::config_file(), dir.join("config.json"));
+
+    let mut s = store::open("json").unwrap();
+    s.insert_and_rank(rec("2026-10-01T09:00:00", 30.0)).unwrap();
+    s.save().unwrap();
+
+    paths::create_profile("佐藤").unwrap();
+    assert!(paths::create_profile("佐藤").is_err());
+    assert!(paths::create_profile("../x").is_err());
+    assert!(paths::create_profile("").is_err());
+    assert_eq!(paths::profiles(), vec!["default".to_string(), "佐藤".to_string()]);
+
+    paths::set_profile("佐藤");
+    assert_eq!(paths::scores_json(), dir.join("profiles/佐藤/scores.json"));
+    let cfg = AppConfig { target_chars: 123, ..Default::default() };
+    cfg.save().unwrap();
+    let mut s = store::open("json").unwrap();
+    assert!(s.history().unwrap().is_empty());
+    s.insert_and_rank(rec("2026-10-01T10:00:00", 25.0)).unwrap();
+    s.insert_and_rank(rec("2026-10-01T11:00:00", 28.0)).unwrap();
+    s.save().unwrap();
+    paths::save_active_profile().unwrap();
+
+    // configs stay separate
+    paths::set_profile("default");
+    assert_eq!(AppConfig::load_or_default().unwrap().target_chars, AppConfig::default().target_chars);
+
+    let board = store::office_leaderboard("basic_common");
+    let names: Vec<(&str, f64)> = board.iter().map(|(p, r)| (p.as_str(), r.time_sec)).collect();
+    assert_eq!(names, vec![("佐藤", 25.0), ("default", 30.0)]);
+    assert!(store::office_leaderboard("long_text").is_empty());
+
+    // the last used profile comes back on the next start
+    paths::restore_active_profile();
+    assert_eq!(paths::profile(), "佐藤");
+    assert_eq!(AppConfig::load_or_default().unwrap().target_chars, 123);
+    fs::remove_dir_all(&dir).ok();
+}
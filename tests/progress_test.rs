@@ -0,0 +1,51 @@
+use chrono::NaiveDate;
+use irohatype::engine::progress;
+use irohatype::store::json::{KeyEv, ScoreRecord};
+
+fn rec(datetime: &str, mode: &str, time_sec: f64, rank: &str, keys: usize, miss: usize, aborted: bool) -> ScoreRecord {
+    let replay = (0..keys + miss).map(|i| KeyEv { t: i as f64 * 0.1, c: "a".into(), ok: i >= miss, w: 0 }).collect();
+    ScoreRecord { datetime: datetime.into(), mode: mode.into(), time_sec, rank: rank.into(), miss: miss as u32, replay: Some(replay), aborted, ..Default::default() }
+}
+
+fn day(s: &str) -> NaiveDate { NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap() }
+
+#[test]
+fn days_aggregate_best_median_and_rates() {
+    let hist = vec![
+        rec("2026-03-01T21:00:00+09:00", "basic", 30.0, "B", 120, 0, false),
+        rec("2026-03-01T21:10:00+09:00", "basic", 20.0, "A", 120, 6, false),
+        rec("2026-03-01T21:20:00+09:00", "basic", 25.0, "A", 120, 6, false),
+        rec("2026-03-01T21:30:00+09:00", "basic", 5.0, "F", 10, 0, true),
+        rec("2026-03-03T08:00:00+09:00", "basic", 18.0, "A", 120, 0, false),
+        rec("2026-03-03T09:00:00+09:00", "long", 90.0, "C", 300, 0, false),
+    ];
+    let p = progress::build(&hist, "basic");
+    assert_eq!(p.days.len(), 2);
+    let d = &p.days[0];
+    assert_eq!((d.date, d.sessions), (day("2026-03-01"), 4));
+    // aborted runs count as practice but not as results
+    assert_eq!((d.best, d.median), (20.0, 25.0));
+    assert!((d.kpm.unwrap() - 120.0 / 25.0 * 60.0).abs() < 1e-9);
+    assert!((d.miss_rate.unwrap() - 12.0 / 372.0).abs() < 1e-9);
+    assert_eq!(p.days[1].median, 18.0);
+    assert_eq!(p.sessions(), 5);
+    assert_eq!(p.completed(), 4);
+    let count = |r: &str| p.ranks.iter().find(|x| x.0 == r).unwrap().1;
+    assert_eq!((count("A"), count("B"), count("F"), count("C")), (3, 1, 0, 0));
+    // the calendar counts every mode
+    assert_eq!(p.activity.get(&day("2026-03-03")), Some(&2));
+}
+
+#[test]
+fn heatmap_ends_with_the_current_week() {
+    let hist = vec![rec("2026-03-02T10:00:00+09:00", "basic", 20.0, "A", 10, 0, false), rec("2026-03-04T10:00:00+09:00", "basic", 20.0, "A", 10, 0, false)];
+    let p = progress::build(&hist, "basic");
+    // Wednesday 2026-03-04
+    let grid = progress::heatmap(&p.activity, day("2026-03-04"), 2);
+    assert_eq!(grid.len(), 2);
+    assert_eq!(grid[1][0], Some(1));
+    assert_eq!(grid[1][1], Some(0));
+    assert_eq!(grid[1][2], Some(1));
+    assert_eq!(grid[1][3], None);
+    assert_eq!(grid[0].iter().flatten().sum::<usize>(), 0);
+}
@@ -0,0 +1,53 @@
+use irohatype::store::json::{ScoreBook, ScoreRecord};
+use irohatype::store::memory::MemoryStore;
+use irohatype::store::ScoreStore;
+
+fn rec(dt: &str, t: f64) -> ScoreRecord {
+    ScoreRecord { mode: "basic_common".into(), datetime: dt.into(), time_sec: t, ..Default::default() }
+}
+
+// Pinned runs survive Top-100 truncation; memo edit, delete and undo (re-import) round-trip
+fn exercise(store: &mut dyn ScoreStore) {
+    store.insert_and_rank(rec("2026-09-01T00:00:00", 500.0)).unwrap();
+    let slow = store.top("basic_common", 1).unwrap()[0].id.clone();
+    assert!(store.set_pinned(&slow, true).unwrap());
+    assert!(!store.set_pinned("no-such-id", true).unwrap());
+    for i in 0..100 { store.insert_and_rank(rec(&format!("2026-10-01T00:{:02}:{:02}", i / 60, i % 60), 10.0 + i as f64)).unwrap(); }
+    let top = store.top("basic_common", 100).unwrap();
+    assert_eq!(top.len(), 101);
+    assert!(top.iter().any(|r| r.id == slow && r.pinned));
+    assert!(store.set_pinned(&slow, false).unwrap());
+    assert!(!store.top("basic_common", 100).unwrap().iter().any(|r| r.id == slow));
+
+    let first = store.top("basic_common", 1).unwrap()[0].id.clone();
+    assert!(store.set_memo(&first, "調子よし".into()).unwrap());
+    assert_eq!(store.get(&first).unwrap().unwrap().memo.as_deref(), Some("調子よし"));
+    assert!(store.set_memo(&first, String::new()).unwrap());
+    assert_eq!(store.get(&first).unwrap().unwrap().memo, None);
+
+    let backup = store.get(&first).unwrap().unwrap();
+    assert!(store.delete(&first).unwrap());
+    assert_ne!(store.top("basic_common", 1).unwrap()[0].id, first);
+    assert_eq!(store.import(vec![backup]).unwrap(), 1);
+    assert_eq!(store.top("basic_common", 1).unwrap()[0].id, first);
+}
+
+#[test]
+fn memory_record_management() { exercise(&mut MemoryStore::default()); }
+
+#[test]
+fn json_record_management() { exercise(&mut ScoreBook::default()); }
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_record_management() { exercise(&mut irohatype::store::sqlite::SqliteStore::open_in_memory().unwrap()); }
+
+#[test]
+fn pinned_flag_survives_json_round_trip() {
+    let mut book = ScoreBook::default();
+    book.insert_and_rank(rec("2026-10-01T00:00:00", 30.0));
+    let id = book.history[0].id.clone();
+    book.set_pinned(&id, true);
+    let json = serde_json::to_string(&book).unwrap();
+    assert!(ScoreBook::from_json(&json).unwrap().history[0].pinned);
+}
@@ -0,0 +1,72 @@
+use irohatype::app::ReplayState;
+use irohatype::store::json::KeyEv;
+use irohatype::store::replay;
+use irohatype::util::config::AppConfig;
+
+fn ev(t: f64, c: &str, ok: bool, w: usize) -> KeyEv { KeyEv { t, c: c.into(), ok, w } }
+
+/// "ka" "si" "ta" with a miss in the second and third word
+fn sample() -> Vec<KeyEv> {
+    vec![ev(0.3, "k", true, 0), ev(0.5, "a", true, 0), ev(0.9, "s", true, 1), ev(1.1, "h", false, 1), ev(1.4, "i", true, 1),
+         ev(2.0, "t", true, 2), ev(2.2, "r", false, 2), ev(2.5, "a", true, 2)]
+}
+
+#[test]
+fn word_jumps_land_on_first_keystrokes() {
+    let evs = sample();
+    assert_eq!(replay::word_starts(&evs), vec![0, 2, 5]);
+    assert_eq!(replay::next_word(&evs, 0), Some(2));
+    assert_eq!(replay::next_word(&evs, 3), Some(5));
+    assert_eq!(replay::next_word(&evs, 6), None);
+    // inside a word: back to its start; on its start: the previous word
+    assert_eq!(replay::prev_word(&evs, 4), Some(2));
+    assert_eq!(replay::prev_word(&evs, 2), Some(0));
+    assert_eq!(replay::prev_word(&evs, 0), None);
+}
+
+#[test]
+fn miss_jumps_skip_correct_keys() {
+    let evs = sample();
+    assert_eq!(replay::next_miss(&evs, 0), Some(3));
+    assert_eq!(replay::next_miss(&evs, 3), Some(6));
+    assert_eq!(replay::next_miss(&evs, 6), None);
+    assert_eq!(replay::prev_miss(&evs, 7), Some(6));
+    assert_eq!(replay::prev_miss(&evs, 3), None);
+    assert_eq!(replay::prev_miss(&evs, 100), Some(6));
+}
+
+#[test]
+fn time_seek_and_typed_keys() {
+    let evs = sample();
+    assert_eq!(replay::event_at(&evs, 0.1), None);
+    assert_eq!(replay::event_at(&evs, 1.1), Some(3));
+    assert_eq!(replay::event_at(&evs, 99.0), Some(7));
+    let mut rep = ReplayState::default();
+    rep.seek_time(&evs, 1.2);
+    assert_eq!((rep.ev_idx, rep.time, rep.playing), (3, 1.2, false));
+    rep.seek_time(&evs, 50.0);
+    assert_eq!((rep.ev_idx, rep.time), (7, 2.5));
+    rep.seek_event(&evs, usize::MAX);
+    assert_eq!(rep.ev_idx, 7);
+    // wrong keys are kept, later words are not typed yet
+    assert_eq!(replay::typed_keys(&evs, 4, 1), vec![("s", true), ("h", false), ("i", true)]);
+    assert_eq!(replay::typed_keys(&evs, 3, 1), vec![("s", true), ("h", false)]);
+    assert!(replay::typed_keys(&evs, 4, 2).is_empty());
+}
+
+#[test]
+fn parse_time_accepts_seconds_and_minutes() {
+    assert_eq!(replay::parse_time("12.5"), Some(12.5));
+    assert_eq!(replay::parse_time(" 1:05 "), Some(65.0));
+    assert_eq!(replay::parse_time("0:59.5"), Some(59.5));
+    assert_eq!(replay::parse_time("1:75"), None);
+    assert_eq!(replay::parse_time("-3"), None);
+    assert_eq!(replay::parse_time("abc"), None);
+}
+
+#[test]
+fn looping_defaults_on_for_old_configs() {
+    let cfg = AppConfig::from_json(r#"{"loss_ms_per_miss":200,"theme":"default"}"#).unwrap();
+    assert!(cfg.replay_loop);
+    assert!(AppConfig::default().replay_loop);
+}
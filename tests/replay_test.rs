@@ -0,0 +1,121 @@
+use irohatype::store::json::{KeyEv, ScoreBook, ScoreRecord, SplitRec};
+use irohatype::store::replay::{self, ReplayFile};
+
+fn ev(t: f64, c: &str, ok: bool, w: usize) -> KeyEv { KeyEv { t, c: c.into(), ok, w } }
+
+fn sample() -> Vec<KeyEv> {
+    vec![ev(0.412, "a", true, 0), ev(0.5004, "x", false, 0), ev(0.61, "i", true, 0), ev(1.2, "k", true, 1), ev(1.3, "あ", false, 1), ev(1.31, "a", true, 1), ev(9.0, "n", true, 3)]
+}
+
+#[test]
+fn events_round_trip_at_millisecond_precision() {
+    let evs = sample();
+    let back = replay::decode(&replay::encode(&evs)).unwrap();
+    assert_eq!(back.len(), evs.len());
+    for (a, b) in evs.iter().zip(&back) {
+        assert!((a.t - b.t).abs() <= 0.0005, "{} vs {}", a.t, b.t);
+        assert_eq!((&a.c, a.ok, a.w), (&b.c, b.ok, b.w));
+    }
+}
+
+#[test]
+fn encoding_is_much_smaller_than_json() {
+    let evs: Vec<KeyEv> = (0..500).map(|i| ev(i as f64 * 0.137, "k", i % 7 != 0, i / 5)).collect();
+    let bin = replay::encode(&evs).len();
+    let json = serde_json::to_string(&evs).unwrap().len();
+    assert!(bin * 8 < json, "binary {} vs json {}", bin, json);
+}
+
+#[test]
+fn truncated_or_unknown_data_is_an_error() {
+    let bin = replay::encode(&sample());
+    assert!(replay::decode(&bin[..bin.len() - 2]).is_err());
+    let mut bad = bin.clone();
+    bad[0] = 99;
+    assert!(replay::decode(&bad).is_err());
+}
+
+#[test]
+fn out_of_range_numbers_are_an_error() {
+    let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+    // two events whose time deltas add up past u64::MAX
+    let mut bad = vec![replay::EVENTS_VERSION, 2, 0];
+    bad.extend(max);
+    bad.extend([b'a', 0]);
+    bad.extend(max);
+    bad.push(b'a');
+    assert!(replay::decode(&bad).unwrap_err().to_string().contains("数値が不正"));
+    // word steps of +i64::MAX (zigzag u64::MAX - 1) twice
+    let mut step = max;
+    step[0] = 0xfe;
+    let mut bad = vec![replay::EVENTS_VERSION, 2, 2, 1];
+    bad.extend(step);
+    bad.extend([b'a', 2, 1]);
+    bad.extend(step);
+    bad.push(b'a');
+    assert!(replay::decode(&bad).unwrap_err().to_string().contains("数値が不正"));
+}
+
+#[test]
+fn records_store_replays_compactly_and_read_the_old_form() {
+    let rec = ScoreRecord { id: "r1".into(), mode: "basic_common".into(), datetime: "2026-10-01T10:00:00".into(), time_sec: 9.0, replay: Some(sample()), ..Default::default() };
+    let json = serde_json::to_value(&rec).unwrap();
+    assert!(json["replay"].is_string());
+    let back: ScoreRecord = serde_json::from_value(json).unwrap();
+    assert_eq!(back.replay.unwrap().len(), 7);
+
+    let legacy = r#"{"mode":"basic_common","datetime":"x","time_sec":1.0,"miss":0,"timeloss_sec":0.0,"splits":[],"wpm_top":0.0,"wpm_worst":0.0,"rank":"E",
+        "replay":[{"t":0.5,"c":"a","ok":true,"w":0}]}"#;
+    let old: ScoreRecord = serde_json::from_str(legacy).unwrap();
+    assert_eq!(old.replay.unwrap()[0].c, "a");
+}
+
+#[test]
+fn v2_scorebook_drops_the_tick_series_when_a_replay_exists() {
+    let v2 = r#"{"version":2,"history":[
+        {"id":"a","mode":"basic_common","datetime":"2026-10-01T10:00:00","time_sec":2.0,"miss":0,"timeloss_sec":0.0,"splits":[],"wpm_top":0.0,"wpm_worst":0.0,"rank":"E",
+         "speed_series":[[0.016,0.0],[0.032,0.0]],"replay":[{"t":0.5,"c":"a","ok":true,"w":0}]},
+        {"id":"b","mode":"basic_common","datetime":"2026-10-02T10:00:00","time_sec":2.0,"miss":0,"timeloss_sec":0.0,"splits":[],"wpm_top":0.0,"wpm_worst":0.0,"rank":"E",
+         "speed_series":[[0.016,0.0]]}]}"#;
+    let book = ScoreBook::from_json(v2).unwrap();
+    assert_eq!(book.version, irohatype::store::json::SCOREBOOK_VERSION);
+    assert!(book.history[0].speed_series.is_none());
+    assert!(book.history[1].speed_series.is_some());
+}
+
+#[test]
+fn speed_curve_is_rebuilt_from_events() {
+    let pts = replay::speed_series(&sample(), 2.0, 0.5);
+    assert_eq!(pts.len(), 4);
+    assert_eq!(pts[0], (0.5, 2.0));
+    assert_eq!(pts[3], (2.0, 4.0 / 2.0));
+}
+
+#[test]
+fn replay_file_carries_words_and_rules() {
+    let rules = "yure:\n  し: [\"shi\",\"si\"]\n";
+    let rec = ScoreRecord {
+        id: "20261001-100000-deadbeef".into(), mode: "basic_common".into(), datetime: "2026-10-01T10:00:00".into(), time_sec: 9.0,
+        splits: vec![SplitRec { word: "あい".into(), sec: 0.61, miss: 1 }],
+        word_seq: vec![irohatype::engine::game::WordEntry { jp: "あい".into(), romas: vec!["ai".into()], weight: None, tags: vec![], difficulty: None }],
+        rules_version: Some(replay::fingerprint(rules)),
+        replay: Some(sample()), speed_series: Some(vec![(0.1, 0.0)]), ..Default::default()
+    };
+    let dir = std::env::temp_dir().join(format!("irohatype-replay-{}", std::process::id()));
+    let path = dir.join(replay::file_name(&rec));
+    ReplayFile::from_record(&rec, rules).unwrap().write(&path).unwrap();
+    assert!(path.to_string_lossy().ends_with(".irohareplay"));
+
+    let file = ReplayFile::read(&path).unwrap();
+    assert_eq!(file.header.rules, rules);
+    assert_eq!(replay::fingerprint(&file.header.rules), file.header.record.rules_version.clone().unwrap());
+    let back = file.into_record();
+    assert_eq!(back.word_seq[0].romas, vec!["ai"]);
+    assert_eq!(back.replay.as_ref().unwrap().len(), 7);
+    assert!(back.speed_series.is_none());
+
+    // import path: a replay file is a one-record source
+    assert_eq!(irohatype::store::merge::read_source(&path).unwrap()[0].id, rec.id);
+    assert!(ReplayFile::from_bytes(b"not a replay").is_err());
+    std::fs::remove_dir_all(&dir).ok();
+}
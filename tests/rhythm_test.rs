@@ -0,0 +1,76 @@
+use irohatype::engine::stats::{self, RHYTHM_BINS};
+use irohatype::store::json::{KeyEv, ScoreBook, ScoreRecord};
+
+fn ev(t: f64, ok: bool) -> KeyEv { KeyEv { t, c: "a".into(), ok, w: 0 } }
+
+#[test]
+fn even_keystrokes_are_fully_consistent() {
+    let r = stats::rhythm(&[ev(0.1, true), ev(0.2, true), ev(0.3, true), ev(0.4, true)], 0.0).unwrap();
+    assert!((r.mean - 0.1).abs() < 1e-9);
+    assert!(r.std < 1e-9);
+    assert!((r.consistency - 100.0).abs() < 1e-6);
+    assert_eq!(r.histogram.len(), RHYTHM_BINS);
+    assert_eq!(r.histogram[2], 3);
+    assert!(stats::rhythm(&[ev(0.1, true)], 0.0).is_none());
+}
+
+#[test]
+fn uneven_keystrokes_and_histogram() {
+    // intervals 0.1, 0.1, 1.0: the long one lands in the last bin
+    let r = stats::rhythm(&[ev(0.0, true), ev(0.1, true), ev(0.2, true), ev(1.2, true)], 0.0).unwrap();
+    assert!((r.longest_pause - 1.0).abs() < 1e-9);
+    assert!(r.cv > 0.5 && r.consistency < 70.0);
+    assert_eq!(r.histogram[2], 2);
+    assert_eq!(r.histogram[RHYTHM_BINS - 1], 1);
+}
+
+#[test]
+fn miss_penalty_is_not_a_pause() {
+    // the miss at 0.5 includes a 0.2 penalty, so the player's gap is 0.1
+    let evs = [ev(0.1, true), ev(0.2, true), ev(0.5, false), ev(0.6, true)];
+    let rec = ScoreRecord { miss: 1, timeloss_sec: 0.2, replay: Some(evs.to_vec()), ..Default::default() };
+    let r = rec.compute_rhythm().unwrap();
+    assert!((r.longest_pause - 0.1).abs() < 1e-9);
+    assert!((r.consistency - 100.0).abs() < 1e-6);
+}
+
+#[test]
+fn v3_scorebook_gets_rhythm() {
+    let legacy = r#"{
+        "version": 3,
+        "history": [
+            {"mode":"basic_common","datetime":"2026-10-01T10:00:00+09:00","time_sec":0.3,"miss":0,"timeloss_sec":0.0,"splits":[],"wpm_top":0.0,"wpm_worst":0.0,"rank":"A",
+             "replay":[{"t":0.1,"c":"a","ok":true,"w":0},{"t":0.2,"c":"i","ok":true,"w":0},{"t":0.3,"c":"u","ok":true,"w":0}]},
+            {"mode":"basic_common","datetime":"2026-10-02T10:00:00+09:00","time_sec":20.0,"miss":0,"timeloss_sec":0.0,"splits":[],"wpm_top":0.0,"wpm_worst":0.0,"rank":"A"}
+        ]
+    }"#;
+    let book = ScoreBook::from_json(legacy).unwrap();
+    assert!((book.history[0].consistency().unwrap() - 100.0).abs() < 1e-6);
+    assert!(book.history[1].rhythm.is_none());
+    // stored with the record, so it survives a round trip
+    let back = ScoreBook::from_json(&serde_json::to_string(&book).unwrap()).unwrap();
+    let (a, b) = (back.history[0].rhythm.as_ref().unwrap(), book.history[0].rhythm.as_ref().unwrap());
+    assert!((a.consistency - b.consistency).abs() < 1e-9);
+    assert_eq!(a.histogram, b.histogram);
+}
+
+#[test]
+fn consistency_ranking() {
+    let rec = |dt: &str, time: f64, evs: &[KeyEv], aborted: bool| {
+        let mut r = ScoreRecord { mode: "basic_common".into(), datetime: dt.into(), time_sec: time, aborted, replay: Some(evs.to_vec()), ..Default::default() };
+        r.rhythm = r.compute_rhythm();
+        r
+    };
+    let even = [ev(0.1, true), ev(0.2, true), ev(0.3, true)];
+    let uneven = [ev(0.1, true), ev(0.2, true), ev(1.0, true)];
+    let history = vec![
+        rec("2026-10-01T10:00:00+09:00", 10.0, &uneven, false),
+        rec("2026-10-02T10:00:00+09:00", 30.0, &even, false),
+        rec("2026-10-03T10:00:00+09:00", 20.0, &even, false),
+        rec("2026-10-04T10:00:00+09:00", 5.0, &even, true),
+        ScoreRecord { mode: "basic_common".into(), datetime: "2026-10-05T10:00:00+09:00".into(), time_sec: 1.0, ..Default::default() },
+    ];
+    let top = irohatype::store::top_by_consistency(history, "basic_common", 10);
+    let times: Vec<f64> = top.iter().map(|r| r.time_sec).collect();
+    assert_eq!(times, vec![20.0, 30.0, 10.0]);
+}
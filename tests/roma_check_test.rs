@@ -0,0 +1,33 @@
+use irohatype::engine::romaji::{check_roma, RomaCheck, RomajiRules};
+use irohatype::util::persist::write_atomic;
+
+fn rules() -> RomajiRules {
+    RomajiRules::from_yaml_file(std::path::Path::new("data/rules/romaji.yaml")).unwrap()
+}
+
+#[test]
+fn roma_matches_kana_reading() {
+    let r = rules();
+    assert_eq!(check_roma("がっこう", "gakkou", &r), RomaCheck::Ok);
+    assert_eq!(check_roma("しゃしん", "syasinn", &r), RomaCheck::Ok);
+    assert!(matches!(check_roma("がっこう", "gakou", &r), RomaCheck::Invalid(_)));
+    assert!(matches!(check_roma("がっこう", "gakkouu", &r), RomaCheck::Invalid(_)));
+    assert!(matches!(check_roma("がっこう", "gakko", &r), RomaCheck::Invalid(_)));
+    assert!(matches!(check_roma("あい", "AI", &r), RomaCheck::Invalid(_)));
+}
+
+#[test]
+fn kanji_cannot_be_verified() {
+    assert_eq!(check_roma("学校", "gakkou", &rules()), RomaCheck::Unverified);
+}
+
+#[test]
+fn atomic_write_replaces_file() {
+    let dir = std::env::temp_dir().join(format!("irohatype-persist-{}", std::process::id()));
+    let p = dir.join("words.json");
+    write_atomic(&p, b"one").unwrap();
+    write_atomic(&p, b"two").unwrap();
+    assert_eq!(std::fs::read_to_string(&p).unwrap(), "two");
+    assert!(!dir.join("words.json.tmp").exists());
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,60 @@
+use irohatype::store::json::{ScoreBook, ScoreRecord, SplitRec};
+use irohatype::store::memory::MemoryStore;
+use irohatype::store::ScoreStore;
+
+fn rec(mode: &str, dt: &str, t: f64) -> ScoreRecord {
+    ScoreRecord {
+        mode: mode.into(), datetime: dt.into(), time_sec: t,
+        splits: vec![SplitRec { word: format!("w{}", dt), sec: t / 10.0, miss: 0 }],
+        ..Default::default()
+    }
+}
+
+// The same contract must hold for every backend
+fn exercise(store: &mut dyn ScoreStore) {
+    assert_eq!(store.insert_and_rank(rec("basic_common", "2026-10-01T10:00:00", 30.0)).unwrap(), (Some(1), true));
+    assert_eq!(store.insert_and_rank(rec("basic_common", "2026-10-02T10:00:00", 35.0)).unwrap(), (Some(2), false));
+    assert_eq!(store.insert_and_rank(rec("basic_common", "2026-10-03T10:00:00", 25.0)).unwrap(), (Some(1), true));
+    // other modes rank independently
+    assert_eq!(store.insert_and_rank(rec("long_text", "2026-10-04T10:00:00", 90.0)).unwrap(), (Some(1), true));
+
+    let top: Vec<f64> = store.top("basic_common", 10).unwrap().iter().map(|r| r.time_sec).collect();
+    assert_eq!(top, vec![25.0, 30.0, 35.0]);
+    assert_eq!(store.top("basic_common", 2).unwrap().len(), 2);
+    assert_eq!(store.lap_top(1).unwrap()[0].time_sec, 2.5);
+
+    let slow = store.top("basic_common", 10).unwrap()[2].id.clone();
+    assert!(!slow.is_empty());
+    assert!(store.set_memo(&slow, "眠い".into()).unwrap());
+    assert!(!store.set_memo("no-such-id", "x".into()).unwrap());
+    assert_eq!(store.get(&slow).unwrap().unwrap().memo.as_deref(), Some("眠い"));
+
+    let fastest = store.top("basic_common", 1).unwrap()[0].id.clone();
+    assert!(store.delete(&fastest).unwrap());
+    assert!(!store.delete(&fastest).unwrap());
+    let hist: Vec<String> = store.history().unwrap().into_iter().map(|r| r.datetime).collect();
+    assert_eq!(hist, vec!["2026-10-01T10:00:00", "2026-10-02T10:00:00", "2026-10-04T10:00:00"]);
+
+    // aborted sessions stay in the history but are never ranked
+    let mut ab = rec("basic_common", "2026-10-05T10:00:00", 1.0);
+    ab.aborted = true;
+    assert_eq!(store.insert_and_rank(ab).unwrap(), (None, false));
+    assert_eq!(store.top("basic_common", 10).unwrap().len(), 2);
+    assert_eq!(store.history().unwrap().len(), 4);
+}
+
+#[test]
+fn memory_store_contract() { exercise(&mut MemoryStore::default()); }
+
+#[test]
+fn json_store_contract() { exercise(&mut ScoreBook::default()); }
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_store_contract() { exercise(&mut irohatype::store::sqlite::SqliteStore::open_in_memory().unwrap()); }
+
+#[test]
+fn open_by_name() {
+    assert_eq!(irohatype::store::open("memory").unwrap().backend(), "memory");
+    assert!(irohatype::store::open("nope").is_err());
+}
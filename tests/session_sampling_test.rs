@@ -0,0 +1,90 @@
+use irohatype::engine::game::{filter_words, Game, GameConfig, SessionOptions, WordEntry};
+use irohatype::engine::romaji::RomajiRules;
+
+fn rules() -> RomajiRules {
+    RomajiRules::from_yaml_file(std::path::Path::new("data/rules/romaji.yaml")).unwrap()
+}
+
+fn w(jp: &str, roma: &str, tags: &[&str], weight: Option<f64>) -> WordEntry {
+    WordEntry { jp: jp.into(), romas: vec![roma.into()], tags: tags.iter().map(|t| t.to_string()).collect(), weight, ..Default::default() }
+}
+
+fn pool() -> Vec<WordEntry> {
+    vec![
+        w("あい", "ai", &["short"], None),
+        w("いえ", "ie", &["short"], Some(3.0)),
+        w("うえ", "ue", &["short", "rare"], Some(0.0)),
+        w("かいしゃ", "kaisha", &["long"], None),
+        w("がっこう", "gakkou", &["long"], Some(2.0)),
+    ]
+}
+
+fn session(opts: SessionOptions, target: usize) -> Vec<WordEntry> {
+    let gc = GameConfig { fixed_chars: true, target_chars: target, max_words: usize::MAX, session: opts, ..Default::default() };
+    Game::new_with_rules(gc, pool(), rules()).unwrap().words
+}
+
+#[test]
+fn tag_and_length_filters() {
+    let opts = SessionOptions { include_tags: vec!["short".into()], exclude_tags: vec!["rare".into()], ..Default::default() };
+    let jp: Vec<String> = filter_words(&pool(), &opts).into_iter().map(|e| e.jp).collect();
+    assert_eq!(jp, vec!["あい", "いえ"]);
+    let opts = SessionOptions { min_keys: Some(3), max_keys: Some(6), ..Default::default() };
+    assert_eq!(filter_words(&pool(), &opts).len(), 2);
+}
+
+#[test]
+fn weighted_respects_zero_weight_and_window() {
+    let opts = SessionOptions { weighted: true, no_repeat_within: 2, ..Default::default() };
+    let words = session(opts, 300);
+    assert!(words.iter().map(|e| e.romas[0].len()).sum::<usize>() >= 300);
+    assert!(words.iter().all(|e| e.jp != "うえ"));
+    for win in words.windows(3) {
+        assert_ne!(win[0].jp, win[1].jp);
+        assert_ne!(win[0].jp, win[2].jp);
+        assert_ne!(win[1].jp, win[2].jp);
+    }
+}
+
+#[test]
+fn empty_filter_is_an_error() {
+    let opts = SessionOptions { include_tags: vec!["missing".into()], ..Default::default() };
+    let gc = GameConfig { fixed_chars: true, target_chars: 50, session: opts, ..Default::default() };
+    assert!(Game::new_with_rules(gc, pool(), rules()).is_err());
+}
+
+#[test]
+fn options_are_stored_in_record() {
+    let opts = SessionOptions { weighted: true, ..Default::default() };
+    let gc = GameConfig { fixed_chars: true, target_chars: 10, session: opts.clone(), ..Default::default() };
+    let g = Game::new_with_rules(gc, pool(), rules()).unwrap();
+    assert_eq!(g.finish_record().session, Some(opts));
+}
+
+#[test]
+fn options_apply_without_fixed_chars() {
+    let opts = SessionOptions { weighted: true, no_repeat_within: 1, ..Default::default() };
+    let free = |max_words: usize| {
+        let gc = GameConfig { fixed_chars: false, max_words, session: opts.clone(), ..Default::default() };
+        Game::new_with_rules(gc, pool(), rules()).unwrap().words
+    };
+    let mut count = std::collections::HashMap::new();
+    for _ in 0..200 {
+        let words = free(3);
+        assert_eq!(words.len(), 3);
+        assert!(words.iter().all(|e| e.jp != "うえ"));
+        assert!(words.windows(2).all(|p| p[0].jp != p[1].jp));
+        for e in words { *count.entry(e.jp).or_insert(0) += 1; }
+    }
+    // weight 3 against weight 1
+    assert!(count["いえ"] > count["あい"]);
+    // the whole-pool default cap draws one pool's worth
+    assert_eq!(free(usize::MAX).len(), pool().len());
+}
+
+#[test]
+fn long_text_keeps_its_order() {
+    let gc = GameConfig { fixed_chars: false, max_words: 5, mode: irohatype::engine::game::MODE_LONG_TEXT.into(), ..Default::default() };
+    let jp: Vec<String> = Game::new_with_rules(gc, pool(), rules()).unwrap().words.into_iter().map(|e| e.jp).collect();
+    assert_eq!(jp, pool().into_iter().map(|e| e.jp).collect::<Vec<_>>());
+}
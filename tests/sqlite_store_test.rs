@@ -0,0 +1,63 @@
+#![cfg(feature = "sqlite")]
+
+use irohatype::store::json::{KeyEv, ScoreBook, ScoreRecord, SplitRec};
+use irohatype::store::sqlite::SqliteStore;
+
+fn rec(mode: &str, dt: &str, time: f64) -> ScoreRecord {
+    ScoreRecord {
+        mode: mode.into(),
+        datetime: dt.into(),
+        time_sec: time,
+        rank: "A".into(),
+        splits: vec![SplitRec { word: "あい".into(), sec: time / 2.0, miss: 0 }, SplitRec { word: "いえ".into(), sec: time / 2.0, miss: 1 }],
+        replay: Some(vec![KeyEv { t: 0.1, c: "a".into(), ok: true, w: 0 }, KeyEv { t: 0.2, c: "x".into(), ok: false, w: 0 }]),
+        word_seq: vec![irohatype::engine::game::WordEntry { jp: "あい".into(), romas: vec!["ai".into()], weight: None, tags: vec![], difficulty: None }],
+        rules_version: Some("0123456789abcdef".into()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn migrations_and_roundtrip() {
+    let mut s = SqliteStore::open_in_memory().unwrap();
+    assert_eq!(s.schema_version().unwrap(), 7);
+    s.insert(&rec("basic_common", "2026-10-01T10:00:00+09:00", 30.0)).unwrap();
+    let r = s.load("2026-10-01T10:00:00+09:00").unwrap().unwrap();
+    assert_eq!(r.splits.len(), 2);
+    assert_eq!(r.replay.unwrap().len(), 2);
+    assert_eq!(r.word_seq.len(), 1);
+    assert_eq!(r.rules_version.as_deref(), Some("0123456789abcdef"));
+    // duplicates by datetime are ignored
+    s.insert(&rec("basic_common", "2026-10-01T10:00:00+09:00", 30.0)).unwrap();
+    assert_eq!(s.count().unwrap(), 1);
+}
+
+#[test]
+fn rankings_per_mode_and_history() {
+    let mut s = SqliteStore::open_in_memory().unwrap();
+    assert_eq!(s.insert_and_rank(&rec("basic_common", "2026-10-01T10:00:00+09:00", 30.0)).unwrap(), (Some(1), true));
+    assert_eq!(s.insert_and_rank(&rec("basic_common", "2026-10-02T10:00:00+09:00", 40.0)).unwrap(), (Some(2), false));
+    assert_eq!(s.insert_and_rank(&rec("long_text", "2026-10-03T10:00:00+09:00", 90.0)).unwrap(), (Some(1), true));
+    let top = s.top("basic_common", 10).unwrap();
+    assert_eq!(top.iter().map(|r| r.time_sec).collect::<Vec<_>>(), vec![30.0, 40.0]);
+    assert_eq!(s.lap_top(1).unwrap()[0].time_sec, 15.0);
+    assert_eq!(s.history(Some("2026-10-02"), Some("2026-10-02")).unwrap().len(), 1);
+    assert_eq!(s.history(None, None).unwrap().len(), 3);
+    assert!(s.set_memo_by_datetime("2026-10-02T10:00:00+09:00", "memo".into()).unwrap());
+    assert!(s.delete_by_datetime("2026-10-02T10:00:00+09:00").unwrap());
+    assert_eq!(s.count().unwrap(), 2);
+}
+
+#[test]
+fn json_migration_runs_once() {
+    let dir = std::env::temp_dir().join(format!("irohatype-sqlite-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let json = dir.join("scores.json");
+    let book = ScoreBook { history: vec![rec("basic_common", "a", 1.0), rec("basic_common", "b", 2.0)], ..Default::default() };
+    std::fs::write(&json, serde_json::to_string(&book).unwrap()).unwrap();
+    let mut s = SqliteStore::open(&dir.join("scores.db")).unwrap();
+    assert_eq!(s.migrate_from_json(&json).unwrap(), 2);
+    assert_eq!(s.migrate_from_json(&json).unwrap(), 0);
+    drop(s);
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,156 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use irohatype::engine::game::{Game, GameConfig, WordEntry};
+use irohatype::engine::romaji::RomajiRules;
+use irohatype::engine::verify;
+use irohatype::store::json::ScoreRecord;
+
+fn rules() -> RomajiRules {
+    RomajiRules::from_yaml_file(std::path::Path::new("data/rules/romaji.yaml")).unwrap()
+}
+
+fn w(jp: &str, roma: &str) -> WordEntry { WordEntry { jp: jp.into(), romas: vec![roma.into()], ..Default::default() } }
+
+fn key(c: char) -> KeyEvent { KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE) }
+
+/// Play a short session for real (one wrong key on the first word).
+fn played() -> ScoreRecord {
+    let gc = GameConfig { max_words: 3, time_limit_sec: f64::INFINITY, ..Default::default() };
+    let mut g = Game::new_with_rules(gc, vec![w("あい", "ai"), w("いえ", "ie"), w("しか", "shika")], rules()).unwrap();
+    g.start();
+    g.begin_now();
+    let words = g.words.clone();
+    for (i, word) in words.iter().enumerate() {
+        if i == 0 { g.handle_key(key('q')).unwrap(); }
+        for c in word.romas[0].chars() { std::thread::sleep(std::time::Duration::from_millis(3)); g.handle_key(key(c)).unwrap(); }
+    }
+    g.finish_record()
+}
+
+#[test]
+fn a_played_session_verifies() {
+    let rec = played();
+    assert_eq!(rec.hash_ok(), Some(true));
+    let rep = verify::verify(&rec, &rules());
+    assert!(rep.verified(), "{:?}", rep.issues);
+    assert_eq!(rep.rules_match, Some(true));
+    assert_eq!(rep.replayed.as_ref().unwrap().miss, 1);
+}
+
+#[test]
+fn verification_survives_the_compact_store_format() {
+    let rec = played();
+    let back: ScoreRecord = serde_json::from_str(&serde_json::to_string(&rec).unwrap()).unwrap();
+    assert!(verify::verify(&back, &rules()).verified());
+}
+
+#[test]
+fn hand_edits_are_detected() {
+    let mut rec = played();
+    rec.time_sec -= 0.5;
+    let rep = verify::verify(&rec, &rules());
+    assert_eq!(rep.hash_ok, Some(false));
+    assert!(rep.issues.iter().any(|i| i.contains("タイム")));
+
+    // re-sealing does not help: the replay disagrees
+    rec.seal();
+    let rep = verify::verify(&rec, &rules());
+    assert_eq!(rep.hash_ok, Some(true));
+    assert!(!rep.ok());
+
+    let mut rec = played();
+    rec.miss = 0;
+    rec.splits[0].miss = 0;
+    rec.seal();
+    assert!(verify::verify(&rec, &rules()).issues.iter().any(|i| i.contains("ミス")));
+
+    // a forged keystroke
+    let mut rec = played();
+    rec.replay.as_mut().unwrap()[0].ok = true;
+    rec.seal();
+    assert!(!verify::verify(&rec, &rules()).ok());
+}
+
+#[test]
+fn edited_rhythm_is_detected_and_not_ranked() {
+    let rec = played();
+    assert!(rec.rhythm.is_some());
+    let mut forged = rec.clone();
+    forged.rhythm.as_mut().unwrap().consistency = 100.0;
+    // the rhythm is not sealed: the hash still holds, the replay does not
+    assert_eq!(forged.hash_ok(), Some(true));
+    let rep = verify::verify(&forged, &rules());
+    assert!(rep.issues.iter().any(|i| i.contains("安定度")), "{:?}", rep.issues);
+    let top = irohatype::store::top_by_consistency(vec![forged], &rec.mode, 1);
+    assert_eq!(top[0].consistency(), rec.consistency());
+}
+
+#[test]
+fn memo_and_pin_do_not_break_the_hash() {
+    let mut rec = played();
+    rec.memo = Some("好調".into());
+    rec.pinned = true;
+    rec.id = "other".into();
+    assert_eq!(rec.hash_ok(), Some(true));
+}
+
+#[test]
+fn records_without_hash_or_replay_are_unverified_not_failed() {
+    let rec = ScoreRecord { mode: "basic_common".into(), datetime: "2026-10-01T10:00:00".into(), time_sec: 10.0, ..Default::default() };
+    let rep = verify::verify(&rec, &rules());
+    assert!(rep.ok() && !rep.verified());
+    assert_eq!(rep.hash_ok, None);
+}
+
+#[test]
+fn other_rules_are_reported() {
+    let rec = played();
+    let other = RomajiRules::from_yaml_str("yure:\n  し: [\"si\"]\n").unwrap();
+    assert_eq!(verify::verify(&rec, &other).rules_match, Some(false));
+}
+
+#[test]
+fn tampered_records_are_not_imported() {
+    let mut book = irohatype::store::json::ScoreBook::default();
+    let good = played();
+    let mut bad = played();
+    bad.datetime = "2026-01-01T00:00:00+09:00".into();
+    bad.time_sec = 1.0;
+    let rep = irohatype::store::merge::merge_into(&mut book, vec![good, bad.clone()]).unwrap();
+    assert_eq!(rep.added, 1);
+    assert_eq!(rep.rejected, vec![bad.id]);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn verification_survives_the_sqlite_store() {
+    use irohatype::store::ScoreStore;
+    let mut s = irohatype::store::sqlite::SqliteStore::open_in_memory().unwrap();
+    let rec = played();
+    s.insert_and_rank(&rec).unwrap();
+    let back = s.get(&rec.id).unwrap().unwrap();
+    assert!(verify::verify(&back, &rules()).verified());
+}
+
+#[test]
+fn hashes_survive_a_scorebook_round_trip() {
+    use irohatype::store::json::{ScoreBook, SplitRec};
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+    let mut book = ScoreBook::default();
+    for i in 0..2000 {
+        // measured like the game does (Duration::as_secs_f64), so nothing is round
+        let sec = |rng: &mut rand::rngs::ThreadRng| std::time::Duration::from_nanos(rng.gen_range(100_000_000..5_000_000_000)).as_secs_f64();
+        let splits: Vec<SplitRec> = (0..5).map(|_| SplitRec { word: "あい".into(), sec: sec(&mut rng), miss: 0 }).collect();
+        let mut rec = ScoreRecord {
+            mode: "basic_common".into(), datetime: format!("2026-10-01T10:{:02}:{:02}.{:03}+09:00", i / 600, i / 10 % 60, i),
+            time_sec: splits.iter().map(|s| s.sec).sum(), timeloss_sec: sec(&mut rng) / 7.0, wpm_top: 60.0 / sec(&mut rng), wpm_worst: 60.0 / sec(&mut rng),
+            rank: "A".into(), splits, id: format!("r{}", i), ..Default::default()
+        };
+        rec.seal();
+        book.history.push(rec);
+    }
+    let back = ScoreBook::from_json(&serde_json::to_string(&book).unwrap()).unwrap();
+    assert_eq!(back.history.len(), 2000);
+    let bad = back.history.iter().filter(|r| r.hash_ok() != Some(true)).count();
+    assert_eq!(bad, 0, "{} of 2000 records fail their hash after a round trip", bad);
+}